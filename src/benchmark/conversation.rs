@@ -0,0 +1,117 @@
+//! Scripted conversations for multi-turn benchmarks.
+//!
+//! A conversation file is a JSON array of OpenAI-style messages:
+//!
+//! ```json
+//! [
+//!   {"role": "system", "content": "You are a travel agent."},
+//!   {"role": "user", "content": "Plan three days in Lisbon."},
+//!   {"role": "assistant", "content": "Day 1: Alfama and the castle..."},
+//!   {"role": "user", "content": "Now make it vegetarian-friendly."}
+//! ]
+//! ```
+//!
+//! Each `user` message is one turn, sent with every message before it as
+//! history. Assistant replies are replayed from the file rather than taken
+//! from the model, so every provider sees the same context at each turn.
+
+use crate::providers::ChatMessage;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+/// Roles a conversation message may have
+const ROLES: [&str; 3] = ["system", "user", "assistant"];
+
+/// Errors that can occur while loading a conversation
+#[derive(Debug, Error)]
+pub enum ConversationError {
+    #[error("Failed to read conversation {path}: {source}")]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+
+    #[error("Invalid conversation {path}: {source}")]
+    Parse {
+        path: PathBuf,
+        source: serde_json::Error,
+    },
+
+    #[error("Unknown role {role:?} in conversation {path} (expected system, user, or assistant)")]
+    Role { path: PathBuf, role: String },
+
+    #[error("Conversation {0} has no user messages")]
+    NoTurns(PathBuf),
+}
+
+/// Load a conversation from a JSON file of messages
+pub fn load_conversation(path: &Path) -> Result<Vec<ChatMessage>, ConversationError> {
+    let contents = std::fs::read_to_string(path).map_err(|source| ConversationError::Io {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    let messages: Vec<ChatMessage> =
+        serde_json::from_str(&contents).map_err(|source| ConversationError::Parse {
+            path: path.to_path_buf(),
+            source,
+        })?;
+
+    if let Some(message) = messages.iter().find(|m| !ROLES.contains(&m.role.as_str())) {
+        return Err(ConversationError::Role {
+            path: path.to_path_buf(),
+            role: message.role.clone(),
+        });
+    }
+    if turn_count(&messages) == 0 {
+        return Err(ConversationError::NoTurns(path.to_path_buf()));
+    }
+    Ok(messages)
+}
+
+/// Number of turns (user messages) in a conversation
+pub fn turn_count(messages: &[ChatMessage]) -> usize {
+    messages.iter().filter(|m| m.role == "user").count()
+}
+
+/// History and prompt for a 1-based turn, or `None` past the last turn
+pub(crate) fn split_turn(messages: &[ChatMessage], turn: u32) -> Option<(&[ChatMessage], &str)> {
+    let index = messages
+        .iter()
+        .enumerate()
+        .filter(|(_, m)| m.role == "user")
+        .nth(turn.checked_sub(1)? as usize)?
+        .0;
+    Some((&messages[..index], &messages[index].content))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(role: &str, content: &str) -> ChatMessage {
+        ChatMessage {
+            role: role.to_string(),
+            content: content.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_turns_carry_earlier_messages() {
+        let messages = [
+            message("system", "Be brief."),
+            message("user", "Hi"),
+            message("assistant", "Hello!"),
+            message("user", "Name a color"),
+        ];
+        assert_eq!(turn_count(&messages), 2);
+
+        let (history, prompt) = split_turn(&messages, 1).unwrap();
+        assert_eq!((history.len(), prompt), (1, "Hi"));
+        let (history, prompt) = split_turn(&messages, 2).unwrap();
+        assert_eq!(history, &messages[..3]);
+        assert_eq!(prompt, "Name a color");
+
+        assert!(split_turn(&messages, 0).is_none());
+        assert!(split_turn(&messages, 3).is_none());
+    }
+}
@@ -0,0 +1,110 @@
+//! Prompt corpora for averaging benchmarks over many prompts.
+//!
+//! A corpus is either a directory, where each file holds one prompt, or a
+//! JSONL file whose lines are JSON strings or objects with a `prompt` field.
+
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+/// Errors that can occur while loading a prompt corpus
+#[derive(Debug, Error)]
+pub enum CorpusError {
+    #[error("Failed to read prompt corpus {path}: {source}")]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+
+    #[error("Invalid prompt on line {line} of {path}: {source}")]
+    Parse {
+        path: PathBuf,
+        line: usize,
+        source: serde_json::Error,
+    },
+
+    #[error("Prompt corpus {0} contains no prompts")]
+    Empty(PathBuf),
+}
+
+/// One JSONL corpus line
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum CorpusLine {
+    Text(String),
+    Object { prompt: String },
+}
+
+/// Load prompts from a directory of files or a JSONL file
+pub fn load_corpus(path: &Path) -> Result<Vec<String>, CorpusError> {
+    let io_err = |source| CorpusError::Io {
+        path: path.to_path_buf(),
+        source,
+    };
+
+    let prompts = if path.is_dir() {
+        let mut files: Vec<PathBuf> = std::fs::read_dir(path)
+            .map_err(io_err)?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|p| p.is_file())
+            .collect();
+        // Sorted so iterations map to the same prompts on every run
+        files.sort();
+
+        let mut prompts = Vec::new();
+        for file in files {
+            let text = std::fs::read_to_string(&file).map_err(|source| CorpusError::Io {
+                path: file.clone(),
+                source,
+            })?;
+            let text = text.trim();
+            if !text.is_empty() {
+                prompts.push(text.to_string());
+            }
+        }
+        prompts
+    } else {
+        let contents = std::fs::read_to_string(path).map_err(io_err)?;
+        parse_jsonl(&contents).map_err(|(line, source)| CorpusError::Parse {
+            path: path.to_path_buf(),
+            line,
+            source,
+        })?
+    };
+
+    if prompts.is_empty() {
+        return Err(CorpusError::Empty(path.to_path_buf()));
+    }
+    Ok(prompts)
+}
+
+/// Parse JSONL prompts, returning the 1-based line number on failure
+fn parse_jsonl(contents: &str) -> Result<Vec<String>, (usize, serde_json::Error)> {
+    contents
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            serde_json::from_str(line)
+                .map(|parsed| match parsed {
+                    CorpusLine::Text(prompt) | CorpusLine::Object { prompt } => prompt,
+                })
+                .map_err(|e| (i + 1, e))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_jsonl_strings_and_objects() {
+        let prompts =
+            parse_jsonl("\"Explain TCP\"\n\n{\"prompt\": \"Write a haiku\", \"id\": 7}\n").unwrap();
+        assert_eq!(prompts, vec!["Explain TCP", "Write a haiku"]);
+
+        let (line, _) = parse_jsonl("\"ok\"\n{\"text\": 1}\n").unwrap_err();
+        assert_eq!(line, 2);
+    }
+}
@@ -0,0 +1,224 @@
+//! Rate-limit discovery for `--find-limit`.
+//!
+//! Concurrency doubles from 1 up to a ceiling, each level sending
+//! `iterations` requests per slot, until a level draws a 429. The last level
+//! without one is the highest throughput the provider sustains.
+
+use super::runner::{BenchmarkConfig, run_iteration};
+use crate::providers::{InferenceProvider, ProviderError, redact};
+use futures::stream::{self, StreamExt};
+use serde::Serialize;
+use tokio::time::Instant;
+
+/// Throughput at one concurrency level
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LimitStep {
+    pub concurrency: u32,
+    /// Requests sent at this level
+    pub requests: u32,
+    pub succeeded: u32,
+    /// Requests rejected with HTTP 429
+    pub rate_limited: u32,
+    /// Requests that failed for any other reason
+    pub failed: u32,
+    /// Wall-clock time until every request at this level finished
+    pub elapsed_ms: u64,
+    /// Successful requests per second
+    pub requests_per_sec: f64,
+    /// Output tokens per second across all requests in flight
+    pub tokens_per_sec: f64,
+    pub cost_usd: f64,
+}
+
+/// Outcome of ramping one provider's concurrency
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LimitResult {
+    pub provider: String,
+    pub display_name: String,
+    pub model: String,
+    pub steps: Vec<LimitStep>,
+    /// Concurrency of the first level to draw a 429, if any did
+    pub breaking_concurrency: Option<u32>,
+    /// Why the ramp stopped before reaching a 429 or the ceiling
+    pub stopped: Option<String>,
+}
+
+impl LimitResult {
+    /// Highest level that finished without a 429 and with a success
+    pub fn sustained(&self) -> Option<&LimitStep> {
+        self.steps
+            .iter()
+            .rev()
+            .find(|s| s.rate_limited == 0 && s.succeeded > 0)
+    }
+}
+
+/// Concurrency levels to try: doubling from 1, ending at `max`
+pub fn limit_steps(max: u32) -> Vec<u32> {
+    let mut steps = Vec::new();
+    let mut concurrency = 1;
+    while concurrency < max {
+        steps.push(concurrency);
+        concurrency = concurrency.saturating_mul(2);
+    }
+    steps.push(max.max(1));
+    steps
+}
+
+/// Ramp a provider's concurrency through [`limit_steps`] until a level is
+/// rate limited
+///
+/// Levels run one after another, with `config.iterations` requests per slot.
+/// The ramp also stops after a level with no successes (keeping its last
+/// error), and before starting a level once `config`'s duration or cost
+/// budget is spent.
+pub async fn find_limit(
+    provider: &dyn InferenceProvider,
+    config: &BenchmarkConfig,
+    max_concurrency: u32,
+) -> LimitResult {
+    let available = provider.is_available().await;
+    let model = config
+        .model_for(provider)
+        .unwrap_or(provider.default_model())
+        .to_string();
+    let mut result = LimitResult {
+        provider: provider.name().to_string(),
+        display_name: provider.display_name().to_string(),
+        model,
+        steps: Vec::new(),
+        breaking_concurrency: None,
+        stopped: None,
+    };
+    if !available {
+        result.stopped = Some("Provider not available".to_string());
+        return result;
+    }
+
+    let prices = config.pricing_for(provider, &result.model);
+    let deadline = config.deadline();
+    let mut spent_usd = 0.0;
+    let mut next_iteration = 0;
+    for concurrency in limit_steps(max_concurrency) {
+        if deadline.is_some_and(|d| Instant::now() >= d) {
+            result.stopped = Some("Max duration reached".to_string());
+            break;
+        }
+        if config
+            .max_cost_per_provider_usd
+            .is_some_and(|budget| spent_usd >= budget)
+        {
+            result.stopped = Some("Cost cap reached".to_string());
+            break;
+        }
+
+        let requests = concurrency.saturating_mul(config.iterations.max(1));
+        let first = next_iteration;
+        next_iteration += requests;
+        let started = Instant::now();
+        let outcomes: Vec<_> = stream::iter(first..first + requests)
+            .map(|i| run_iteration(provider, config, i, prices, None))
+            .buffer_unordered(concurrency as usize)
+            .collect()
+            .await;
+        let elapsed = started.elapsed();
+
+        let mut step = LimitStep {
+            concurrency,
+            requests,
+            succeeded: 0,
+            rate_limited: 0,
+            failed: 0,
+            elapsed_ms: elapsed.as_millis() as u64,
+            requests_per_sec: 0.0,
+            tokens_per_sec: 0.0,
+            cost_usd: 0.0,
+        };
+        let mut tokens = 0;
+        let mut last_error = None;
+        for outcome in outcomes {
+            match outcome {
+                Ok(run) => {
+                    step.succeeded += 1;
+                    tokens += (run.output_tokens + run.reasoning_tokens) as u64;
+                    step.cost_usd += run.cost_usd;
+                }
+                Err(ProviderError::RateLimited) => step.rate_limited += 1,
+                Err(e) => {
+                    step.failed += 1;
+                    last_error = Some(e);
+                }
+            }
+        }
+        let secs = elapsed.as_secs_f64();
+        if secs > 0.0 {
+            step.requests_per_sec = step.succeeded as f64 / secs;
+            step.tokens_per_sec = tokens as f64 / secs;
+        }
+        spent_usd += step.cost_usd;
+        tracing::info!(
+            provider = provider.name(),
+            concurrency,
+            succeeded = step.succeeded,
+            rate_limited = step.rate_limited,
+            failed = step.failed,
+            tokens_per_sec = format!("{:.1}", step.tokens_per_sec),
+            "concurrency level complete"
+        );
+
+        let (rate_limited, succeeded) = (step.rate_limited, step.succeeded);
+        result.steps.push(step);
+        if rate_limited > 0 {
+            result.breaking_concurrency = Some(concurrency);
+            break;
+        }
+        if succeeded == 0 {
+            // Error bodies can echo the request, key included
+            result.stopped = last_error.map(|e| redact(&e.to_string()));
+            break;
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::mock_server::{MockResponse, MockServer, openai_provider};
+
+    #[test]
+    fn test_limit_steps_double_up_to_max() {
+        assert_eq!(limit_steps(1), vec![1]);
+        assert_eq!(limit_steps(8), vec![1, 2, 4, 8]);
+        assert_eq!(limit_steps(12), vec![1, 2, 4, 8, 12]);
+    }
+
+    #[tokio::test]
+    async fn test_find_limit_stops_at_first_rate_limited_level() {
+        let ok = || {
+            MockResponse::sse(&[
+                r#"{"choices":[{"delta":{"content":"Hello there"}}]}"#,
+                r#"{"choices":[],"usage":{"prompt_tokens":10,"completion_tokens":20}}"#,
+                "[DONE]",
+            ])
+        };
+        let limited = MockResponse::status(429, r#"{"error":"rate limit exceeded"}"#);
+        let probe = MockResponse::probe();
+        // Levels 1 and 2 succeed; one of level 4's requests is limited
+        let server =
+            MockServer::start(vec![probe, ok(), ok(), ok(), ok(), limited, ok(), ok()]).await;
+        let provider = openai_provider(&server);
+
+        let result = find_limit(&provider, &BenchmarkConfig::default(), 8).await;
+        let levels: Vec<u32> = result.steps.iter().map(|s| s.concurrency).collect();
+        assert_eq!(levels, vec![1, 2, 4]);
+        assert_eq!(result.breaking_concurrency, Some(4));
+        assert_eq!(result.stopped, None);
+        assert_eq!(result.steps[2].rate_limited, 1);
+        assert_eq!(result.steps[2].succeeded, 3);
+
+        let sustained = result.sustained().unwrap();
+        assert_eq!((sustained.concurrency, sustained.succeeded), (2, 2));
+        assert!(sustained.tokens_per_sec > 0.0);
+    }
+}
@@ -2,16 +2,49 @@
 
 use super::SingleRunResult;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 /// Aggregated metrics from multiple benchmark runs
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AggregatedMetrics {
     /// Average time to send prompt (ms)
     pub avg_time_to_prompt_ms: f64,
     /// Average time to first token (ms)
     pub avg_ttft_ms: f64,
+    /// 50th percentile time to first token (ms)
+    pub p50_ttft_ms: f64,
+    /// 95th percentile time to first token (ms)
+    pub p95_ttft_ms: f64,
+    /// 99th percentile time to first token (ms)
+    pub p99_ttft_ms: f64,
+    /// Average provider-reported queue wait, over the runs that report one
+    /// (ms); part of the TTFT
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub avg_queue_time_ms: Option<f64>,
     /// Average tokens per second
     pub avg_tokens_per_sec: f64,
+    /// Lower bound of the 95% confidence interval on `avg_tokens_per_sec`
+    /// (t-distribution; None with fewer than two runs)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tokens_per_sec_ci_low: Option<f64>,
+    /// Upper bound of the 95% confidence interval on `avg_tokens_per_sec`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tokens_per_sec_ci_high: Option<f64>,
+    /// `avg_tokens_per_sec` scaled by `success_rate`, so providers that fail
+    /// often rank below steadier ones
+    #[serde(default)]
+    pub effective_tokens_per_sec: f64,
+    /// Average output words per second (tokenizer-independent)
+    #[serde(default)]
+    pub avg_words_per_sec: f64,
+    /// Average output characters per second (tokenizer-independent)
+    #[serde(default)]
+    pub avg_chars_per_sec: f64,
+    /// Average reasoning tokens per run (reasoning models only; included in
+    /// `avg_tokens_per_sec` and cost)
+    #[serde(default)]
+    pub avg_reasoning_tokens: f64,
     /// Average total latency (ms)
     pub avg_latency_ms: f64,
     /// 50th percentile latency (ms)
@@ -20,63 +53,279 @@ pub struct AggregatedMetrics {
     pub p95_latency_ms: f64,
     /// Total cost for all runs (USD)
     pub total_cost_usd: f64,
+    /// Average cost of one successful request (USD)
+    #[serde(default)]
+    pub cost_per_request_usd: f64,
+    /// Output tokens (excluding reasoning) bought per dollar; None when the
+    /// runs cost nothing
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_tokens_per_dollar: Option<f64>,
     /// One-time model load time if applicable (ms)
     pub model_load_time_ms: Option<u64>,
     /// Number of successful runs
     pub run_count: usize,
+    /// Iterations that completed, successfully or not
+    #[serde(default)]
+    pub attempted_count: usize,
+    /// Fraction of attempted iterations that succeeded (0.0 - 1.0)
+    #[serde(default)]
+    pub success_rate: f64,
+    /// Runs excluded from averages as latency outliers (still in raw results)
+    #[serde(default)]
+    pub trimmed_count: usize,
+    /// Runs that stopped at `max_tokens`, so tok/s reflects a cut-short answer
+    #[serde(default)]
+    pub truncated_count: usize,
+    /// Runs timed by provider-reported processing times rather than the
+    /// client's clock (see `SingleRunResult::server_timing`)
+    #[serde(default)]
+    pub server_timed_count: usize,
+    /// Number of streamed network reads carrying N estimated tokens, keyed by
+    /// N (token-by-token streaming shows up as small keys; buffering as large
+    /// ones)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub chunk_size_histogram: BTreeMap<u32, usize>,
+    /// Average request body size (bytes)
+    #[serde(default)]
+    pub avg_request_bytes: f64,
+    /// Average response body size, SSE framing included (bytes)
+    #[serde(default)]
+    pub avg_response_bytes: f64,
+    /// Embedding vector length, for embeddings runs (TTFT fields then hold
+    /// server time after upload rather than time to a first token)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub embedding_dimensions: Option<u32>,
 }
 
 impl AggregatedMetrics {
     /// Calculate aggregated metrics from raw run results
     pub fn from_raw(results: &[SingleRunResult]) -> Self {
-        if results.is_empty() {
+        Self::from_raw_trimmed(results, None)
+    }
+
+    /// Calculate aggregated metrics, optionally discarding runs whose latency is
+    /// more than `trim_stddevs` standard deviations from the mean
+    pub fn from_raw_trimmed(all_results: &[SingleRunResult], trim_stddevs: Option<f64>) -> Self {
+        if all_results.is_empty() {
             return Self::empty();
         }
 
+        let kept = match trim_stddevs {
+            Some(n) => trim_outliers(all_results, n),
+            None => all_results.to_vec(),
+        };
+        let results = kept.as_slice();
+
         let time_to_prompts: Vec<f64> =
             results.iter().map(|r| r.time_to_prompt_ms as f64).collect();
-        let ttfts: Vec<f64> = results
+        let mut ttfts: Vec<f64> = results
             .iter()
             .map(|r| r.time_to_first_token_ms as f64)
             .collect();
         let mut latencies: Vec<f64> = results.iter().map(|r| r.total_latency_ms as f64).collect();
         let throughputs: Vec<f64> = results.iter().map(|r| r.tokens_per_sec()).collect();
+        let words_per_sec: Vec<f64> = results.iter().map(|r| r.words_per_sec()).collect();
+        let chars_per_sec: Vec<f64> = results.iter().map(|r| r.chars_per_sec()).collect();
+        let reasoning_tokens: Vec<f64> =
+            results.iter().map(|r| r.reasoning_tokens as f64).collect();
+        let queue_times: Vec<f64> = results
+            .iter()
+            .filter_map(|r| r.queue_time_ms)
+            .map(|ms| ms as f64)
+            .collect();
+        let request_bytes: Vec<f64> = all_results.iter().map(|r| r.request_bytes as f64).collect();
+        let response_bytes: Vec<f64> = all_results
+            .iter()
+            .map(|r| r.response_bytes as f64)
+            .collect();
 
-        // Sort latencies for percentile calculation
+        // Sort latencies and TTFTs for percentile calculation
         latencies.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+        ttfts.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
 
         // Get model load time from first run (it's one-time)
-        let model_load_time_ms = results.first().and_then(|r| r.model_load_time_ms);
+        let model_load_time_ms = all_results.first().and_then(|r| r.model_load_time_ms);
+
+        let ci = confidence_interval_95(&throughputs);
+        let total_cost_usd: f64 = all_results.iter().map(|r| r.cost_usd).sum();
+        let total_output_tokens: u64 = all_results.iter().map(|r| r.output_tokens as u64).sum();
 
         Self {
             avg_time_to_prompt_ms: mean(&time_to_prompts),
             avg_ttft_ms: mean(&ttfts),
+            p50_ttft_ms: percentile(&ttfts, 50.0),
+            p95_ttft_ms: percentile(&ttfts, 95.0),
+            p99_ttft_ms: percentile(&ttfts, 99.0),
+            avg_queue_time_ms: (!queue_times.is_empty()).then(|| mean(&queue_times)),
             avg_tokens_per_sec: mean(&throughputs),
+            tokens_per_sec_ci_low: ci.map(|(low, _)| low),
+            tokens_per_sec_ci_high: ci.map(|(_, high)| high),
+            effective_tokens_per_sec: mean(&throughputs),
+            avg_words_per_sec: mean(&words_per_sec),
+            avg_chars_per_sec: mean(&chars_per_sec),
+            avg_reasoning_tokens: mean(&reasoning_tokens),
             avg_latency_ms: mean(&latencies),
             p50_latency_ms: percentile(&latencies, 50.0),
             p95_latency_ms: percentile(&latencies, 95.0),
-            total_cost_usd: results.iter().map(|r| r.cost_usd).sum(),
+            total_cost_usd,
+            cost_per_request_usd: total_cost_usd / all_results.len() as f64,
+            output_tokens_per_dollar: (total_cost_usd > 0.0)
+                .then(|| total_output_tokens as f64 / total_cost_usd),
             model_load_time_ms,
-            run_count: results.len(),
+            run_count: all_results.len(),
+            attempted_count: all_results.len(),
+            success_rate: 1.0,
+            trimmed_count: all_results.len() - results.len(),
+            truncated_count: all_results.iter().filter(|r| r.hit_max_tokens).count(),
+            server_timed_count: all_results
+                .iter()
+                .filter(|r| r.server_timing.is_some())
+                .count(),
+            chunk_size_histogram: chunk_size_histogram(all_results),
+            avg_request_bytes: mean(&request_bytes),
+            avg_response_bytes: mean(&response_bytes),
+            embedding_dimensions: all_results.iter().find_map(|r| r.embedding_dimensions),
         }
     }
 
+    /// Half-width of the 95% confidence interval on throughput, if known
+    pub fn tokens_per_sec_margin(&self) -> Option<f64> {
+        Some((self.tokens_per_sec_ci_high? - self.tokens_per_sec_ci_low?) / 2.0)
+    }
+
+    /// Record how many iterations were attempted, failures included
+    pub fn with_attempts(mut self, attempted: usize) -> Self {
+        self.attempted_count = attempted.max(self.run_count);
+        self.success_rate = if self.attempted_count == 0 {
+            0.0
+        } else {
+            self.run_count as f64 / self.attempted_count as f64
+        };
+        self.effective_tokens_per_sec = self.avg_tokens_per_sec * self.success_rate;
+        self
+    }
+
     /// Create empty metrics (no successful runs)
     fn empty() -> Self {
         Self {
             avg_time_to_prompt_ms: 0.0,
             avg_ttft_ms: 0.0,
+            p50_ttft_ms: 0.0,
+            p95_ttft_ms: 0.0,
+            p99_ttft_ms: 0.0,
+            avg_queue_time_ms: None,
             avg_tokens_per_sec: 0.0,
+            tokens_per_sec_ci_low: None,
+            tokens_per_sec_ci_high: None,
+            effective_tokens_per_sec: 0.0,
+            avg_words_per_sec: 0.0,
+            avg_chars_per_sec: 0.0,
+            avg_reasoning_tokens: 0.0,
             avg_latency_ms: 0.0,
             p50_latency_ms: 0.0,
             p95_latency_ms: 0.0,
             total_cost_usd: 0.0,
+            cost_per_request_usd: 0.0,
+            output_tokens_per_dollar: None,
             model_load_time_ms: None,
             run_count: 0,
+            attempted_count: 0,
+            success_rate: 0.0,
+            trimmed_count: 0,
+            truncated_count: 0,
+            server_timed_count: 0,
+            chunk_size_histogram: BTreeMap::new(),
+            avg_request_bytes: 0.0,
+            avg_response_bytes: 0.0,
+            embedding_dimensions: None,
+        }
+    }
+}
+
+/// Metrics for the first (cold) request and the remaining (warm) ones,
+/// reported separately
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ColdWarmMetrics {
+    pub cold: AggregatedMetrics,
+    pub warm: AggregatedMetrics,
+}
+
+impl ColdWarmMetrics {
+    /// Split runs on `SingleRunResult::cold`; None if no run is marked cold
+    pub fn from_raw(results: &[SingleRunResult], trim_stddevs: Option<f64>) -> Option<Self> {
+        let (cold, warm): (Vec<_>, Vec<_>) = results.iter().cloned().partition(|r| r.cold);
+        if cold.is_empty() {
+            return None;
+        }
+        Some(Self {
+            cold: AggregatedMetrics::from_raw(&cold),
+            warm: AggregatedMetrics::from_raw_trimmed(&warm, trim_stddevs),
+        })
+    }
+}
+
+/// Run-to-run spread of throughput when the whole benchmark is repeated
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunSpread {
+    /// Average tokens per second from each repeated run
+    pub per_run_tokens_per_sec: Vec<f64>,
+    /// Mean of the per-run averages
+    pub mean_tokens_per_sec: f64,
+    /// Sample standard deviation of the per-run averages
+    pub std_dev_tokens_per_sec: f64,
+}
+
+impl RunSpread {
+    /// Summarize the per-run average throughputs
+    pub fn from_run_means(per_run_tokens_per_sec: Vec<f64>) -> Self {
+        Self {
+            mean_tokens_per_sec: mean(&per_run_tokens_per_sec),
+            std_dev_tokens_per_sec: std_dev(&per_run_tokens_per_sec),
+            per_run_tokens_per_sec,
         }
     }
 }
 
+/// Count streamed reads by how many estimated tokens each carried
+fn chunk_size_histogram(results: &[SingleRunResult]) -> BTreeMap<u32, usize> {
+    let mut histogram = BTreeMap::new();
+    for count in results.iter().flat_map(|r| &r.chunk_token_counts) {
+        *histogram.entry(*count).or_insert(0) += 1;
+    }
+    histogram
+}
+
+/// Drop runs whose total latency lies more than `n` standard deviations
+/// from the mean of the other runs
+///
+/// Each run is compared against the rest (leave-one-out), so a spike does not
+/// inflate the spread it is measured against; with all runs included, no run
+/// of five could ever lie two deviations out.
+fn trim_outliers(results: &[SingleRunResult], n: f64) -> Vec<SingleRunResult> {
+    // Too few samples to estimate the others' spread
+    if results.len() < 3 {
+        return results.to_vec();
+    }
+    let latencies: Vec<f64> = results.iter().map(|r| r.total_latency_ms as f64).collect();
+
+    results
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| {
+            let others: Vec<f64> = latencies
+                .iter()
+                .enumerate()
+                .filter(|(j, _)| j != i)
+                .map(|(_, latency)| *latency)
+                .collect();
+            (latencies[*i] - mean(&others)).abs() <= n * std_dev(&others)
+        })
+        .map(|(_, r)| r.clone())
+        .collect()
+}
+
 /// Calculate mean of a slice of f64 values
 fn mean(values: &[f64]) -> f64 {
     if values.is_empty() {
@@ -85,6 +334,51 @@ fn mean(values: &[f64]) -> f64 {
     values.iter().sum::<f64>() / values.len() as f64
 }
 
+/// Calculate sample standard deviation of a slice of f64 values
+fn std_dev(values: &[f64]) -> f64 {
+    if values.len() < 2 {
+        return 0.0;
+    }
+    let avg = mean(values);
+    let variance =
+        values.iter().map(|v| (v - avg).powi(2)).sum::<f64>() / (values.len() - 1) as f64;
+    variance.sqrt()
+}
+
+/// Sample standard deviation relative to the mean, if there are at least two
+/// values and a positive mean
+pub(crate) fn coefficient_of_variation(values: &[f64]) -> Option<f64> {
+    let avg = mean(values);
+    (values.len() >= 2 && avg > 0.0).then(|| std_dev(values) / avg)
+}
+
+/// 95% confidence interval on the mean, using Student's t for small samples
+fn confidence_interval_95(values: &[f64]) -> Option<(f64, f64)> {
+    if values.len() < 2 {
+        return None;
+    }
+    let avg = mean(values);
+    let margin = t_critical_95(values.len() - 1) * std_dev(values) / (values.len() as f64).sqrt();
+    Some((avg - margin, avg + margin))
+}
+
+/// Two-sided 95% critical value of Student's t for `df` degrees of freedom
+fn t_critical_95(df: usize) -> f64 {
+    const TABLE: [f64; 30] = [
+        12.706, 4.303, 3.182, 2.776, 2.571, 2.447, 2.365, 2.306, 2.262, 2.228, 2.201, 2.179, 2.160,
+        2.145, 2.131, 2.120, 2.110, 2.101, 2.093, 2.086, 2.080, 2.074, 2.069, 2.064, 2.060, 2.056,
+        2.052, 2.048, 2.045, 2.042,
+    ];
+    match df {
+        0 => f64::NAN,
+        1..=30 => TABLE[df - 1],
+        31..=40 => 2.021,
+        41..=60 => 2.000,
+        61..=120 => 1.980,
+        _ => 1.960,
+    }
+}
+
 /// Calculate percentile of a sorted slice of f64 values
 fn percentile(sorted_values: &[f64], pct: f64) -> f64 {
     if sorted_values.is_empty() {
@@ -132,4 +426,141 @@ mod tests {
         let sorted = vec![1.0, 2.0, 3.0, 4.0, 5.0];
         assert_eq!(percentile(&sorted, 50.0), 3.0);
     }
+
+    fn run_with_latency(total_latency_ms: u64) -> SingleRunResult {
+        SingleRunResult {
+            time_to_prompt_ms: 10,
+            output_tokens: 50,
+            cost_usd: 0.001,
+            chunk_token_counts: vec![1, 1, 3],
+            output_words: 40,
+            output_chars: 200,
+            ..SingleRunResult::test_run(20, total_latency_ms)
+        }
+    }
+
+    #[test]
+    fn test_throughput_confidence_interval() {
+        // 50 tokens over 400/500/600ms: 125, 100, 83.3 tok/s
+        let runs: Vec<_> = [400, 500, 600].into_iter().map(run_with_latency).collect();
+        let metrics = AggregatedMetrics::from_raw(&runs);
+        let low = metrics.tokens_per_sec_ci_low.unwrap();
+        let high = metrics.tokens_per_sec_ci_high.unwrap();
+        assert!(low < metrics.avg_tokens_per_sec && metrics.avg_tokens_per_sec < high);
+
+        // t(2) = 4.303 widens the interval well beyond the normal 1.96
+        let values = [125.0, 100.0, 50.0 / 0.6];
+        let expected = 4.303 * std_dev(&values) / 3f64.sqrt();
+        assert!((metrics.tokens_per_sec_margin().unwrap() - expected).abs() < 1e-9);
+
+        let single = AggregatedMetrics::from_raw(&runs[..1]);
+        assert_eq!(single.tokens_per_sec_margin(), None);
+    }
+
+    #[test]
+    fn test_cost_per_request_and_tokens_per_dollar() {
+        let runs: Vec<_> = (0..4).map(|_| run_with_latency(100)).collect();
+        let metrics = AggregatedMetrics::from_raw(&runs);
+        assert!((metrics.cost_per_request_usd - 0.001).abs() < 1e-12);
+        // 50 output tokens per $0.001
+        let per_dollar = metrics.output_tokens_per_dollar.unwrap();
+        assert!((per_dollar - 50_000.0).abs() < 1e-6);
+
+        let free: Vec<_> = runs
+            .into_iter()
+            .map(|r| SingleRunResult { cost_usd: 0.0, ..r })
+            .collect();
+        assert_eq!(
+            AggregatedMetrics::from_raw(&free).output_tokens_per_dollar,
+            None
+        );
+    }
+
+    #[test]
+    fn test_success_rate_counts_failed_attempts() {
+        let runs: Vec<_> = (0..7).map(|_| run_with_latency(100)).collect();
+        let metrics = AggregatedMetrics::from_raw(&runs).with_attempts(10);
+        assert_eq!(metrics.attempted_count, 10);
+        assert!((metrics.success_rate - 0.7).abs() < 1e-9);
+        // 7 of 10 attempts at the same speed
+        let expected = metrics.avg_tokens_per_sec * 0.7;
+        assert!((metrics.effective_tokens_per_sec - expected).abs() < 1e-9);
+
+        let none = AggregatedMetrics::from_raw(&[]).with_attempts(3);
+        assert_eq!(none.success_rate, 0.0);
+    }
+
+    #[test]
+    fn test_text_throughput_is_tokenizer_independent() {
+        let metrics = AggregatedMetrics::from_raw(&[run_with_latency(500), run_with_latency(1000)]);
+        // 40 words and 200 chars over 0.5s and 1s
+        assert_eq!(metrics.avg_words_per_sec, 60.0);
+        assert_eq!(metrics.avg_chars_per_sec, 300.0);
+        assert_eq!(metrics.avg_tokens_per_sec, 75.0);
+    }
+
+    #[test]
+    fn test_embedding_runs_measure_input_throughput() {
+        let mut run = run_with_latency(500);
+        run.output_tokens = 0;
+        run.chunk_token_counts.clear();
+        run.embedding_dimensions = Some(768);
+
+        let metrics = AggregatedMetrics::from_raw(&[run]);
+        assert_eq!(metrics.avg_tokens_per_sec, 20.0);
+        assert_eq!(metrics.embedding_dimensions, Some(768));
+    }
+
+    #[test]
+    fn test_chunk_size_histogram_counts_reads() {
+        let runs = vec![run_with_latency(100), run_with_latency(110)];
+        let metrics = AggregatedMetrics::from_raw(&runs);
+        assert_eq!(
+            metrics.chunk_size_histogram,
+            BTreeMap::from([(1, 4), (3, 2)])
+        );
+    }
+
+    #[test]
+    fn test_run_spread_mean_of_means() {
+        let spread = RunSpread::from_run_means(vec![100.0, 110.0, 120.0]);
+        assert_eq!(spread.mean_tokens_per_sec, 110.0);
+        assert_eq!(spread.std_dev_tokens_per_sec, 10.0);
+    }
+
+    #[test]
+    fn test_trim_outliers_drops_spike() {
+        let mut runs: Vec<_> = [1000, 1010, 990, 1005, 995, 1000, 1002, 998]
+            .into_iter()
+            .map(run_with_latency)
+            .collect();
+        runs.push(run_with_latency(6000));
+
+        let metrics = AggregatedMetrics::from_raw_trimmed(&runs, Some(2.0));
+        assert_eq!(metrics.trimmed_count, 1);
+        assert_eq!(metrics.run_count, 9);
+        assert!(metrics.avg_latency_ms < 1100.0);
+
+        let untrimmed = AggregatedMetrics::from_raw(&runs);
+        assert_eq!(untrimmed.trimmed_count, 0);
+        assert!(untrimmed.avg_latency_ms > 1500.0);
+    }
+
+    #[test]
+    fn test_trim_outliers_drops_spike_among_five_runs() {
+        // One GC pause in a five-iteration benchmark
+        let runs: Vec<_> = [1000, 1010, 990, 1005, 3000]
+            .into_iter()
+            .map(run_with_latency)
+            .collect();
+
+        let metrics = AggregatedMetrics::from_raw_trimmed(&runs, Some(2.0));
+        assert_eq!(metrics.trimmed_count, 1);
+        assert!(metrics.avg_latency_ms < 1010.0);
+
+        // Identical runs have no spread and nothing to trim
+        let steady: Vec<_> = [1000; 5].into_iter().map(run_with_latency).collect();
+        let metrics = AggregatedMetrics::from_raw_trimmed(&steady, Some(2.0));
+        assert_eq!(metrics.trimmed_count, 0);
+    }
 }
@@ -1,9 +1,28 @@
 //! Benchmark engine for running inference tests.
 
+mod conversation;
+mod corpus;
+mod limit;
 mod metrics;
+mod progress;
 mod prompts;
+mod regression;
 mod runner;
+mod score;
+mod template;
 
-pub use metrics::AggregatedMetrics;
-pub use prompts::{LONG_PROMPT, MEDIUM_PROMPT, SHORT_PROMPT, TestPrompt};
-pub use runner::{BenchmarkConfig, BenchmarkResult, BenchmarkRunner, SingleRunResult};
+pub use conversation::{ConversationError, load_conversation, turn_count};
+pub use corpus::{CorpusError, load_corpus};
+pub use limit::{LimitResult, LimitStep, find_limit, limit_steps};
+pub use metrics::{AggregatedMetrics, ColdWarmMetrics, RunSpread};
+pub use progress::{EtaTracker, ProgressCallback, ProgressEvent};
+pub use prompts::{
+    LONG_PROMPT, MEDIUM_PROMPT, SHORT_PROMPT, TOOL_PROMPT, TestPrompt, estimate_tokens,
+};
+pub use regression::{RegressedMetric, Regression, find_regressions};
+pub use runner::{
+    BenchmarkConfig, BenchmarkResult, BenchmarkRunner, CostEstimate, DEFAULT_MAX_ITERATIONS,
+    DEFAULT_MAX_TOKENS_BUFFER, ResultStatus, SingleRunResult, benchmark_single,
+};
+pub use score::{ScoreWeights, rank, score};
+pub use template::{TemplateError, render_template};
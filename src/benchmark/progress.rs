@@ -0,0 +1,194 @@
+//! Incremental progress events emitted while a benchmark runs.
+//!
+//! Register a callback with [`BenchmarkRunner::with_progress`] to observe
+//! iterations as they complete, e.g. to drive a live dashboard.
+//!
+//! [`EtaTracker`] turns the same events into percent complete and an
+//! estimate of the time remaining.
+//!
+//! [`BenchmarkRunner::with_progress`]: super::BenchmarkRunner::with_progress
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// Iteration intervals averaged for the estimate
+const ETA_WINDOW: usize = 10;
+
+/// Something that happened during a benchmark
+#[derive(Debug, Clone, PartialEq)]
+pub enum ProgressEvent {
+    /// A provider's timed iterations are about to start (once per run)
+    ProviderStarted { provider: String, iterations: u32 },
+    /// A timed iteration succeeded
+    IterationComplete {
+        provider: String,
+        iteration: u32,
+        ttft_ms: u64,
+        tokens_per_sec: f64,
+    },
+    /// A timed iteration failed
+    IterationFailed {
+        provider: String,
+        iteration: u32,
+        error: String,
+    },
+    /// A provider finished (once per run); `skipped` holds the reason if no
+    /// iteration ran at all (e.g. the provider was unavailable)
+    ProviderFinished {
+        provider: String,
+        skipped: Option<String>,
+    },
+}
+
+/// Callback invoked for each [`ProgressEvent`]
+pub type ProgressCallback<'a> = Box<dyn Fn(&ProgressEvent) + Send + Sync + 'a>;
+
+/// Percent complete and time remaining across a whole sweep
+///
+/// The estimate is the rolling average time between finished iterations
+/// times the iterations left. Iterations in flight together finish closer
+/// together, so concurrency is reflected without knowing its level.
+#[derive(Debug, Clone)]
+pub struct EtaTracker {
+    total: u32,
+    finished: u32,
+    /// Iterations the current provider was started with, and how many it ran
+    provider_planned: u32,
+    provider_finished: u32,
+    last: Option<Instant>,
+    intervals: VecDeque<Duration>,
+}
+
+impl EtaTracker {
+    /// Track a sweep of `total` iterations, e.g.
+    /// [`BenchmarkRunner::planned_iterations`](super::BenchmarkRunner::planned_iterations)
+    pub fn new(total: u32) -> Self {
+        Self {
+            total,
+            finished: 0,
+            provider_planned: 0,
+            provider_finished: 0,
+            last: None,
+            intervals: VecDeque::with_capacity(ETA_WINDOW),
+        }
+    }
+
+    /// Update from an event received now
+    pub fn record(&mut self, event: &ProgressEvent) {
+        self.record_at(event, Instant::now());
+    }
+
+    fn record_at(&mut self, event: &ProgressEvent, now: Instant) {
+        match event {
+            ProgressEvent::ProviderStarted { iterations, .. } => {
+                self.provider_planned = *iterations;
+                self.provider_finished = 0;
+                // Time between providers is not iteration time
+                self.last = Some(now);
+            }
+            ProgressEvent::IterationComplete { .. } | ProgressEvent::IterationFailed { .. } => {
+                self.finished += 1;
+                self.provider_finished += 1;
+                if let Some(last) = self.last {
+                    if self.intervals.len() == ETA_WINDOW {
+                        self.intervals.pop_front();
+                    }
+                    self.intervals.push_back(now - last);
+                }
+                self.last = Some(now);
+            }
+            ProgressEvent::ProviderFinished { .. } => {
+                // Iterations a provider stopped short of will never run
+                self.finished += self.provider_planned.saturating_sub(self.provider_finished);
+                self.provider_planned = 0;
+                self.provider_finished = 0;
+            }
+        }
+    }
+
+    /// Share of the planned iterations finished, from 0 to 100
+    pub fn percent(&self) -> f64 {
+        if self.total == 0 {
+            return 100.0;
+        }
+        (self.finished as f64 / self.total as f64 * 100.0).min(100.0)
+    }
+
+    /// Estimated time until the sweep finishes; `None` before the first
+    /// iteration has finished
+    pub fn remaining(&self) -> Option<Duration> {
+        if self.intervals.is_empty() {
+            return None;
+        }
+        let average = self.intervals.iter().sum::<Duration>() / self.intervals.len() as u32;
+        Some(average * self.total.saturating_sub(self.finished))
+    }
+}
+
+impl std::fmt::Display for EtaTracker {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:.0}% done", self.percent())?;
+        if let Some(remaining) = self.remaining() {
+            write!(f, ", ~{} remaining", format_remaining(remaining))?;
+        }
+        Ok(())
+    }
+}
+
+/// Coarse duration: seconds under a minute, then minutes, then hours
+fn format_remaining(duration: Duration) -> String {
+    let secs = duration.as_secs_f64().round() as u64;
+    match secs {
+        0..60 => format!("{}s", secs),
+        60..3600 => format!("{}m", (secs as f64 / 60.0).round() as u64),
+        _ => format!("{}h {}m", secs / 3600, secs % 3600 / 60),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_eta_from_rolling_iteration_time() {
+        let start = Instant::now();
+        let at = |secs: u64| start + Duration::from_secs(secs);
+        let started = |iterations| ProgressEvent::ProviderStarted {
+            provider: "groq".to_string(),
+            iterations,
+        };
+        let complete = ProgressEvent::IterationComplete {
+            provider: "groq".to_string(),
+            iteration: 0,
+            ttft_ms: 100,
+            tokens_per_sec: 500.0,
+        };
+        let finished = ProgressEvent::ProviderFinished {
+            provider: "groq".to_string(),
+            skipped: None,
+        };
+
+        // Providers of four and six iterations, the first stopping after two
+        let mut eta = EtaTracker::new(10);
+        assert_eq!(eta.to_string(), "0% done");
+        eta.record_at(&started(4), at(0));
+        eta.record_at(&complete, at(10));
+        eta.record_at(&complete, at(20));
+        assert_eq!(eta.percent(), 20.0);
+        assert_eq!(eta.remaining(), Some(Duration::from_secs(80)));
+        assert_eq!(eta.to_string(), "20% done, ~1m remaining");
+
+        eta.record_at(&finished, at(20));
+        assert_eq!(eta.percent(), 40.0);
+        // The gap before the next provider starts is not counted
+        eta.record_at(&started(6), at(100));
+        eta.record_at(&complete, at(104));
+        assert_eq!(eta.remaining(), Some(Duration::from_secs(5 * 8)));
+        assert_eq!(eta.to_string(), "50% done, ~40s remaining");
+
+        assert_eq!(
+            format_remaining(Duration::from_secs(3 * 3600 + 125)),
+            "3h 2m"
+        );
+    }
+}
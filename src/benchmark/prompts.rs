@@ -1,38 +1,43 @@
 //! Test prompts for benchmarking.
 
+use std::borrow::Cow;
+
 /// A standardized test prompt with expected token counts
 #[derive(Debug, Clone)]
 pub struct TestPrompt {
-    pub name: &'static str,
-    pub text: &'static str,
+    pub name: Cow<'static, str>,
+    pub text: Cow<'static, str>,
     pub expected_input_tokens: u32,
     pub expected_output_tokens: u32,
 }
 
 /// Short prompt (~50 output tokens) - minimal cost
 pub const SHORT_PROMPT: TestPrompt = TestPrompt {
-    name: "short",
-    text: "Explain what a binary search tree is in exactly three sentences.",
+    name: Cow::Borrowed("short"),
+    text: Cow::Borrowed("Explain what a binary search tree is in exactly three sentences."),
     expected_input_tokens: 15,
     expected_output_tokens: 50,
 };
 
 /// Medium prompt (~200 output tokens) - typical interaction
 pub const MEDIUM_PROMPT: TestPrompt = TestPrompt {
-    name: "medium",
-    text: r#"Write a Python function that implements merge sort. Include:
+    name: Cow::Borrowed("medium"),
+    text: Cow::Borrowed(
+        r#"Write a Python function that implements merge sort. Include:
 1. The main merge_sort function
 2. A helper merge function
 3. Brief comments explaining each step
 4. An example of calling the function with a sample list"#,
+    ),
     expected_input_tokens: 50,
     expected_output_tokens: 200,
 };
 
 /// Long prompt (~500 output tokens) - extended response
 pub const LONG_PROMPT: TestPrompt = TestPrompt {
-    name: "long",
-    text: r#"You are a technical writer. Write a comprehensive guide about REST API design best practices. The guide should cover:
+    name: Cow::Borrowed("long"),
+    text: Cow::Borrowed(
+        r#"You are a technical writer. Write a comprehensive guide about REST API design best practices. The guide should cover:
 
 1. Resource naming conventions
 2. HTTP method usage (GET, POST, PUT, PATCH, DELETE)
@@ -43,15 +48,102 @@ pub const LONG_PROMPT: TestPrompt = TestPrompt {
 7. Authentication considerations
 
 For each topic, provide a brief explanation and a concrete example. The guide should be suitable for intermediate developers who understand HTTP but are new to API design."#,
+    ),
     expected_input_tokens: 100,
     expected_output_tokens: 500,
 };
 
+/// Tool-calling prompt (~20 output tokens of call arguments) for `--tools`;
+/// the input estimate includes the function definition sent with it
+pub const TOOL_PROMPT: TestPrompt = TestPrompt {
+    name: Cow::Borrowed("tool"),
+    text: Cow::Borrowed(
+        "What's the weather in Paris right now? Look it up with the get_weather tool.",
+    ),
+    expected_input_tokens: 90,
+    expected_output_tokens: 20,
+};
+
 impl TestPrompt {
+    /// Build a prompt from user-supplied text, estimating its input tokens
+    ///
+    /// The expected output length defaults to that of the medium prompt.
+    pub fn custom(name: &str, text: String, expected_output_tokens: Option<u32>) -> Self {
+        Self {
+            name: Cow::Owned(name.to_string()),
+            expected_input_tokens: estimate_tokens(&text),
+            text: Cow::Owned(text),
+            expected_output_tokens: expected_output_tokens
+                .unwrap_or(MEDIUM_PROMPT.expected_output_tokens),
+        }
+    }
+
     /// Estimate cost for this prompt with given pricing (per million tokens)
     pub fn estimate_cost(&self, input_price: f64, output_price: f64) -> f64 {
+        self.estimate_cost_for_output(input_price, output_price, self.expected_output_tokens)
+    }
+
+    /// Estimate cost for this prompt when generating a specific number of output tokens
+    pub fn estimate_cost_for_output(
+        &self,
+        input_price: f64,
+        output_price: f64,
+        output_tokens: u32,
+    ) -> f64 {
         let input_cost = (self.expected_input_tokens as f64 / 1_000_000.0) * input_price;
-        let output_cost = (self.expected_output_tokens as f64 / 1_000_000.0) * output_price;
+        let output_cost = (output_tokens as f64 / 1_000_000.0) * output_price;
         input_cost + output_cost
     }
 }
+
+/// Rough token count for text when a provider reports no usage
+///
+/// Counts `char`s rather than bytes so multibyte text is not over-counted.
+/// CJK characters and emoji are counted as a token each, since BPE
+/// tokenizers rarely merge them; other text runs at about four characters
+/// per token.
+pub fn estimate_tokens(text: &str) -> u32 {
+    let (wide, narrow) = text.chars().fold((0usize, 0usize), |(wide, narrow), c| {
+        if is_wide_char(c) {
+            (wide + 1, narrow)
+        } else {
+            (wide, narrow + 1)
+        }
+    });
+    (wide + narrow.div_ceil(4)) as u32
+}
+
+/// CJK ideographs, kana, hangul, full-width forms and emoji
+fn is_wide_char(c: char) -> bool {
+    matches!(
+        c,
+        '\u{2E80}'..='\u{9FFF}'
+            | '\u{AC00}'..='\u{D7AF}'
+            | '\u{F900}'..='\u{FAFF}'
+            | '\u{FF00}'..='\u{FFEF}'
+            | '\u{1F000}'..='\u{1FAFF}'
+            | '\u{20000}'..='\u{3FFFF}'
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_estimate_tokens_counts_chars_not_bytes() {
+        assert_eq!(estimate_tokens(""), 0);
+        assert_eq!(estimate_tokens("Hello, world"), 3);
+
+        // 11 CJK characters are 33 UTF-8 bytes; expect about one token each
+        let chinese = "快速的推理基准测试工具";
+        assert_eq!(chinese.len(), 33);
+        assert_eq!(estimate_tokens(chinese), 11);
+
+        // Emoji are four bytes each but should not count as four tokens
+        assert_eq!(estimate_tokens("🚀🔥👍"), 3);
+
+        // Mixed text: three wide chars plus " is king " at ~4 chars/token
+        assert_eq!(estimate_tokens("速度 is king 👑"), 6);
+    }
+}
@@ -0,0 +1,202 @@
+//! Regression checks against a previously saved ("blessed") result set.
+
+use super::BenchmarkResult;
+use crate::cli::BenchmarkMode;
+
+/// Metric that got worse than the baseline allows
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RegressedMetric {
+    /// Average output tokens per second dropped
+    TokensPerSec,
+    /// Average time to first token grew
+    Ttft,
+    /// The provider succeeded in the baseline but not now
+    Failed,
+}
+
+/// One provider metric outside the tolerance
+#[derive(Debug, Clone, PartialEq)]
+pub struct Regression {
+    pub display_name: String,
+    pub metric: RegressedMetric,
+    /// Baseline value (tok/s for `Failed`)
+    pub baseline: f64,
+    /// Current value (0 for `Failed`)
+    pub current: f64,
+}
+
+impl Regression {
+    /// Change from the baseline as a percentage
+    pub fn change_pct(&self) -> f64 {
+        (self.current - self.baseline) / self.baseline * 100.0
+    }
+}
+
+impl std::fmt::Display for Regression {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.metric {
+            RegressedMetric::TokensPerSec => write!(
+                f,
+                "{}: {:.1} tok/s vs {:.1} baseline ({:+.1}%)",
+                self.display_name,
+                self.current,
+                self.baseline,
+                self.change_pct()
+            ),
+            RegressedMetric::Ttft => write!(
+                f,
+                "{}: {:.0}ms TTFT vs {:.0}ms baseline ({:+.1}%)",
+                self.display_name,
+                self.current,
+                self.baseline,
+                self.change_pct()
+            ),
+            RegressedMetric::Failed => write!(
+                f,
+                "{}: failed (baseline {:.1} tok/s)",
+                self.display_name, self.baseline
+            ),
+        }
+    }
+}
+
+/// Providers whose tok/s fell or TTFT rose by more than `tolerance` (a
+/// fraction, e.g. 0.1 for 10%) relative to `baseline`
+///
+/// Rows are matched by provider ID, model and requested output length (and by
+/// whether responses were streamed, for `--stream both` rows, and by
+/// conversation turn). Those missing from either side, or that already failed
+/// in the baseline, are not checked.
+pub fn find_regressions(
+    current: &[BenchmarkResult],
+    baseline: &[BenchmarkResult],
+    tolerance: f64,
+) -> Vec<Regression> {
+    let mut regressions = Vec::new();
+    for before in baseline.iter().filter(|r| r.is_success()) {
+        let Some(now) = current.iter().find(|r| {
+            r.provider == before.provider
+                && r.model == before.model
+                && r.requested_output_tokens == before.requested_output_tokens
+                && r.non_streaming == before.non_streaming
+                && r.turn == before.turn
+        }) else {
+            continue;
+        };
+        let regression = |metric, baseline, current| Regression {
+            display_name: now.display_name.clone(),
+            metric,
+            baseline,
+            current,
+        };
+        if !now.is_success() {
+            regressions.push(regression(
+                RegressedMetric::Failed,
+                before.metrics.avg_tokens_per_sec,
+                0.0,
+            ));
+            continue;
+        }
+
+        let (old_tps, new_tps) = (
+            before.metrics.avg_tokens_per_sec,
+            now.metrics.avg_tokens_per_sec,
+        );
+        if old_tps > 0.0 && new_tps < old_tps * (1.0 - tolerance) {
+            regressions.push(regression(RegressedMetric::TokensPerSec, old_tps, new_tps));
+        }
+        // Embeddings have no first token to compare
+        let (old_ttft, new_ttft) = (before.metrics.avg_ttft_ms, now.metrics.avg_ttft_ms);
+        if now.mode == BenchmarkMode::Chat
+            && old_ttft > 0.0
+            && new_ttft > old_ttft * (1.0 + tolerance)
+        {
+            regressions.push(regression(RegressedMetric::Ttft, old_ttft, new_ttft));
+        }
+    }
+    regressions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::benchmark::{AggregatedMetrics, SingleRunResult};
+
+    fn result(provider: &str, ttft_ms: u64, latency_ms: u64) -> BenchmarkResult {
+        BenchmarkResult::test_result(
+            provider,
+            vec![SingleRunResult::test_run(ttft_ms, latency_ms)],
+        )
+    }
+
+    fn model_result(model: &str, ttft_ms: u64, latency_ms: u64) -> BenchmarkResult {
+        let mut r = result("groq", ttft_ms, latency_ms);
+        r.model = model.to_string();
+        r.display_name = format!("GROQ {}", model);
+        r
+    }
+
+    #[test]
+    fn test_regressions_match_by_model() {
+        let baseline = [
+            model_result("fast", 100, 250),
+            model_result("slow", 100, 1000),
+        ];
+        // Same numbers as the baseline, listed in the other order
+        let current = [
+            model_result("slow", 100, 1000),
+            model_result("fast", 100, 250),
+        ];
+        assert!(find_regressions(&current, &baseline, 0.1).is_empty());
+
+        let current = [
+            model_result("slow", 100, 1000),
+            model_result("fast", 100, 500),
+        ];
+        let regressions = find_regressions(&current, &baseline, 0.1);
+        assert_eq!(regressions.len(), 1);
+        assert_eq!(regressions[0].display_name, "GROQ fast");
+        assert_eq!(regressions[0].metric, RegressedMetric::TokensPerSec);
+
+        // Rows for other output lengths are not compared against each other
+        let mut longer = model_result("fast", 100, 500);
+        longer.requested_output_tokens = Some(1024);
+        assert!(find_regressions(&[longer], &baseline[..1], 0.1).is_empty());
+    }
+
+    #[test]
+    fn test_regressions_beyond_tolerance() {
+        let baseline = [
+            result("groq", 100, 500),
+            result("cerebras", 100, 500),
+            result("sambanova", 100, 500),
+            // Not benchmarked this time
+            result("fireworks", 100, 500),
+        ];
+        let mut failed = result("sambanova", 100, 500);
+        failed.raw_results.clear();
+        failed.metrics = AggregatedMetrics::from_raw(&[]);
+        let current = [
+            // 200 -> 190 tok/s, 100 -> 105ms TTFT: within 10%
+            result("groq", 105, 526),
+            // 200 -> 100 tok/s, 100 -> 150ms TTFT
+            result("cerebras", 150, 1000),
+            failed,
+        ];
+
+        let regressions = find_regressions(&current, &baseline, 0.1);
+        assert_eq!(regressions.len(), 3);
+        assert_eq!(
+            regressions[0].to_string(),
+            "CEREBRAS: 100.0 tok/s vs 200.0 baseline (-50.0%)"
+        );
+        assert_eq!(
+            regressions[1].to_string(),
+            "CEREBRAS: 150ms TTFT vs 100ms baseline (+50.0%)"
+        );
+        assert_eq!(regressions[2].metric, RegressedMetric::Failed);
+
+        // A failure is a regression at any tolerance
+        assert_eq!(find_regressions(&current, &baseline, 1.0).len(), 1);
+    }
+}
@@ -1,34 +1,482 @@
 //! Benchmark runner - orchestrates benchmark execution.
 
-use super::metrics::AggregatedMetrics;
-use super::prompts::{LONG_PROMPT, MEDIUM_PROMPT, SHORT_PROMPT, TestPrompt};
-use crate::cli::PromptSize;
-use crate::providers::{InferenceProvider, InferenceRequest, InferenceResponse, ProviderError};
+use super::conversation::{split_turn, turn_count};
+use super::metrics::{AggregatedMetrics, ColdWarmMetrics, RunSpread, coefficient_of_variation};
+use super::progress::{ProgressCallback, ProgressEvent};
+use super::prompts::{
+    LONG_PROMPT, MEDIUM_PROMPT, SHORT_PROMPT, TOOL_PROMPT, TestPrompt, estimate_tokens,
+};
+use crate::cli::{BenchmarkMode, PromptSize, ReportMode, StreamMode};
+use crate::pricing::PricingMode;
+use crate::providers::{
+    ChatMessage, EmbeddingRequest, EmbeddingResponse, InferenceProvider, InferenceRequest,
+    InferenceResponse, ProviderError, RateLimitInfo, ServerTiming, redact,
+};
 use chrono::{DateTime, Utc};
+use futures::{StreamExt, stream};
 use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::time::Duration;
+use tokio::time::Instant;
+use uuid::Uuid;
 
 /// Configuration for a benchmark run
-#[derive(Debug, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BenchmarkConfig {
-    /// Number of iterations per provider
+    /// Number of iterations per provider (the minimum when `target_cv` is set)
     pub iterations: u32,
+    /// Keep running iterations until the coefficient of variation of tok/s
+    /// falls to this value (e.g. 0.05), up to `max_iterations`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target_cv: Option<f64>,
+    /// Iteration cap for `target_cv`
+    #[serde(default = "default_max_iterations")]
+    pub max_iterations: u32,
+    /// Workload being benchmarked
+    #[serde(default)]
+    pub mode: BenchmarkMode,
+    /// Model requested from every provider instead of its configured default
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    /// Whether the first iteration is reported apart as a cold start
+    #[serde(default)]
+    pub report: ReportMode,
+    /// Whether chat responses are streamed; `Both` benchmarks providers that
+    /// can turn streaming off a second time without it
+    #[serde(default)]
+    pub stream: StreamMode,
+    /// Offer a tool with each chat request and require the model to call it,
+    /// timing the function-call response instead of a plain answer
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub tools: bool,
     /// Test prompt size
     pub prompt_size: PromptSize,
-    /// Request timeout in milliseconds
-    pub timeout_ms: u64,
+    /// Request timeout in milliseconds, if `--timeout` overrode the
+    /// providers' own
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_ms: Option<u64>,
+    /// Force every provider to generate this many tokens (overrides the
+    /// prompt's expected length)
+    pub output_tokens: Option<u32>,
+    /// Headroom added to the prompt's expected output length to form
+    /// `max_tokens` (ignored when `output_tokens` is set)
+    #[serde(default = "default_max_tokens_buffer")]
+    pub max_tokens_buffer: u32,
+    /// Discard runs more than this many standard deviations from the mean
+    /// latency before averaging (disabled if None)
+    pub trim_outliers: Option<f64>,
+    /// Maximum in-flight requests per provider
+    pub concurrency: u32,
+    /// Per-provider overrides of `concurrency`, keyed by provider ID, for
+    /// providers with tighter or looser rate limits
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub provider_concurrency: BTreeMap<String, u32>,
+    /// Window over which the first wave of concurrent requests is staggered (ms)
+    pub ramp_up_ms: u64,
+    /// Untimed requests sent before the measured iterations
+    #[serde(default)]
+    pub warmup: u32,
+    /// Extra attempts allowed per iteration after a network error or timeout,
+    /// with exponential backoff between them
+    #[serde(default)]
+    pub max_retries: u32,
+    /// Rate card used for cost figures
+    #[serde(default)]
+    pub pricing_mode: PricingMode,
+    /// Load on-demand models (e.g. Ollama) once before the timed sweep so
+    /// load time is excluded from measured runs
+    #[serde(default)]
+    pub warmup_shared: bool,
+    /// Seed of the random provider order of each run; providers run in the
+    /// given order if None
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shuffle_seed: Option<u64>,
+    /// Wall-clock budget for the whole benchmark (seconds); once spent, no
+    /// new iterations start and completed ones are reported as truncated
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_duration_secs: Option<u64>,
+    /// Spend allowed on each provider's measured iterations (USD, across all
+    /// runs and rows); once reached, no new iterations of that provider start
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_cost_per_provider_usd: Option<f64>,
+    /// Abort an iteration with `FirstTokenTimeout` if streaming output has
+    /// not started within this many ms
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ttft_timeout_ms: Option<u64>,
+    /// Abort an iteration with `StreamStalled` if streaming output pauses
+    /// for this many ms after it has started
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stall_timeout_ms: Option<u64>,
+    /// Every request opened its own connection (set on the HTTP clients,
+    /// recorded here)
+    #[serde(default)]
+    pub fresh_connection: bool,
+    /// Idle pooled connections were kept this long instead of the default
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pool_idle_timeout_secs: Option<u64>,
+    /// Number of times the whole benchmark is repeated
+    #[serde(default = "default_runs")]
+    pub runs: u32,
+    /// `max_tokens` values each benchmarked as their own row, forcing that
+    /// output length like `output_tokens` (`--max-tokens-sweep`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub max_tokens_sweep: Vec<u32>,
+    /// Count chat responses with no text or no output tokens as successes
+    /// instead of `EmptyResponse` failures
+    #[serde(default)]
+    pub allow_empty: bool,
+    /// Keep the generated text of each provider's first successful iteration
+    /// in `BenchmarkResult::output_sample`
+    #[serde(default)]
+    pub capture_output: bool,
+    /// Prompt used instead of the built-in one for `prompt_size` (e.g. read
+    /// from stdin)
+    #[serde(skip)]
+    pub custom_prompt: Option<TestPrompt>,
+    /// Prompts cycled across iterations instead of the built-in prompt; see
+    /// `BenchmarkResult::corpus_size`
+    #[serde(skip)]
+    pub corpus: Vec<String>,
+    /// Scripted conversation whose turns are each benchmarked as their own
+    /// row, with the earlier messages as history; see `BenchmarkResult::turn`
+    #[serde(skip)]
+    pub conversation: Vec<ChatMessage>,
+    /// 1-based conversation turn this config sends (set per row)
+    #[serde(skip)]
+    pub turn: Option<u32>,
+}
+
+fn default_runs() -> u32 {
+    1
+}
+
+/// Default iteration cap when iterating to a target coefficient of variation
+pub const DEFAULT_MAX_ITERATIONS: u32 = 20;
+
+/// Fewest successful runs whose spread is trusted for `target_cv`
+const MIN_CV_SAMPLES: usize = 3;
+
+/// Backoff before the first retry of an iteration; doubles per retry
+const RETRY_BASE_DELAY: Duration = Duration::from_millis(200);
+
+/// Longest backoff between retries, before jitter
+const RETRY_MAX_DELAY: Duration = Duration::from_secs(5);
+
+fn default_max_iterations() -> u32 {
+    DEFAULT_MAX_ITERATIONS
+}
+
+fn is_zero(n: &u32) -> bool {
+    *n == 0
+}
+
+/// Default `max_tokens` headroom over the prompt's expected output length
+pub const DEFAULT_MAX_TOKENS_BUFFER: u32 = 50;
+
+fn default_max_tokens_buffer() -> u32 {
+    DEFAULT_MAX_TOKENS_BUFFER
+}
+
+impl BenchmarkConfig {
+    /// Get the custom prompt if set, else the tool prompt with `tools`,
+    /// else the test prompt for the configured prompt size
+    pub fn prompt(&self) -> &TestPrompt {
+        if let Some(prompt) = &self.custom_prompt {
+            return prompt;
+        }
+        if self.tools {
+            return &TOOL_PROMPT;
+        }
+        match self.prompt_size {
+            PromptSize::Short => &SHORT_PROMPT,
+            PromptSize::Medium => &MEDIUM_PROMPT,
+            PromptSize::Long => &LONG_PROMPT,
+        }
+    }
+
+    /// Prompt text for an iteration, cycling through the corpus if one is set
+    pub fn prompt_text(&self, iteration: u32) -> &str {
+        if self.corpus.is_empty() {
+            &self.prompt().text
+        } else {
+            &self.corpus[iteration as usize % self.corpus.len()]
+        }
+    }
+
+    /// Most iterations a provider can run: `max_iterations` when iterating to
+    /// `target_cv`, else `iterations`
+    pub fn iteration_cap(&self) -> u32 {
+        match self.target_cv {
+            Some(_) => self.max_iterations.max(self.iterations),
+            None => self.iterations,
+        }
+    }
+
+    /// Whether `target_cv` is set and the runs so far meet it
+    fn reached_target_cv(&self, runs: &[SingleRunResult]) -> bool {
+        let Some(target) = self.target_cv else {
+            return false;
+        };
+        if runs.len() < (self.iterations as usize).max(MIN_CV_SAMPLES) {
+            return false;
+        }
+        let tokens_per_sec: Vec<f64> = runs.iter().map(|r| r.tokens_per_sec()).collect();
+        coefficient_of_variation(&tokens_per_sec).is_some_and(|cv| cv <= target)
+    }
+
+    /// Number of corpus prompts actually exercised, if a corpus is set
+    pub fn corpus_size(&self) -> Option<usize> {
+        (!self.corpus.is_empty()).then(|| self.corpus.len().min(self.iterations as usize))
+    }
+
+    /// Estimated input tokens of a request: the conversation up to `turn`
+    /// when replaying one, the average over the corpus prompts exercised when
+    /// one is set, else the prompt's
+    fn input_tokens(&self) -> u32 {
+        match self
+            .turn
+            .and_then(|turn| split_turn(&self.conversation, turn))
+        {
+            Some((history, prompt)) => {
+                history
+                    .iter()
+                    .map(|m| estimate_tokens(&m.content))
+                    .sum::<u32>()
+                    + estimate_tokens(prompt)
+            }
+            None => match self.corpus_size() {
+                Some(size) => {
+                    let total: u32 = self.corpus[..size].iter().map(|p| estimate_tokens(p)).sum();
+                    total.div_ceil(size as u32)
+                }
+                None => self.prompt().expected_input_tokens,
+            },
+        }
+    }
+
+    /// Build the request sent for an iteration
+    fn request(&self, iteration: u32) -> InferenceRequest {
+        let (history, prompt) = self
+            .turn
+            .and_then(|turn| split_turn(&self.conversation, turn))
+            .unwrap_or((&[], self.prompt_text(iteration)));
+        InferenceRequest {
+            prompt: prompt.to_string(),
+            max_tokens: self.max_tokens(),
+            min_tokens: self.output_tokens,
+            model: self.model.clone(),
+            first_token_timeout_ms: self.ttft_timeout_ms,
+            stall_timeout_ms: self.stall_timeout_ms,
+            idempotency_key: None,
+            stream: self.stream != StreamMode::Off,
+            tools: self.tools,
+            history: history.to_vec(),
+        }
+    }
+
+    /// Build the embeddings request sent for an iteration
+    fn embedding_request(&self, iteration: u32) -> EmbeddingRequest {
+        EmbeddingRequest {
+            input: self.prompt_text(iteration).to_string(),
+            model: self.model.clone(),
+        }
+    }
+
+    /// Model benchmarked for a provider in the configured mode: `model` if
+    /// set, else the provider's own; None if the provider does not support
+    /// the mode
+    pub fn model_for<'a>(&'a self, provider: &'a dyn InferenceProvider) -> Option<&'a str> {
+        let own = match self.mode {
+            BenchmarkMode::Chat => Some(provider.default_model()),
+            BenchmarkMode::Embeddings => provider.embedding_model(),
+        };
+        own.map(|own| self.model.as_deref().unwrap_or(own))
+    }
+
+    /// Whether `provider` is benchmarked both streamed and not (`Both`, in
+    /// chat mode, for providers that can turn streaming off)
+    pub fn compares_streaming(&self, provider: &dyn InferenceProvider) -> bool {
+        self.stream == StreamMode::Both
+            && self.mode == BenchmarkMode::Chat
+            && provider.supports_non_streaming()
+    }
+
+    /// Whether requests to `provider` actually go out without streaming
+    fn sends_unstreamed(&self, provider: &dyn InferenceProvider) -> bool {
+        self.stream == StreamMode::Off
+            && self.mode == BenchmarkMode::Chat
+            && provider.supports_non_streaming()
+    }
+
+    /// Configs to benchmark `provider` with, one per result row, labelled
+    /// when a conversation is replayed, `max_tokens` is swept, or it is
+    /// compared streamed and not
+    fn variants(
+        &self,
+        provider: &dyn InferenceProvider,
+    ) -> Vec<(Cow<'_, BenchmarkConfig>, Option<String>)> {
+        let turns: Vec<u32> = (1..=turn_count(&self.conversation) as u32).collect();
+        let compares_streaming = self.compares_streaming(provider);
+        if turns.is_empty() && self.max_tokens_sweep.is_empty() && !compares_streaming {
+            return vec![(Cow::Borrowed(self), None)];
+        }
+
+        let mut rows = vec![(self.clone(), Vec::new())];
+        rows = expand_rows(rows, &turns, |config, turn| {
+            config.turn = Some(turn);
+            format!("turn {}", turn)
+        });
+        rows = expand_rows(rows, &self.max_tokens_sweep, |config, max_tokens| {
+            config.output_tokens = Some(max_tokens);
+            format!("max_tokens {}", max_tokens)
+        });
+        if compares_streaming {
+            rows = expand_rows(
+                rows,
+                &[StreamMode::On, StreamMode::Off],
+                |config, stream| {
+                    config.stream = stream;
+                    match stream {
+                        StreamMode::Off => "no stream".to_string(),
+                        _ => "stream".to_string(),
+                    }
+                },
+            );
+        }
+        rows.into_iter()
+            .map(|(config, labels)| (Cow::Owned(config), Some(labels.join(", "))))
+            .collect()
+    }
+
+    /// `max_tokens` sent with each request
+    pub fn max_tokens(&self) -> u32 {
+        self.output_tokens
+            .unwrap_or(self.prompt().expected_output_tokens + self.max_tokens_buffer)
+    }
+
+    /// (input, output) rates per 1M tokens for a model under the pricing mode
+    pub fn pricing_for(&self, provider: &dyn InferenceProvider, model: &str) -> (f64, f64) {
+        self.pricing_mode
+            .apply(provider.name(), model, provider.pricing_for_model(model))
+    }
+
+    /// Deadline for a benchmark starting now, if `max_duration_secs` is set
+    pub(super) fn deadline(&self) -> Option<Instant> {
+        self.max_duration_secs
+            .map(|secs| Instant::now() + Duration::from_secs(secs))
+    }
+
+    /// Maximum in-flight requests for a provider: its override if set, else
+    /// the global `concurrency`
+    pub fn concurrency_for(&self, provider: &str) -> u32 {
+        self.provider_concurrency
+            .get(provider)
+            .copied()
+            .unwrap_or(self.concurrency)
+            .max(1)
+    }
+
+    /// Start delay for an iteration when ramping up concurrent requests
+    ///
+    /// The provider's first `concurrency_for` requests start at evenly spaced
+    /// offsets within `ramp_up_ms`; later requests start as soon as a slot
+    /// frees up.
+    pub fn ramp_up_offset(&self, provider: &str, iteration: u32) -> Duration {
+        let concurrency = self.concurrency_for(provider);
+        if concurrency <= 1 || iteration >= concurrency {
+            return Duration::ZERO;
+        }
+        Duration::from_millis(self.ramp_up_ms * iteration as u64 / concurrency as u64)
+    }
+}
+
+/// Each row once per value, set by `apply`, whose returned label is
+/// appended; rows pass through unchanged if there are no values
+fn expand_rows<T: Copy>(
+    rows: Vec<(BenchmarkConfig, Vec<String>)>,
+    values: &[T],
+    apply: impl Fn(&mut BenchmarkConfig, T) -> String,
+) -> Vec<(BenchmarkConfig, Vec<String>)> {
+    if values.is_empty() {
+        return rows;
+    }
+    let mut expanded = Vec::with_capacity(rows.len() * values.len());
+    for (config, labels) in rows {
+        for &value in values {
+            let mut config = config.clone();
+            let mut labels = labels.clone();
+            labels.push(apply(&mut config, value));
+            expanded.push((config, labels));
+        }
+    }
+    expanded
 }
 
 impl Default for BenchmarkConfig {
     fn default() -> Self {
         Self {
             iterations: 1,
+            target_cv: None,
+            max_iterations: DEFAULT_MAX_ITERATIONS,
+            mode: BenchmarkMode::Chat,
+            model: None,
+            report: ReportMode::Combined,
+            stream: StreamMode::On,
+            tools: false,
             prompt_size: PromptSize::Short,
-            timeout_ms: 60_000,
+            timeout_ms: None,
+            output_tokens: None,
+            max_tokens_buffer: DEFAULT_MAX_TOKENS_BUFFER,
+            trim_outliers: None,
+            concurrency: 1,
+            provider_concurrency: BTreeMap::new(),
+            ramp_up_ms: 0,
+            warmup: 0,
+            max_retries: 0,
+            pricing_mode: PricingMode::Standard,
+            warmup_shared: false,
+            shuffle_seed: None,
+            max_duration_secs: None,
+            max_cost_per_provider_usd: None,
+            ttft_timeout_ms: None,
+            stall_timeout_ms: None,
+            fresh_connection: false,
+            pool_idle_timeout_secs: None,
+            runs: 1,
+            max_tokens_sweep: Vec::new(),
+            allow_empty: false,
+            capture_output: false,
+            custom_prompt: None,
+            corpus: Vec::new(),
+            conversation: Vec::new(),
+            turn: None,
         }
     }
 }
 
+/// Range of expected spend for a benchmark run, in USD
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct CostEstimate {
+    /// Measured iterations only, each succeeding first time
+    pub min: f64,
+    /// Warmups plus measured iterations, each succeeding first time
+    pub expected: f64,
+    /// Every request retried the maximum number of times and generating up
+    /// to `max_tokens`
+    pub max: f64,
+}
+
+impl CostEstimate {
+    /// Whether the run could cost anything at all
+    pub fn is_free(&self) -> bool {
+        self.max <= 0.0
+    }
+}
+
 /// Result from a single benchmark iteration
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SingleRunResult {
     pub time_to_prompt_ms: u64,
@@ -36,17 +484,93 @@ pub struct SingleRunResult {
     pub total_latency_ms: u64,
     pub input_tokens: u32,
     pub output_tokens: u32,
+    /// Reasoning tokens generated before the answer (reasoning models only)
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub reasoning_tokens: u32,
     pub cost_usd: f64,
     pub model_load_time_ms: Option<u64>,
+    /// Rate-limit quota reported after this run, if the provider exposes it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rate_limit: Option<RateLimitInfo>,
+    /// Estimated tokens received per network read while streaming
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub chunk_token_counts: Vec<u32>,
+    /// Length of the returned vector, for embeddings runs
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub embedding_dimensions: Option<u32>,
+    /// Whitespace-separated words in the output text
+    #[serde(default)]
+    pub output_words: u32,
+    /// Characters (not bytes) in the output text
+    #[serde(default)]
+    pub output_chars: u32,
+    /// Generation stopped at `max_tokens` rather than on its own (reported by
+    /// the provider, or output reached the cap), so the output may be
+    /// truncated
+    #[serde(default)]
+    pub hit_max_tokens: bool,
+    /// Serialized request body size (bytes)
+    #[serde(default)]
+    pub request_bytes: u64,
+    /// Response body received, SSE framing included (bytes)
+    #[serde(default)]
+    pub response_bytes: u64,
+    /// First measured iteration of a provider, with `--report cold-warm`
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub cold: bool,
+    /// Sent with an `Idempotency-Key`, so a retried attempt is not billed twice
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub idempotent: bool,
+    /// Time the provider reports the request waited in its queue (ms),
+    /// part of `time_to_first_token_ms`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub queue_time_ms: Option<u64>,
+    /// Provider-reported processing times; when set, TTFT is the queue wait
+    /// plus prompt processing and tok/s uses the server's total time
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub server_timing: Option<ServerTiming>,
+    /// Generated text, kept only with `capture_output` until the runner
+    /// moves it into `BenchmarkResult::output_sample`
+    #[serde(skip)]
+    pub output_text: Option<String>,
 }
 
 impl SingleRunResult {
-    /// Calculate tokens per second (output throughput)
+    /// Calculate tokens per second (generated throughput including
+    /// reasoning, or input throughput for embeddings runs)
+    ///
+    /// Timed by the provider's reported total when it has one, else by the
+    /// wall-clock latency.
     pub fn tokens_per_sec(&self) -> f64 {
-        if self.total_latency_ms == 0 {
+        let tokens = match self.embedding_dimensions {
+            Some(_) => self.input_tokens,
+            None => self.output_tokens + self.reasoning_tokens,
+        };
+        self.per_sec(tokens)
+    }
+
+    /// Output words per second, independent of the provider's tokenizer and
+    /// timed like [`Self::tokens_per_sec`]
+    pub fn words_per_sec(&self) -> f64 {
+        self.per_sec(self.output_words)
+    }
+
+    /// Output characters per second, independent of the provider's tokenizer
+    /// and timed like [`Self::tokens_per_sec`]
+    pub fn chars_per_sec(&self) -> f64 {
+        self.per_sec(self.output_chars)
+    }
+
+    /// Rate over the provider's reported total time, else the wall-clock
+    /// latency
+    fn per_sec(&self, count: u32) -> f64 {
+        let elapsed_ms = self
+            .server_timing
+            .map_or(self.total_latency_ms, |timing| timing.total_ms);
+        if elapsed_ms == 0 {
             return 0.0;
         }
-        self.output_tokens as f64 / (self.total_latency_ms as f64 / 1000.0)
+        count as f64 / (elapsed_ms as f64 / 1000.0)
     }
 
     /// Create from inference response with pricing
@@ -56,22 +580,104 @@ impl SingleRunResult {
         output_price: f64,
     ) -> Self {
         let input_cost = (response.input_tokens as f64 / 1_000_000.0) * input_price;
-        let output_cost = (response.output_tokens as f64 / 1_000_000.0) * output_price;
+        // Reasoning tokens are billed as output
+        let generated = response.output_tokens + response.reasoning_tokens;
+        let output_cost = (generated as f64 / 1_000_000.0) * output_price;
 
         Self {
             time_to_prompt_ms: response.time_to_prompt_ms,
-            time_to_first_token_ms: response.time_to_first_token_ms,
+            time_to_first_token_ms: match response.server_timing {
+                Some(timing) => response.queue_time_ms.unwrap_or(0) + timing.prompt_ms,
+                None => response.time_to_first_token_ms,
+            },
             total_latency_ms: response.total_latency_ms,
             input_tokens: response.input_tokens,
             output_tokens: response.output_tokens,
+            reasoning_tokens: response.reasoning_tokens,
             cost_usd: input_cost + output_cost,
             model_load_time_ms: response.model_load_time_ms,
+            rate_limit: response.rate_limit.clone(),
+            chunk_token_counts: response.chunk_token_counts.clone(),
+            embedding_dimensions: None,
+            output_words: response.text.split_whitespace().count() as u32,
+            output_chars: response.text.chars().count() as u32,
+            hit_max_tokens: false,
+            request_bytes: response.request_bytes,
+            response_bytes: response.response_bytes,
+            cold: false,
+            idempotent: response.idempotent,
+            queue_time_ms: response.queue_time_ms,
+            server_timing: response.server_timing,
+            output_text: None,
         }
     }
+
+    /// Create from embeddings response with input pricing
+    ///
+    /// There is no first token, so TTFT is reported as the full latency.
+    pub fn from_embedding(response: &EmbeddingResponse, input_price: f64) -> Self {
+        Self {
+            time_to_prompt_ms: response.time_to_prompt_ms,
+            time_to_first_token_ms: response
+                .total_latency_ms
+                .saturating_sub(response.time_to_prompt_ms),
+            total_latency_ms: response.total_latency_ms,
+            input_tokens: response.input_tokens,
+            output_tokens: 0,
+            reasoning_tokens: 0,
+            cost_usd: (response.input_tokens as f64 / 1_000_000.0) * input_price,
+            model_load_time_ms: response.model_load_time_ms,
+            rate_limit: None,
+            chunk_token_counts: Vec::new(),
+            embedding_dimensions: Some(response.dimensions),
+            output_words: 0,
+            output_chars: 0,
+            hit_max_tokens: false,
+            request_bytes: 0,
+            response_bytes: 0,
+            cold: false,
+            idempotent: false,
+            queue_time_ms: None,
+            server_timing: None,
+            output_text: None,
+        }
+    }
+}
+
+/// Outcome of one provider's benchmark, so consumers can branch on it
+/// without matching error messages
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ResultStatus {
+    /// Every attempted iteration succeeded
+    Ok,
+    /// Some iterations succeeded and some failed
+    Partial,
+    /// Nothing succeeded
+    Failed,
+}
+
+impl ResultStatus {
+    fn new(succeeded: bool, has_errors: bool) -> Self {
+        match (succeeded, has_errors) {
+            (false, _) => ResultStatus::Failed,
+            (true, true) => ResultStatus::Partial,
+            (true, false) => ResultStatus::Ok,
+        }
+    }
+
+    /// Placeholder until [`BenchmarkResult`]'s deserializer fills in the
+    /// saved or derived status
+    fn unrecorded() -> Self {
+        ResultStatus::Failed
+    }
 }
 
 /// Complete benchmark result for a single provider
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(remote = "Self")]
 pub struct BenchmarkResult {
     /// Provider identifier
     pub provider: String,
@@ -79,20 +685,207 @@ pub struct BenchmarkResult {
     pub display_name: String,
     /// Model used
     pub model: String,
+    /// Base URL requests were sent to, for providers that expose one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_url: Option<String>,
+    /// Workload benchmarked; TTFT is not meaningful for embeddings
+    #[serde(default)]
+    pub mode: BenchmarkMode,
     /// Aggregated metrics
     pub metrics: AggregatedMetrics,
-    /// Raw results from each iteration
+    /// Raw results from each iteration; left out of JSON output unless
+    /// `--include-raw` is given
+    #[serde(default)]
     pub raw_results: Vec<SingleRunResult>,
+    /// Output length requested via `--output-tokens`, if any
+    pub requested_output_tokens: Option<u32>,
     /// Errors encountered
     pub errors: Vec<String>,
+    /// Whether all, some, or none of the iterations succeeded; derived from
+    /// the runs and errors for results saved before this was recorded
+    #[serde(default = "ResultStatus::unrecorded")]
+    #[cfg_attr(feature = "schema", schemars(!default))]
+    pub status: ResultStatus,
+    /// Model load time paid up front by `--warmup-shared` (ms)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub preload_time_ms: Option<u64>,
+    /// Number of distinct prompts iterations were spread over, if a corpus was used
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub corpus_size: Option<usize>,
+    /// Whether `--max-duration` cut the benchmark short; metrics cover only
+    /// the iterations that completed in time
+    #[serde(default)]
+    pub truncated: bool,
+    /// Whether the provider stopped at `--max-cost-per-provider`; metrics
+    /// cover only the iterations run before the cap
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub cost_capped: bool,
+    /// Throughput spread across repeated runs (`--runs` > 1)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub run_spread: Option<RunSpread>,
+    /// First-request and steady-state metrics (`--report cold-warm`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cold_warm: Option<ColdWarmMetrics>,
+    /// Responses were requested whole instead of streamed (`--stream off`
+    /// or `both`), so TTFT is the time to the complete response
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub non_streaming: bool,
+    /// 1-based conversation turn measured (`--conversation`); later turns
+    /// carry more history
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub turn: Option<u32>,
+    /// Generated text of the first successful iteration (`--capture-output`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_sample: Option<String>,
     /// Timestamp of benchmark
     pub timestamp: DateTime<Utc>,
 }
 
+// The derived impls are inherent functions (`remote = "Self"`) so that
+// deserializing can fill in a missing status from the rest of the result
+impl Serialize for BenchmarkResult {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        BenchmarkResult::serialize(self, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for BenchmarkResult {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        struct Saved {
+            status: Option<ResultStatus>,
+            #[serde(flatten, with = "BenchmarkResult")]
+            result: BenchmarkResult,
+        }
+
+        let Saved { status, mut result } = Saved::deserialize(deserializer)?;
+        // Results saved before the status was recorded must not all read as ok
+        result.status = status
+            .unwrap_or_else(|| ResultStatus::new(result.is_success(), !result.errors.is_empty()));
+        Ok(result)
+    }
+}
+
 impl BenchmarkResult {
     /// Check if benchmark was successful (at least one good run)
+    ///
+    /// Also holds for results read back from JSON written without raw results.
     pub fn is_success(&self) -> bool {
-        !self.raw_results.is_empty()
+        !self.raw_results.is_empty() || self.metrics.run_count > 0
+    }
+
+    /// Number of runs that stopped at the `max_tokens` cap
+    pub fn max_tokens_hits(&self) -> usize {
+        self.metrics.truncated_count
+    }
+
+    /// Most recent rate-limit quota reported by the provider, if any
+    pub fn latest_rate_limit(&self) -> Option<&RateLimitInfo> {
+        self.raw_results
+            .iter()
+            .rev()
+            .find_map(|r| r.rate_limit.as_ref())
+    }
+
+    /// Combine the results of repeated runs against the same provider
+    ///
+    /// Metrics are recomputed over the pooled iterations, and the spread of
+    /// each successful run's average throughput is kept in `run_spread`.
+    fn merge_runs(runs: Vec<BenchmarkResult>, trim_outliers: Option<f64>) -> BenchmarkResult {
+        let per_run_tokens_per_sec = runs
+            .iter()
+            .filter(|r| r.is_success())
+            .map(|r| r.metrics.avg_tokens_per_sec)
+            .collect();
+
+        let attempted = runs.iter().map(|r| r.metrics.attempted_count).sum();
+
+        let mut runs = runs.into_iter().enumerate();
+        let (_, mut merged) = runs.next().expect("at least one run");
+        merged.errors = merged
+            .errors
+            .iter()
+            .map(|e| format!("Run 1: {}", e))
+            .collect();
+
+        for (n, run) in runs {
+            merged.raw_results.extend(run.raw_results);
+            merged
+                .errors
+                .extend(run.errors.iter().map(|e| format!("Run {}: {}", n + 1, e)));
+            merged.truncated |= run.truncated;
+            merged.cost_capped |= run.cost_capped;
+            merged.output_sample = merged.output_sample.or(run.output_sample);
+            merged.timestamp = run.timestamp;
+        }
+
+        merged.metrics = AggregatedMetrics::from_raw_trimmed(&merged.raw_results, trim_outliers)
+            .with_attempts(attempted);
+        merged.run_spread = Some(RunSpread::from_run_means(per_run_tokens_per_sec));
+        merged.cold_warm = ColdWarmMetrics::from_raw(&merged.raw_results, trim_outliers);
+        merged.status = ResultStatus::new(merged.is_success(), !merged.errors.is_empty());
+        merged
+    }
+}
+
+#[cfg(test)]
+impl SingleRunResult {
+    /// Successful chat run for tests: 10 input and 100 output tokens (80
+    /// words, 400 characters), free; override fields with struct update
+    pub(crate) fn test_run(ttft_ms: u64, latency_ms: u64) -> Self {
+        Self {
+            time_to_prompt_ms: 0,
+            time_to_first_token_ms: ttft_ms,
+            total_latency_ms: latency_ms,
+            input_tokens: 10,
+            output_tokens: 100,
+            reasoning_tokens: 0,
+            cost_usd: 0.0,
+            model_load_time_ms: None,
+            rate_limit: None,
+            chunk_token_counts: Vec::new(),
+            embedding_dimensions: None,
+            output_words: 80,
+            output_chars: 400,
+            hit_max_tokens: false,
+            request_bytes: 0,
+            response_bytes: 0,
+            cold: false,
+            idempotent: false,
+            queue_time_ms: None,
+            server_timing: None,
+            output_text: None,
+        }
+    }
+}
+
+#[cfg(test)]
+impl BenchmarkResult {
+    /// Chat result for tests aggregating `runs`, with `provider` as its ID
+    /// and upper-cased as its display name; failed if `runs` is empty
+    pub(crate) fn test_result(provider: &str, runs: Vec<SingleRunResult>) -> Self {
+        Self {
+            provider: provider.to_string(),
+            display_name: provider.to_uppercase(),
+            model: "model".to_string(),
+            base_url: None,
+            mode: BenchmarkMode::Chat,
+            metrics: AggregatedMetrics::from_raw(&runs),
+            status: ResultStatus::new(!runs.is_empty(), false),
+            raw_results: runs,
+            requested_output_tokens: None,
+            errors: Vec::new(),
+            preload_time_ms: None,
+            corpus_size: None,
+            truncated: false,
+            cost_capped: false,
+            run_spread: None,
+            cold_warm: None,
+            non_streaming: false,
+            turn: None,
+            output_sample: None,
+            timestamp: Utc::now(),
+        }
     }
 }
 
@@ -100,104 +893,1636 @@ impl BenchmarkResult {
 pub struct BenchmarkRunner<'a> {
     providers: Vec<&'a dyn InferenceProvider>,
     config: BenchmarkConfig,
+    progress: Option<ProgressCallback<'a>>,
 }
 
 impl<'a> BenchmarkRunner<'a> {
     /// Create a new benchmark runner
     pub fn new(providers: Vec<&'a dyn InferenceProvider>, config: BenchmarkConfig) -> Self {
-        Self { providers, config }
+        Self {
+            providers,
+            config,
+            progress: None,
+        }
+    }
+
+    /// Report each provider start, iteration, and finish to `callback`
+    pub fn with_progress(mut self, callback: impl Fn(&ProgressEvent) + Send + Sync + 'a) -> Self {
+        self.progress = Some(Box::new(callback));
+        self
+    }
+
+    /// Iterations a full sweep would run: each provider row (two with
+    /// `--stream both`) times `runs` times the iteration cap
+    ///
+    /// Providers that stop early (e.g. on `target_cv`) run fewer.
+    pub fn planned_iterations(&self) -> u32 {
+        let rows: usize = self
+            .providers
+            .iter()
+            .map(|provider| self.config.variants(*provider).len())
+            .sum();
+        rows as u32 * self.config.runs.max(1) * self.config.iteration_cap()
     }
 
     /// Run benchmarks across all providers sequentially
+    ///
+    /// With `runs` > 1 the whole sweep is repeated and each provider's
+    /// results are merged, so run-level effects show up in `run_spread`.
+    ///
+    /// With `max_duration_secs` set, the budget covers the whole sweep
+    /// (preloading included); later runs are skipped once it is spent.
+    ///
+    /// With `target_cv` set, each provider runs between `iterations` and
+    /// `max_iterations` iterations, stopping once its tok/s is stable.
+    ///
+    /// With `max_cost_per_provider_usd` set, each provider's spend is tracked
+    /// across runs and rows; a provider that has reached it sits out the rest
+    /// of the sweep.
+    ///
+    /// With `shuffle_seed` set, each run visits providers in a new random
+    /// order; results keep the providers' order either way.
     pub async fn run(&self) -> Vec<BenchmarkResult> {
-        let mut results = Vec::new();
+        let runs = self.config.runs.max(1);
+        // (provider index, config, row label) for each row of the sweep
+        let entries: Vec<_> = self
+            .providers
+            .iter()
+            .enumerate()
+            .flat_map(|(i, provider)| {
+                self.config
+                    .variants(*provider)
+                    .into_iter()
+                    .map(move |(config, label)| (i, config, label))
+            })
+            .collect();
+        let mut sweeps: Vec<Vec<BenchmarkResult>> = vec![Vec::new(); entries.len()];
+        let mut order: Vec<usize> = (0..entries.len()).collect();
+        let mut rng = self.config.shuffle_seed.map(fastrand::Rng::with_seed);
+        let deadline = self.config.deadline();
+        let preload_times = self.preload().await;
+        // Providers whose first iteration still follows a fresh preload
+        let mut preloaded: Vec<bool> = preload_times.iter().map(Option::is_some).collect();
+
+        for run in 0..runs {
+            if run > 0 && deadline.is_some_and(|d| Instant::now() >= d) {
+                tracing::warn!(completed_runs = run, runs, "max duration reached");
+                for provider_runs in &mut sweeps {
+                    if let Some(last) = provider_runs.last_mut() {
+                        last.truncated = true;
+                    }
+                }
+                break;
+            }
+            if runs > 1 {
+                tracing::info!(run = run + 1, runs, "starting run");
+            }
+            let progress: &(dyn Fn(&ProgressEvent) + Sync) = match &self.progress {
+                Some(callback) => callback.as_ref(),
+                None => &|_| {},
+            };
+            if let Some(rng) = &mut rng {
+                rng.shuffle(&mut order);
+                let names: Vec<&str> = order
+                    .iter()
+                    .map(|&i| self.providers[entries[i].0].name())
+                    .collect();
+                tracing::info!(run = run + 1, order = ?names, "shuffled provider order");
+            }
+            for &i in &order {
+                let (index, config, _) = &entries[i];
+                let provider = self.providers[*index];
+                // Every row of a provider draws on the same budget
+                let budget = self.config.max_cost_per_provider_usd.map(|cap| {
+                    cap - entries
+                        .iter()
+                        .zip(&sweeps)
+                        .filter(|((other, _, _), _)| other == index)
+                        .flat_map(|(_, provider_runs)| provider_runs)
+                        .flat_map(|r| &r.raw_results)
+                        .map(|run| run.cost_usd)
+                        .sum::<f64>()
+                });
+                if budget.is_some_and(|b| b <= 0.0) {
+                    match sweeps[i].last_mut() {
+                        Some(last) => last.cost_capped = true,
+                        None => {
+                            let model = config
+                                .model_for(provider)
+                                .unwrap_or(provider.default_model());
+                            let mut result =
+                                failed_result(provider, config, model, "Cost cap reached".into());
+                            result.cost_capped = true;
+                            sweeps[i].push(result);
+                        }
+                    }
+                    continue;
+                }
+                progress(&ProgressEvent::ProviderStarted {
+                    provider: provider.name().to_string(),
+                    iterations: self.config.iteration_cap(),
+                });
+                let result = benchmark_until(
+                    provider,
+                    config,
+                    deadline,
+                    budget,
+                    std::mem::take(&mut preloaded[*index]),
+                    progress,
+                )
+                .await;
+                progress(&ProgressEvent::ProviderFinished {
+                    provider: provider.name().to_string(),
+                    skipped: (result.metrics.attempted_count == 0)
+                        .then(|| result.errors.first().cloned())
+                        .flatten(),
+                });
+                sweeps[i].push(result);
+            }
+        }
+
+        sweeps
+            .into_iter()
+            .zip(entries)
+            .map(|(mut provider_runs, (provider, _, label))| {
+                let mut result = if provider_runs.len() > 1 {
+                    BenchmarkResult::merge_runs(provider_runs, self.config.trim_outliers)
+                } else {
+                    provider_runs.remove(0)
+                };
+                result.preload_time_ms = preload_times[provider];
+                if let Some(label) = label {
+                    result.display_name = format!("{} ({})", result.display_name, label);
+                }
+                result
+            })
+            .collect()
+    }
 
+    /// Load each provider's benchmarked model ahead of timing when
+    /// `warmup_shared` is set
+    async fn preload(&self) -> Vec<Option<u64>> {
+        let mut load_times = Vec::with_capacity(self.providers.len());
         for provider in &self.providers {
-            let result = self.benchmark_provider(*provider).await;
-            results.push(result);
+            let load_time = match self.config.model_for(*provider) {
+                Some(model) if self.config.warmup_shared => {
+                    provider.preload(model).await.unwrap_or_else(|e| {
+                        tracing::warn!(
+                            provider = provider.name(),
+                            error = %redact(&e.to_string()),
+                            "preload failed"
+                        );
+                        None
+                    })
+                }
+                _ => None,
+            };
+            load_times.push(load_time);
         }
+        load_times
+    }
 
-        results
+    /// Get the test prompt based on configuration
+    fn get_prompt(&self) -> &TestPrompt {
+        self.config.prompt()
     }
 
-    /// Benchmark a single provider
-    async fn benchmark_provider(&self, provider: &dyn InferenceProvider) -> BenchmarkResult {
+    /// Estimate the cost range for the benchmark run
+    ///
+    /// With `target_cv` set, only the maximum assumes every provider runs to
+    /// `max_iterations`.
+    pub fn estimate_cost(&self) -> CostEstimate {
         let prompt = self.get_prompt();
-        let (input_price, output_price) = provider.pricing_per_million();
-
-        let mut raw_results = Vec::new();
-        let mut errors = Vec::new();
-
-        // Check availability first
-        if !provider.is_available().await {
-            errors.push("Provider not available".to_string());
-            return BenchmarkResult {
-                provider: provider.name().to_string(),
-                display_name: provider.display_name().to_string(),
-                model: provider.default_model().to_string(),
-                metrics: AggregatedMetrics::from_raw(&[]),
-                raw_results,
-                errors,
-                timestamp: Utc::now(),
+        let runs = self.config.runs.max(1) as f64;
+        let iterations = runs * self.config.iterations as f64;
+        let most_iterations = runs * self.config.iteration_cap() as f64;
+        let warmups = runs * self.config.warmup as f64;
+        let attempts_per_request = (1 + self.config.max_retries) as f64;
+        let mut estimate = CostEstimate::default();
+
+        for provider in &self.providers {
+            let Some(model) = self.config.model_for(*provider) else {
+                continue;
             };
+            let (input_price, output_price) = self.config.pricing_for(*provider, model);
+            // Measured spend across all of the provider's rows, and the
+            // priciest single iteration of any row
+            let (mut measured, mut measured_max) = (0.0, 0.0);
+            let (mut per_run_most, mut per_run_max_most) = (0.0_f64, 0.0_f64);
+            for (variant, _) in self.config.variants(*provider) {
+                // Embeddings are billed on input only
+                let (output_tokens, max_tokens) = match variant.mode {
+                    BenchmarkMode::Chat => (
+                        variant
+                            .output_tokens
+                            .unwrap_or(prompt.expected_output_tokens),
+                        variant.max_tokens(),
+                    ),
+                    BenchmarkMode::Embeddings => (0, 0),
+                };
+                let prompt = TestPrompt {
+                    expected_input_tokens: variant.input_tokens(),
+                    ..prompt.clone()
+                };
+                let per_run =
+                    prompt.estimate_cost_for_output(input_price, output_price, output_tokens);
+                let per_run_max =
+                    prompt.estimate_cost_for_output(input_price, output_price, max_tokens);
+                measured += per_run * iterations;
+                measured_max += per_run_max * most_iterations;
+                per_run_most = per_run_most.max(per_run);
+                per_run_max_most = per_run_max_most.max(per_run_max);
+                estimate.expected += per_run * warmups;
+                estimate.max += per_run_max * warmups * attempts_per_request;
+            }
+
+            // Measured spend stops at the cap, give or take the iteration
+            // that crosses it (a spent cap starts none); warmups do not count
+            // against it
+            let capped =
+                |measured: f64, per_iteration: f64| match self.config.max_cost_per_provider_usd {
+                    Some(cap) if cap > 0.0 => measured.min(cap + per_iteration),
+                    Some(_) => 0.0,
+                    None => measured,
+                };
+            let expected = capped(measured, per_run_most);
+            estimate.min += expected;
+            estimate.expected += expected;
+            estimate.max += capped(measured_max, per_run_max_most) * attempts_per_request;
         }
 
-        // Run benchmark iterations
-        for i in 0..self.config.iterations {
-            let request = InferenceRequest {
-                prompt: prompt.text.to_string(),
-                max_tokens: prompt.expected_output_tokens + 50, // Some buffer
-                model: None,
-            };
+        estimate
+    }
+}
+
+/// Benchmark a single provider with the given configuration
+///
+/// This is the same code path [`BenchmarkRunner::run`] uses for each provider,
+/// exposed for library consumers that only need one result.
+pub async fn benchmark_single(
+    provider: &dyn InferenceProvider,
+    config: &BenchmarkConfig,
+) -> BenchmarkResult {
+    benchmark_until(
+        provider,
+        config,
+        config.deadline(),
+        config.max_cost_per_provider_usd,
+        false,
+        &|_| {},
+    )
+    .await
+}
+
+/// Delay before retry number `retry` (0-based): exponential backoff from
+/// `RETRY_BASE_DELAY`, capped at `RETRY_MAX_DELAY`, plus up to as much again
+/// in random jitter so concurrent retries spread out
+fn retry_backoff(retry: u32) -> Duration {
+    let delay = RETRY_BASE_DELAY
+        .saturating_mul(2u32.saturating_pow(retry))
+        .min(RETRY_MAX_DELAY);
+    delay + delay.mul_f64(fastrand::f64())
+}
+
+/// Benchmark a single provider, launching no new iterations after `deadline`
+/// or once measured iterations have cost `budget_usd`
+///
+/// Iterations still in flight at the deadline are abandoned, and the result
+/// is marked truncated; one still in flight when the budget runs out is
+/// abandoned too, and the result marked cost-capped. Completed and failed
+/// iterations are reported to `progress` as they finish.
+///
+/// `preloaded` marks the model as just loaded by a preload, so the first
+/// iteration's residual load time is not reported as a cold load.
+async fn benchmark_until(
+    provider: &dyn InferenceProvider,
+    config: &BenchmarkConfig,
+    deadline: Option<Instant>,
+    budget_usd: Option<f64>,
+    preloaded: bool,
+    progress: &(dyn Fn(&ProgressEvent) + Sync),
+) -> BenchmarkResult {
+    let expired = || deadline.is_some_and(|d| Instant::now() >= d);
+
+    // Check availability first (this may also resolve the provider's model)
+    if !provider.is_available().await {
+        let model = config
+            .model_for(provider)
+            .unwrap_or(provider.default_model());
+        return failed_result(
+            provider,
+            config,
+            model,
+            "Provider not available".to_string(),
+        );
+    }
+
+    let Some(model) = config.model_for(provider) else {
+        let error = ProviderError::Unsupported(format!(
+            "{} has no embeddings endpoint",
+            provider.display_name()
+        ));
+        return failed_result(
+            provider,
+            config,
+            provider.default_model(),
+            error.to_string(),
+        );
+    };
+    let model = model.to_string();
+    let prices = config.pricing_for(provider, &model);
+
+    let mut raw_results = Vec::new();
+    let mut errors = Vec::new();
+    let mut output_sample = None;
+    // Warmup requests would absorb the residual load time instead
+    let preloaded = preloaded && config.warmup == 0;
+
+    // Warm up connections and caches; these runs are not measured
+    for i in 0..config.warmup {
+        if expired() {
+            break;
+        }
+        if let Err(e) = run_iteration(provider, config, i, prices, None).await {
+            tracing::warn!(
+                provider = provider.name(),
+                warmup = i + 1,
+                error = %redact(&e.to_string()),
+                "warmup failed"
+            );
+        }
+    }
+
+    // Run benchmark iterations, up to the provider's concurrency in flight at once
+    let mut attempts = stream::iter(0..config.iteration_cap())
+        .map(|i| {
+            let delay = config.ramp_up_offset(provider.name(), i);
 
-            match provider.infer(&request).await {
-                Ok(response) => {
-                    let result =
-                        SingleRunResult::from_response(&response, input_price, output_price);
-                    raw_results.push(result);
+            async move {
+                if !delay.is_zero() {
+                    tokio::time::sleep(delay).await;
                 }
-                Err(e) => {
-                    errors.push(format!("Iteration {}: {}", i + 1, e));
-                    // For rate limiting, stop trying
-                    if matches!(e, ProviderError::RateLimited) {
-                        errors.push("Stopping due to rate limiting".to_string());
-                        break;
+                if expired() {
+                    return (i, None);
+                }
+                let mut retries_left = config.max_retries;
+                // Every attempt of an iteration shares one key, so providers
+                // that honor it bill a retried request once
+                let idempotency_key = (config.max_retries > 0).then(|| Uuid::new_v4().to_string());
+                loop {
+                    match run_iteration(provider, config, i, prices, idempotency_key.as_deref())
+                        .await
+                    {
+                        Err(
+                            e @ (ProviderError::Network(_)
+                            | ProviderError::Timeout(_)
+                            | ProviderError::StreamStalled(_)),
+                        ) if retries_left > 0 => {
+                            let backoff = retry_backoff(config.max_retries - retries_left);
+                            retries_left -= 1;
+                            tracing::warn!(
+                                provider = provider.name(),
+                                iteration = i + 1,
+                                error = %redact(&e.to_string()),
+                                backoff_ms = backoff.as_millis() as u64,
+                                "retrying iteration"
+                            );
+                            tokio::time::sleep(backoff).await;
+                        }
+                        outcome => return (i, Some(outcome)),
                     }
                 }
             }
+        })
+        .buffer_unordered(config.concurrency_for(provider.name()) as usize);
+
+    let mut truncated = false;
+    let mut cost_capped = false;
+    let mut spent_usd = 0.0;
+    let mut attempted = 0;
+    loop {
+        let next = match deadline {
+            Some(deadline) => match tokio::time::timeout_at(deadline, attempts.next()).await {
+                Ok(next) => next,
+                Err(_) => {
+                    // Budget spent: drop in-flight requests
+                    truncated = true;
+                    break;
+                }
+            },
+            None => attempts.next().await,
+        };
+        let Some((i, outcome)) = next else {
+            break;
+        };
+        // Budget spent before this iteration started
+        let Some(outcome) = outcome else {
+            truncated = true;
+            break;
+        };
+        attempted += 1;
+        match outcome {
+            Ok(mut result) => {
+                // The model is already resident; residual load time is not a cold load
+                if preloaded && i == 0 {
+                    result.model_load_time_ms = None;
+                }
+                result.cold = config.report == ReportMode::ColdWarm && i == 0;
+                let text = result.output_text.take();
+                if output_sample.is_none() {
+                    output_sample = text;
+                }
+                tracing::info!(
+                    provider = provider.name(),
+                    iteration = i + 1,
+                    time_to_prompt_ms = result.time_to_prompt_ms,
+                    ttft_ms = result.time_to_first_token_ms,
+                    output_tokens = result.output_tokens,
+                    latency_ms = result.total_latency_ms,
+                    tokens_per_sec = format!("{:.1}", result.tokens_per_sec()),
+                    "iteration complete"
+                );
+                progress(&ProgressEvent::IterationComplete {
+                    provider: provider.name().to_string(),
+                    iteration: i,
+                    ttft_ms: result.time_to_first_token_ms,
+                    tokens_per_sec: result.tokens_per_sec(),
+                });
+                spent_usd += result.cost_usd;
+                raw_results.push(result);
+                // Budget spent: stop launching iterations (drops any in flight)
+                if budget_usd.is_some_and(|budget| spent_usd >= budget) {
+                    tracing::warn!(
+                        provider = provider.name(),
+                        spent_usd,
+                        iterations = raw_results.len(),
+                        "cost cap reached"
+                    );
+                    cost_capped = true;
+                    break;
+                }
+                // Stable enough: stop launching iterations (drops any in flight)
+                if config.reached_target_cv(&raw_results) {
+                    tracing::info!(
+                        provider = provider.name(),
+                        iterations = raw_results.len(),
+                        "target coefficient of variation reached"
+                    );
+                    break;
+                }
+            }
+            Err(e) => {
+                // Error bodies can echo the request, key included
+                let message = redact(&e.to_string());
+                tracing::warn!(
+                    provider = provider.name(),
+                    iteration = i + 1,
+                    error = %message,
+                    "iteration failed"
+                );
+                progress(&ProgressEvent::IterationFailed {
+                    provider: provider.name().to_string(),
+                    iteration: i,
+                    error: message.clone(),
+                });
+                errors.push(format!("Iteration {}: {}", i + 1, message));
+                // For rate limiting, stop trying (drops any in-flight requests)
+                if matches!(e, ProviderError::RateLimited) {
+                    errors.push("Stopping due to rate limiting".to_string());
+                    break;
+                }
+                // Every further request would be rejected the same way
+                if matches!(e, ProviderError::InvalidApiKey { .. }) {
+                    break;
+                }
+            }
         }
+    }
 
-        BenchmarkResult {
-            provider: provider.name().to_string(),
-            display_name: provider.display_name().to_string(),
-            model: provider.default_model().to_string(),
-            metrics: AggregatedMetrics::from_raw(&raw_results),
-            raw_results,
-            errors,
-            timestamp: Utc::now(),
+    if truncated {
+        tracing::warn!(
+            provider = provider.name(),
+            completed = raw_results.len(),
+            iterations = config.iteration_cap(),
+            "max duration reached, reporting partial results"
+        );
+    }
+
+    // Only iteration 1 meets a cold model, so a later run cannot stand in for it
+    if config.report == ReportMode::ColdWarm
+        && !raw_results.is_empty()
+        && !raw_results.iter().any(|r| r.cold)
+    {
+        errors.push("Cold start not captured: iteration 1 did not succeed".to_string());
+    }
+
+    let status = ResultStatus::new(!raw_results.is_empty(), !errors.is_empty());
+    BenchmarkResult {
+        provider: provider.name().to_string(),
+        display_name: provider.display_name().to_string(),
+        model,
+        base_url: provider.endpoint().map(str::to_string),
+        mode: config.mode,
+        metrics: AggregatedMetrics::from_raw_trimmed(&raw_results, config.trim_outliers)
+            .with_attempts(attempted),
+        cold_warm: ColdWarmMetrics::from_raw(&raw_results, config.trim_outliers),
+        raw_results,
+        requested_output_tokens: config.output_tokens,
+        errors,
+        status,
+        preload_time_ms: None,
+        corpus_size: config.corpus_size(),
+        truncated,
+        cost_capped,
+        run_spread: None,
+        non_streaming: config.sends_unstreamed(provider),
+        turn: config.turn,
+        output_sample,
+        timestamp: Utc::now(),
+    }
+}
+
+/// Send one measured request for an iteration in the configured mode
+pub(super) async fn run_iteration(
+    provider: &dyn InferenceProvider,
+    config: &BenchmarkConfig,
+    iteration: u32,
+    (input_price, output_price): (f64, f64),
+    idempotency_key: Option<&str>,
+) -> Result<SingleRunResult, ProviderError> {
+    match config.mode {
+        BenchmarkMode::Chat => {
+            let mut request = config.request(iteration);
+            request.idempotency_key = idempotency_key.map(str::to_string);
+            let mut response = provider.infer(&request).await?;
+            // Usage never arrived (the server ignores stream_options, or has
+            // none); the history is billed as input too
+            if response.input_tokens == 0 {
+                response.input_tokens = request
+                    .history
+                    .iter()
+                    .map(|m| estimate_tokens(&m.content))
+                    .sum::<u32>()
+                    + estimate_tokens(&request.prompt);
+            }
+            if !config.allow_empty {
+                response.validate()?;
+            }
+            if config.tools && !response.tool_call {
+                return Err(ProviderError::NoToolCall);
+            }
+            let mut run = SingleRunResult::from_response(&response, input_price, output_price);
+            // Reaching a forced --output-tokens length is the point, not a cap
+            run.hit_max_tokens = config.output_tokens.is_none()
+                && (response.truncated
+                    || run.output_tokens + run.reasoning_tokens >= request.max_tokens);
+            if config.capture_output {
+                run.output_text = Some(response.text);
+            }
+            Ok(run)
+        }
+        BenchmarkMode::Embeddings => provider
+            .embed(&config.embedding_request(iteration))
+            .await
+            .map(|r| SingleRunResult::from_embedding(&r, input_price)),
+    }
+}
+
+/// Result for a provider that could not be benchmarked at all
+fn failed_result(
+    provider: &dyn InferenceProvider,
+    config: &BenchmarkConfig,
+    model: &str,
+    error: String,
+) -> BenchmarkResult {
+    BenchmarkResult {
+        provider: provider.name().to_string(),
+        display_name: provider.display_name().to_string(),
+        model: model.to_string(),
+        base_url: provider.endpoint().map(str::to_string),
+        mode: config.mode,
+        metrics: AggregatedMetrics::from_raw(&[]),
+        raw_results: Vec::new(),
+        requested_output_tokens: config.output_tokens,
+        errors: vec![redact(&error)],
+        status: ResultStatus::Failed,
+        preload_time_ms: None,
+        corpus_size: config.corpus_size(),
+        truncated: false,
+        cost_capped: false,
+        run_spread: None,
+        cold_warm: None,
+        non_streaming: config.sends_unstreamed(provider),
+        turn: config.turn,
+        output_sample: None,
+        timestamp: Utc::now(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::mock_server::{MOCK_API_KEY, MockResponse, MockServer, openai_provider};
+    use async_trait::async_trait;
+
+    /// Provider that is never called, priced at $1M per million tokens;
+    /// accepts unstreamed requests
+    struct PricedProvider;
+
+    #[async_trait]
+    impl InferenceProvider for PricedProvider {
+        fn name(&self) -> &str {
+            "priced"
+        }
+
+        fn display_name(&self) -> &str {
+            "Priced"
+        }
+
+        async fn is_available(&self) -> bool {
+            false
+        }
+
+        async fn infer(
+            &self,
+            _request: &InferenceRequest,
+        ) -> Result<InferenceResponse, ProviderError> {
+            unreachable!()
+        }
+
+        fn default_model(&self) -> &str {
+            "model"
+        }
+
+        fn pricing_for_model(&self, _model: &str) -> (f64, f64) {
+            (1_000_000.0, 1_000_000.0)
+        }
+
+        fn supports_non_streaming(&self) -> bool {
+            true
         }
     }
 
-    /// Get the test prompt based on configuration
-    fn get_prompt(&self) -> &'static TestPrompt {
-        match self.config.prompt_size {
-            PromptSize::Short => &SHORT_PROMPT,
-            PromptSize::Medium => &MEDIUM_PROMPT,
-            PromptSize::Long => &LONG_PROMPT,
+    /// Available provider whose requests each take 400ms
+    struct SlowProvider;
+
+    #[async_trait]
+    impl InferenceProvider for SlowProvider {
+        fn name(&self) -> &str {
+            "slow"
+        }
+
+        fn display_name(&self) -> &str {
+            "Slow"
+        }
+
+        async fn is_available(&self) -> bool {
+            true
+        }
+
+        async fn infer(
+            &self,
+            _request: &InferenceRequest,
+        ) -> Result<InferenceResponse, ProviderError> {
+            tokio::time::sleep(Duration::from_millis(400)).await;
+            Ok(InferenceResponse {
+                text: "Slow but steady".to_string(),
+                output_tokens: 10,
+                total_latency_ms: 400,
+                ..Default::default()
+            })
+        }
+
+        fn default_model(&self) -> &str {
+            "model"
+        }
+
+        fn pricing_for_model(&self, _model: &str) -> (f64, f64) {
+            (0.0, 0.0)
         }
     }
 
-    /// Estimate total cost for the benchmark run
-    pub fn estimate_cost(&self) -> f64 {
-        let prompt = self.get_prompt();
-        let mut total = 0.0;
+    #[tokio::test(start_paused = true)]
+    async fn test_max_duration_truncates_to_completed_iterations() {
+        let provider = SlowProvider;
+        let config = BenchmarkConfig {
+            iterations: 10,
+            max_duration_secs: Some(1),
+            ..Default::default()
+        };
 
-        for provider in &self.providers {
-            let (input_price, output_price) = provider.pricing_per_million();
-            let per_run = prompt.estimate_cost(input_price, output_price);
-            total += per_run * self.config.iterations as f64;
+        let result = benchmark_single(&provider, &config).await;
+
+        // Iterations end at 400ms and 800ms; the third is abandoned at 1s
+        assert!(result.truncated);
+        assert_eq!(result.raw_results.len(), 2);
+        assert!(result.errors.is_empty());
+        assert_eq!(result.metrics.run_count, 2);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_target_cv_stops_once_stable() {
+        let provider = SlowProvider;
+        let mut config = BenchmarkConfig {
+            iterations: 1,
+            target_cv: Some(0.05),
+            ..Default::default()
+        };
+
+        // Identical runs: stable as soon as there are enough to judge
+        let result = benchmark_single(&provider, &config).await;
+        assert_eq!(result.raw_results.len(), MIN_CV_SAMPLES);
+        assert!(!result.truncated);
+
+        // --iterations is still the minimum
+        config.iterations = 5;
+        let result = benchmark_single(&provider, &config).await;
+        assert_eq!(result.raw_results.len(), 5);
+        assert_eq!(config.iteration_cap(), DEFAULT_MAX_ITERATIONS);
+    }
+
+    /// Available provider billing $0.01 per request, streamed or not
+    struct CostlyProvider;
+
+    #[async_trait]
+    impl InferenceProvider for CostlyProvider {
+        fn name(&self) -> &str {
+            "costly"
+        }
+
+        fn display_name(&self) -> &str {
+            "Costly"
+        }
+
+        async fn is_available(&self) -> bool {
+            true
+        }
+
+        async fn infer(
+            &self,
+            _request: &InferenceRequest,
+        ) -> Result<InferenceResponse, ProviderError> {
+            Ok(InferenceResponse {
+                text: "Worth every cent".to_string(),
+                output_tokens: 10,
+                total_latency_ms: 100,
+                ..Default::default()
+            })
+        }
+
+        fn default_model(&self) -> &str {
+            "model"
+        }
+
+        fn pricing_for_model(&self, _model: &str) -> (f64, f64) {
+            (0.0, 1000.0)
+        }
+
+        fn supports_non_streaming(&self) -> bool {
+            true
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cost_cap_stops_provider_across_runs() {
+        let provider = CostlyProvider;
+        let config = BenchmarkConfig {
+            iterations: 3,
+            runs: 3,
+            max_cost_per_provider_usd: Some(0.045),
+            ..Default::default()
+        };
+
+        let results = BenchmarkRunner::new(vec![&provider], config).run().await;
+
+        // Run 1 spends $0.03, run 2 stops after crossing $0.045, run 3 is skipped
+        let result = &results[0];
+        assert!(result.cost_capped);
+        assert!(!result.truncated);
+        assert_eq!(result.raw_results.len(), 5);
+        assert_eq!(
+            result
+                .run_spread
+                .as_ref()
+                .unwrap()
+                .per_run_tokens_per_sec
+                .len(),
+            2
+        );
+    }
+
+    #[tokio::test]
+    async fn test_cost_cap_is_shared_across_rows() {
+        let provider = CostlyProvider;
+        let config = BenchmarkConfig {
+            iterations: 2,
+            stream: StreamMode::Both,
+            max_cost_per_provider_usd: Some(0.025),
+            ..Default::default()
+        };
+
+        let results = BenchmarkRunner::new(vec![&provider], config).run().await;
+
+        // The first row spends $0.02, leaving the second one iteration
+        assert_eq!(results[0].raw_results.len(), 2);
+        assert!(!results[0].cost_capped);
+        assert_eq!(results[1].raw_results.len(), 1);
+        assert!(results[1].cost_capped);
+    }
+
+    #[tokio::test]
+    async fn test_spent_cost_cap_reports_capped_result() {
+        let provider = CostlyProvider;
+        let config = BenchmarkConfig {
+            max_cost_per_provider_usd: Some(0.0),
+            ..Default::default()
+        };
+
+        let results = BenchmarkRunner::new(vec![&provider], config).run().await;
+
+        assert_eq!(results.len(), 1);
+        assert!(results[0].cost_capped);
+        assert!(results[0].raw_results.is_empty());
+        assert_eq!(results[0].errors, ["Cost cap reached"]);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_cold_warm_splits_first_iteration() {
+        let provider = SlowProvider;
+        let config = BenchmarkConfig {
+            iterations: 3,
+            report: ReportMode::ColdWarm,
+            ..Default::default()
+        };
+
+        let result = benchmark_single(&provider, &config).await;
+
+        assert_eq!(result.raw_results.iter().filter(|r| r.cold).count(), 1);
+        let split = result.cold_warm.unwrap();
+        assert_eq!(split.cold.run_count, 1);
+        assert_eq!(split.warm.run_count, 2);
+        assert_eq!(result.metrics.run_count, 3);
+    }
+
+    #[tokio::test]
+    async fn test_cold_warm_notes_failed_first_iteration() {
+        let done = MockResponse::sse(&[
+            r#"{"choices":[{"delta":{"content":"Hi"}}]}"#,
+            r#"{"choices":[],"usage":{"prompt_tokens":3,"completion_tokens":1}}"#,
+            "[DONE]",
+        ]);
+        let failure = MockResponse::status(500, r#"{"error":"overloaded"}"#);
+        let server = MockServer::start(vec![MockResponse::probe(), failure, done]).await;
+        let provider = openai_provider(&server);
+        let config = BenchmarkConfig {
+            iterations: 2,
+            report: ReportMode::ColdWarm,
+            ..Default::default()
+        };
+
+        let result = benchmark_single(&provider, &config).await;
+
+        assert_eq!(result.raw_results.len(), 1);
+        assert!(!result.raw_results[0].cold);
+        assert!(result.cold_warm.is_none());
+        assert_eq!(
+            result.errors.last().unwrap(),
+            "Cold start not captured: iteration 1 did not succeed"
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_progress_reports_each_iteration() {
+        let slow = SlowProvider;
+        let unavailable = PricedProvider;
+        let config = BenchmarkConfig {
+            iterations: 2,
+            ..Default::default()
+        };
+        let events = std::sync::Mutex::new(Vec::new());
+
+        BenchmarkRunner::new(vec![&slow, &unavailable], config)
+            .with_progress(|event| events.lock().unwrap().push(event.clone()))
+            .run()
+            .await;
+
+        let events = events.into_inner().unwrap();
+        let completed = events
+            .iter()
+            .filter(|e| matches!(e, ProgressEvent::IterationComplete { provider, .. } if provider == "slow"))
+            .count();
+        assert_eq!(completed, 2);
+        assert_eq!(
+            events.last(),
+            Some(&ProgressEvent::ProviderFinished {
+                provider: "priced".to_string(),
+                skipped: Some("Provider not available".to_string()),
+            })
+        );
+    }
+
+    /// Providers in the order they were started over 4 shuffled runs
+    async fn shuffled_start_order(seed: u64) -> Vec<String> {
+        let config = BenchmarkConfig {
+            runs: 4,
+            shuffle_seed: Some(seed),
+            ..Default::default()
+        };
+        let started = std::sync::Mutex::new(Vec::new());
+        let results = BenchmarkRunner::new(
+            vec![&SlowProvider, &CostlyProvider, &PricedProvider],
+            config,
+        )
+        .with_progress(|event| {
+            if let ProgressEvent::ProviderStarted { provider, .. } = event {
+                started.lock().unwrap().push(provider.clone());
+            }
+        })
+        .run()
+        .await;
+        // Results keep the given order
+        let names: Vec<String> = results.into_iter().map(|r| r.provider).collect();
+        assert_eq!(names, ["slow", "costly", "priced"]);
+        started.into_inner().unwrap()
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_shuffle_is_seeded_and_keeps_result_order() {
+        let order = shuffled_start_order(7).await;
+        assert_eq!(order.len(), 12);
+        assert_eq!(order, shuffled_start_order(7).await);
+        assert!(
+            order
+                .chunks(3)
+                .any(|run| run != ["slow", "costly", "priced"])
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_runs_reaching_max_tokens_are_flagged() {
+        let provider = SlowProvider;
+        let config = BenchmarkConfig {
+            custom_prompt: Some(TestPrompt::custom("tiny", "Hi".to_string(), Some(5))),
+            max_tokens_buffer: 5,
+            ..Default::default()
+        };
+        assert_eq!(config.max_tokens(), 10);
+
+        let result = benchmark_single(&provider, &config).await;
+        assert_eq!(result.max_tokens_hits(), 1);
+
+        // A forced output length is expected to reach the cap
+        let forced = BenchmarkConfig {
+            output_tokens: Some(10),
+            ..config
+        };
+        let result = benchmark_single(&provider, &forced).await;
+        assert_eq!(result.max_tokens_hits(), 0);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_missing_input_usage_is_estimated() {
+        let provider = SlowProvider;
+        let config = BenchmarkConfig::default();
+
+        let result = benchmark_single(&provider, &config).await;
+
+        assert_eq!(
+            result.raw_results[0].input_tokens,
+            estimate_tokens(&config.prompt().text)
+        );
+
+        // A later conversation turn counts its history as well
+        let message = |role: &str, content: &str| ChatMessage {
+            role: role.to_string(),
+            content: content.to_string(),
+        };
+        let config = BenchmarkConfig {
+            conversation: vec![
+                message("user", "Plan a trip to the coast"),
+                message("assistant", "Day 1: the castle. Day 2: the harbor."),
+                message("user", "Make it shorter"),
+            ],
+            turn: Some(2),
+            ..Default::default()
+        };
+
+        let result = benchmark_single(&provider, &config).await;
+
+        let input_tokens = result.raw_results[0].input_tokens;
+        assert_eq!(input_tokens, config.input_tokens());
+        assert!(input_tokens > estimate_tokens("Make it shorter"));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_capture_output_keeps_first_answer() {
+        let provider = SlowProvider;
+        let config = BenchmarkConfig {
+            iterations: 2,
+            ..Default::default()
+        };
+        assert_eq!(
+            benchmark_single(&provider, &config).await.output_sample,
+            None
+        );
+
+        let config = BenchmarkConfig {
+            capture_output: true,
+            ..config
+        };
+        let result = benchmark_single(&provider, &config).await;
+        assert_eq!(result.output_sample.as_deref(), Some("Slow but steady"));
+        assert!(result.raw_results.iter().all(|r| r.output_text.is_none()));
+    }
+
+    #[tokio::test]
+    async fn test_dropped_stream_is_retried() {
+        let done = MockResponse::sse(&[
+            r#"{"choices":[{"delta":{"content":"Hi"}}]}"#,
+            r#"{"choices":[],"usage":{"prompt_tokens":3,"completion_tokens":1}}"#,
+            "[DONE]",
+        ]);
+        let server = MockServer::start(vec![
+            // Availability probe
+            MockResponse::probe(),
+            done.clone().abort_mid_body(),
+            done,
+        ])
+        .await;
+        let provider = openai_provider(&server);
+        let config = BenchmarkConfig {
+            max_retries: 1,
+            ..Default::default()
+        };
+
+        let result = benchmark_single(&provider, &config).await;
+
+        assert!(result.errors.is_empty(), "{:?}", result.errors);
+        assert_eq!(result.raw_results.len(), 1);
+        assert_eq!(result.raw_results[0].output_tokens, 1);
+        let requests = server.requests();
+        assert_eq!(requests.len(), 3);
+        assert!(requests[0].starts_with("GET /v1/models "));
+        assert!(requests[2].starts_with("POST /v1/chat/completions "));
+
+        // Both attempts carry the same idempotency key
+        let key = |request: &str| {
+            request
+                .lines()
+                .find_map(|line| line.strip_prefix("idempotency-key: "))
+                .map(str::to_string)
+        };
+        assert!(key(&requests[1]).is_some());
+        assert_eq!(key(&requests[1]), key(&requests[2]));
+        assert!(result.raw_results[0].idempotent);
+    }
+
+    #[tokio::test]
+    async fn test_empty_response_is_a_failure() {
+        let refusal = MockResponse::sse(&[
+            r#"{"choices":[{"delta":{"content":""},"finish_reason":"content_filter"}]}"#,
+            r#"{"choices":[],"usage":{"prompt_tokens":3,"completion_tokens":0}}"#,
+            "[DONE]",
+        ]);
+        let probe = MockResponse::probe();
+        let server = MockServer::start(vec![probe.clone(), refusal.clone(), probe, refusal]).await;
+        let provider = openai_provider(&server);
+
+        let result = benchmark_single(&provider, &BenchmarkConfig::default()).await;
+        assert!(result.raw_results.is_empty());
+        assert_eq!(result.status, ResultStatus::Failed);
+        assert!(
+            result.errors[0].contains("Empty response"),
+            "{:?}",
+            result.errors
+        );
+
+        let config = BenchmarkConfig {
+            allow_empty: true,
+            ..Default::default()
+        };
+        let result = benchmark_single(&provider, &config).await;
+        assert!(result.errors.is_empty(), "{:?}", result.errors);
+        assert_eq!(result.status, ResultStatus::Ok);
+        assert_eq!(result.raw_results.len(), 1);
+        // The endpoint is recorded with the result
+        assert_eq!(result.base_url, Some(format!("{}/v1", server.base_url())));
+    }
+
+    #[tokio::test]
+    async fn test_keys_in_error_bodies_are_redacted() {
+        let key = MOCK_API_KEY;
+        let echo = MockResponse::status(
+            400,
+            &format!(
+                r#"{{"error":"bad request","headers":{{"authorization":"Bearer {}"}}}}"#,
+                key
+            ),
+        );
+        let probe = MockResponse::probe();
+        let server = MockServer::start(vec![probe, echo]).await;
+        let provider = openai_provider(&server);
+
+        let result = benchmark_single(&provider, &BenchmarkConfig::default()).await;
+        assert_eq!(result.errors.len(), 1);
+        assert!(result.errors[0].contains("HTTP 400"), "{:?}", result.errors);
+        assert!(!result.errors[0].contains(key), "{:?}", result.errors);
+        assert!(result.errors[0].contains("[REDACTED]"));
+    }
+
+    #[tokio::test]
+    async fn test_tools_require_a_tool_call() {
+        let call = MockResponse::sse(&[
+            r#"{"choices":[{"delta":{"tool_calls":[{"index":0,"function":{"name":"get_weather","arguments":"{\"city\":\"Paris\"}"}}]},"finish_reason":"tool_calls"}]}"#,
+            r#"{"choices":[],"usage":{"prompt_tokens":90,"completion_tokens":8}}"#,
+            "[DONE]",
+        ]);
+        let answer = MockResponse::sse(&[
+            r#"{"choices":[{"delta":{"content":"It is sunny."}}]}"#,
+            r#"{"choices":[],"usage":{"prompt_tokens":90,"completion_tokens":4}}"#,
+            "[DONE]",
+        ]);
+        let probe = MockResponse::probe();
+        let server = MockServer::start(vec![probe.clone(), call, probe, answer]).await;
+        let provider = openai_provider(&server);
+        let config = BenchmarkConfig {
+            tools: true,
+            ..Default::default()
+        };
+
+        let result = benchmark_single(&provider, &config).await;
+        assert!(result.errors.is_empty(), "{:?}", result.errors);
+        assert_eq!(result.raw_results[0].output_tokens, 8);
+        let request = &server.requests()[1];
+        assert!(request.contains(r#""name":"get_weather""#), "{}", request);
+        assert!(request.contains("get_weather tool"), "{}", request);
+
+        // A plain answer is not the workload being measured
+        let result = benchmark_single(&provider, &config).await;
+        assert!(result.raw_results.is_empty());
+        assert!(
+            result.errors[0].contains("without calling"),
+            "{:?}",
+            result.errors
+        );
+    }
+
+    #[tokio::test]
+    async fn test_stream_both_reports_each_way() {
+        let streamed = MockResponse::sse(&[
+            r#"{"choices":[{"delta":{"content":"Hi there"}}]}"#,
+            r#"{"choices":[],"usage":{"prompt_tokens":3,"completion_tokens":2}}"#,
+            "[DONE]",
+        ]);
+        let whole = MockResponse::status(
+            200,
+            r#"{"choices":[{"message":{"content":"Hi there"},"finish_reason":"stop"}],
+                "usage":{"prompt_tokens":3,"completion_tokens":2}}"#,
+        )
+        .header("Content-Type", "application/json");
+        let probe = MockResponse::probe();
+        let server = MockServer::start(vec![probe.clone(), streamed, probe, whole]).await;
+        let provider = openai_provider(&server);
+        let config = BenchmarkConfig {
+            stream: StreamMode::Both,
+            ..Default::default()
+        };
+        let results = BenchmarkRunner::new(vec![&provider], config).run().await;
+
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].display_name, "OpenAI Compatible (stream)");
+        assert_eq!(results[1].display_name, "OpenAI Compatible (no stream)");
+        assert!(!results[0].non_streaming && results[1].non_streaming);
+        for result in &results {
+            assert!(result.errors.is_empty(), "{:?}", result.errors);
+            assert_eq!(result.raw_results[0].output_tokens, 2);
+        }
+        let requests = server.requests();
+        assert!(requests[1].contains(r#""stream":true"#));
+        assert!(requests[3].contains(r#""stream":false"#));
+        assert!(!requests[3].contains("stream_options"));
+    }
+
+    #[tokio::test]
+    async fn test_conversation_turns_carry_history() {
+        let answer = MockResponse::sse(&[
+            r#"{"choices":[{"delta":{"content":"Sure."}}]}"#,
+            r#"{"choices":[],"usage":{"prompt_tokens":20,"completion_tokens":2}}"#,
+            "[DONE]",
+        ]);
+        let probe = MockResponse::probe();
+        let server = MockServer::start(vec![probe.clone(), answer.clone(), probe, answer]).await;
+        let provider = openai_provider(&server);
+        let message = |role: &str, content: &str| ChatMessage {
+            role: role.to_string(),
+            content: content.to_string(),
+        };
+        let config = BenchmarkConfig {
+            conversation: vec![
+                message("user", "Plan a trip"),
+                message("assistant", "Day 1: the castle"),
+                message("user", "Make it shorter"),
+            ],
+            ..Default::default()
+        };
+        let runner = BenchmarkRunner::new(vec![&provider], config);
+        assert_eq!(runner.planned_iterations(), 2);
+        let results = runner.run().await;
+
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].display_name, "OpenAI Compatible (turn 1)");
+        assert_eq!(results[1].turn, Some(2));
+        assert!(results.iter().all(|r| r.errors.is_empty()));
+        let requests = server.requests();
+        assert!(requests[1].contains("Plan a trip") && !requests[1].contains("castle"));
+        assert!(
+            requests[3].contains(r#"{"role":"assistant","content":"Day 1: the castle"},{"role":"user","content":"Make it shorter"}"#),
+            "{}",
+            requests[3]
+        );
+    }
+
+    #[test]
+    fn test_cost_estimate_accounts_for_warmup_and_retries() {
+        let provider = PricedProvider;
+        let config = BenchmarkConfig {
+            iterations: 4,
+            warmup: 1,
+            max_retries: 2,
+            ..Default::default()
+        };
+        let prompt = config.prompt();
+        let per_run = (prompt.expected_input_tokens + prompt.expected_output_tokens) as f64;
+        let per_run_max = (prompt.expected_input_tokens + config.max_tokens()) as f64;
+
+        let estimate = BenchmarkRunner::new(vec![&provider], config).estimate_cost();
+
+        assert_eq!(estimate.min, per_run * 4.0);
+        assert_eq!(estimate.expected, per_run * 5.0);
+        assert_eq!(estimate.max, per_run_max * 15.0);
+
+        // Iterating to a target CV may run up to the cap
+        let config = BenchmarkConfig {
+            iterations: 4,
+            target_cv: Some(0.05),
+            max_iterations: 9,
+            ..Default::default()
+        };
+        let estimate = BenchmarkRunner::new(vec![&provider], config).estimate_cost();
+        assert_eq!(estimate.min, per_run * 4.0);
+        assert_eq!(estimate.max, per_run_max * 9.0);
+
+        // A cost cap bounds measured spend to the cap plus the iteration that
+        // crosses it
+        let config = BenchmarkConfig {
+            iterations: 4,
+            max_cost_per_provider_usd: Some(per_run * 1.5),
+            ..Default::default()
+        };
+        let estimate = BenchmarkRunner::new(vec![&provider], config).estimate_cost();
+        assert_eq!(estimate.min, per_run * 2.5);
+
+        // The cap covers all of a provider's rows together
+        let config = BenchmarkConfig {
+            iterations: 4,
+            stream: StreamMode::Both,
+            max_cost_per_provider_usd: Some(per_run * 1.5),
+            ..Default::default()
+        };
+        let estimate = BenchmarkRunner::new(vec![&provider], config).estimate_cost();
+        assert_eq!(estimate.min, per_run * 2.5);
+
+        // A spent cap runs nothing
+        let config = BenchmarkConfig {
+            max_cost_per_provider_usd: Some(0.0),
+            ..Default::default()
+        };
+        let estimate = BenchmarkRunner::new(vec![&provider], config).estimate_cost();
+        assert_eq!((estimate.min, estimate.max), (0.0, 0.0));
+
+        // A max_tokens sweep runs each length as its own row
+        let config = BenchmarkConfig {
+            max_tokens_sweep: vec![100, 1000],
+            ..Default::default()
+        };
+        let input = config.prompt().expected_input_tokens as f64;
+        let runner = BenchmarkRunner::new(vec![&provider], config);
+        assert_eq!(runner.planned_iterations(), 2);
+        let labels: Vec<_> = runner
+            .config
+            .variants(&provider)
+            .into_iter()
+            .map(|(config, label)| (config.max_tokens(), label.unwrap()))
+            .collect();
+        assert_eq!(
+            labels,
+            [
+                (100, "max_tokens 100".to_string()),
+                (1000, "max_tokens 1000".to_string())
+            ]
+        );
+        assert_eq!(runner.estimate_cost().min, input * 2.0 + 1100.0);
+    }
+
+    #[test]
+    fn test_cost_estimate_uses_corpus_prompt_sizes() {
+        let provider = PricedProvider;
+        let estimate = |corpus: Vec<String>| {
+            let config = BenchmarkConfig {
+                corpus,
+                ..Default::default()
+            };
+            BenchmarkRunner::new(vec![&provider], config)
+                .estimate_cost()
+                .expected
+        };
+
+        let short = estimate(vec!["Hi".to_string(); 2]);
+        let long = estimate(vec!["word ".repeat(2000); 2]);
+        assert!(long > short);
+        assert_eq!(
+            long - short,
+            (estimate_tokens(&"word ".repeat(2000)) - estimate_tokens("Hi")) as f64
+                * BenchmarkConfig::default().iterations as f64
+        );
+    }
+
+    /// Available provider that fails its first `failures` requests with a
+    /// network error, then answers every request
+    struct FlakyProvider {
+        failures: u32,
+        calls: std::sync::atomic::AtomicU32,
+    }
+
+    impl FlakyProvider {
+        fn new(failures: u32) -> Self {
+            Self {
+                failures,
+                calls: Default::default(),
+            }
+        }
+
+        /// Requests received so far
+        fn calls(&self) -> u32 {
+            self.calls.load(std::sync::atomic::Ordering::SeqCst)
+        }
+    }
+
+    #[async_trait]
+    impl InferenceProvider for FlakyProvider {
+        fn name(&self) -> &str {
+            "flaky"
+        }
+
+        fn display_name(&self) -> &str {
+            "Flaky"
+        }
+
+        async fn is_available(&self) -> bool {
+            true
         }
 
-        total
+        async fn infer(
+            &self,
+            _request: &InferenceRequest,
+        ) -> Result<InferenceResponse, ProviderError> {
+            let call = self.calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            if call < self.failures {
+                return Err(ProviderError::Network("connection reset".to_string()));
+            }
+            Ok(InferenceResponse {
+                text: "Hello".to_string(),
+                input_tokens: 10,
+                output_tokens: 5,
+                total_latency_ms: 100,
+                ..Default::default()
+            })
+        }
+
+        fn default_model(&self) -> &str {
+            "model"
+        }
+
+        fn pricing_for_model(&self, _model: &str) -> (f64, f64) {
+            (0.0, 0.0)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_warmup_requests_are_not_measured() {
+        // The first warmup absorbs the failure
+        let provider = FlakyProvider::new(1);
+        let config = BenchmarkConfig {
+            iterations: 2,
+            warmup: 2,
+            ..Default::default()
+        };
+
+        let result = benchmark_single(&provider, &config).await;
+        assert_eq!(provider.calls(), 4);
+        assert_eq!(result.raw_results.len(), 2);
+        assert!(result.errors.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_network_errors_are_retried() {
+        let provider = FlakyProvider::new(2);
+        let config = BenchmarkConfig {
+            iterations: 1,
+            max_retries: 2,
+            ..Default::default()
+        };
+        let result = benchmark_single(&provider, &config).await;
+        assert_eq!(provider.calls(), 3);
+        assert_eq!(result.raw_results.len(), 1);
+        assert!(result.errors.is_empty());
+
+        // Out of retries, the iteration fails
+        let provider = FlakyProvider::new(2);
+        let config = BenchmarkConfig {
+            max_retries: 1,
+            ..config
+        };
+        let result = benchmark_single(&provider, &config).await;
+        assert_eq!(provider.calls(), 2);
+        assert!(result.raw_results.is_empty());
+        assert_eq!(result.errors.len(), 1);
+    }
+
+    /// Available local-style provider recording the model it preloads;
+    /// every response reports 50ms of model load time
+    #[derive(Default)]
+    struct PreloadProvider {
+        preloaded: std::sync::Mutex<Option<String>>,
+    }
+
+    #[async_trait]
+    impl InferenceProvider for PreloadProvider {
+        fn name(&self) -> &str {
+            "preload"
+        }
+
+        fn display_name(&self) -> &str {
+            "Preload"
+        }
+
+        async fn is_available(&self) -> bool {
+            true
+        }
+
+        async fn infer(
+            &self,
+            _request: &InferenceRequest,
+        ) -> Result<InferenceResponse, ProviderError> {
+            Ok(InferenceResponse {
+                text: "Hello".to_string(),
+                input_tokens: 10,
+                output_tokens: 5,
+                total_latency_ms: 100,
+                model_load_time_ms: Some(50),
+                ..Default::default()
+            })
+        }
+
+        fn default_model(&self) -> &str {
+            "model"
+        }
+
+        fn pricing_for_model(&self, _model: &str) -> (f64, f64) {
+            (0.0, 0.0)
+        }
+
+        async fn preload(&self, model: &str) -> Result<Option<u64>, ProviderError> {
+            *self.preloaded.lock().unwrap() = Some(model.to_string());
+            Ok(Some(2000))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_preload_loads_override_model_and_clears_first_load_time() {
+        let provider = PreloadProvider::default();
+        let config = BenchmarkConfig {
+            iterations: 3,
+            runs: 2,
+            warmup_shared: true,
+            model: Some("override".to_string()),
+            ..Default::default()
+        };
+
+        let results = BenchmarkRunner::new(vec![&provider], config).run().await;
+
+        assert_eq!(
+            provider.preloaded.lock().unwrap().as_deref(),
+            Some("override")
+        );
+        assert_eq!(results[0].preload_time_ms, Some(2000));
+        // Only the first iteration after the preload finds the model resident
+        let load_times: Vec<_> = results[0]
+            .raw_results
+            .iter()
+            .map(|r| r.model_load_time_ms)
+            .collect();
+        assert_eq!(
+            load_times,
+            [None, Some(50), Some(50), Some(50), Some(50), Some(50)]
+        );
+    }
+
+    #[test]
+    fn test_retry_backoff_grows_exponentially_with_jitter() {
+        for (retry, base) in [(0, 200), (1, 400), (2, 800), (10, 5000)] {
+            let delay = retry_backoff(retry);
+            let base = Duration::from_millis(base);
+            assert!(
+                delay >= base && delay <= base * 2,
+                "retry {retry}: {delay:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_model_override_applies_to_every_provider() {
+        let provider = PricedProvider;
+        let mut config = BenchmarkConfig {
+            model: Some("llama-3.1-70b".to_string()),
+            ..Default::default()
+        };
+
+        assert_eq!(config.model_for(&provider), Some("llama-3.1-70b"));
+        assert_eq!(config.request(0).model.as_deref(), Some("llama-3.1-70b"));
+
+        // A provider without embeddings still cannot run them
+        config.mode = BenchmarkMode::Embeddings;
+        assert_eq!(config.model_for(&provider), None);
+    }
+
+    #[test]
+    fn test_server_timing_is_preferred() {
+        let mut response = InferenceResponse {
+            text: "Hello there".to_string(),
+            input_tokens: 10,
+            output_tokens: 100,
+            time_to_prompt_ms: 5,
+            time_to_first_token_ms: 150,
+            total_latency_ms: 1000,
+            queue_time_ms: Some(20),
+            ..Default::default()
+        };
+        let run = SingleRunResult::from_response(&response, 0.0, 0.0);
+        assert_eq!(run.time_to_first_token_ms, 150);
+        assert_eq!(run.tokens_per_sec(), 100.0);
+
+        response.server_timing = Some(ServerTiming {
+            prompt_ms: 30,
+            completion_ms: 450,
+            total_ms: 500,
+        });
+        let run = SingleRunResult::from_response(&response, 0.0, 0.0);
+        // Queue wait plus prompt processing, without the network
+        assert_eq!(run.time_to_first_token_ms, 50);
+        assert_eq!(run.tokens_per_sec(), 200.0);
+        // Words and characters share the denominator
+        assert_eq!(run.words_per_sec(), 4.0);
+        assert_eq!(run.chars_per_sec(), 22.0);
+        // Latency stays what the client saw
+        assert_eq!(run.total_latency_ms, 1000);
+        assert_eq!(AggregatedMetrics::from_raw(&[run]).server_timed_count, 1);
+    }
+
+    #[test]
+    fn test_ramp_up_offsets_evenly_spaced() {
+        let config = BenchmarkConfig {
+            concurrency: 4,
+            ramp_up_ms: 1000,
+            ..Default::default()
+        };
+        let offsets: Vec<u64> = (0..6)
+            .map(|i| config.ramp_up_offset("groq", i).as_millis() as u64)
+            .collect();
+        assert_eq!(offsets, vec![0, 250, 500, 750, 0, 0]);
+    }
+
+    #[test]
+    fn test_provider_concurrency_overrides_global() {
+        let config = BenchmarkConfig {
+            concurrency: 4,
+            provider_concurrency: [("groq".to_string(), 2), ("local".to_string(), 0)].into(),
+            ramp_up_ms: 1000,
+            ..Default::default()
+        };
+        assert_eq!(config.concurrency_for("cerebras"), 4);
+        assert_eq!(config.concurrency_for("groq"), 2);
+        assert_eq!(config.concurrency_for("local"), 1);
+        assert_eq!(config.ramp_up_offset("groq", 1), Duration::from_millis(500));
+    }
+
+    #[test]
+    fn test_ramp_up_disabled_when_sequential() {
+        let config = BenchmarkConfig {
+            ramp_up_ms: 1000,
+            ..Default::default()
+        };
+        assert_eq!(config.ramp_up_offset("groq", 0), Duration::ZERO);
+        assert_eq!(config.ramp_up_offset("groq", 3), Duration::ZERO);
     }
 }
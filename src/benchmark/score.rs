@@ -0,0 +1,189 @@
+//! Composite scores that rank providers by weighted priorities.
+
+use super::{AggregatedMetrics, BenchmarkResult};
+use serde::Deserialize;
+use std::str::FromStr;
+
+/// Relative importance of each metric in a provider's score
+///
+/// Weights need not sum to 1; each is taken as a share of their total.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ScoreWeights {
+    /// Average tokens per second (higher is better)
+    pub speed: f64,
+    /// Cost per request (lower is better)
+    pub cost: f64,
+    /// Average time to first token (lower is better)
+    pub ttft: f64,
+    /// Average total latency (lower is better)
+    pub latency: f64,
+}
+
+impl ScoreWeights {
+    /// Sum of all weights
+    pub fn total(&self) -> f64 {
+        self.speed + self.cost + self.ttft + self.latency
+    }
+}
+
+impl FromStr for ScoreWeights {
+    type Err = String;
+
+    /// Parse weights such as "speed=0.5,cost=0.3,ttft=0.2"
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut weights = Self::default();
+        for part in s.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+            let (name, value) = part
+                .split_once('=')
+                .ok_or_else(|| format!("expected METRIC=WEIGHT, got '{}'", part))?;
+            let slot = match name.trim() {
+                "speed" => &mut weights.speed,
+                "cost" => &mut weights.cost,
+                "ttft" => &mut weights.ttft,
+                "latency" => &mut weights.latency,
+                other => {
+                    return Err(format!(
+                        "unknown metric '{}' (expected speed, cost, ttft, or latency)",
+                        other
+                    ));
+                }
+            };
+            *slot = value
+                .trim()
+                .parse()
+                .ok()
+                .filter(|w: &f64| w.is_finite() && *w >= 0.0)
+                .ok_or_else(|| format!("invalid weight '{}' for {}", value, name))?;
+        }
+        if weights.total() <= 0.0 {
+            return Err("at least one weight must be positive".to_string());
+        }
+        Ok(weights)
+    }
+}
+
+impl std::fmt::Display for ScoreWeights {
+    /// Each non-zero weight as a share of the total, e.g. "speed 50%, cost 30%"
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let total = self.total();
+        let shares: Vec<String> = [
+            ("speed", self.speed),
+            ("cost", self.cost),
+            ("ttft", self.ttft),
+            ("latency", self.latency),
+        ]
+        .into_iter()
+        .filter(|(_, weight)| *weight > 0.0)
+        .map(|(name, weight)| format!("{} {:.0}%", name, weight / total * 100.0))
+        .collect();
+        write!(f, "{}", shares.join(", "))
+    }
+}
+
+/// Weighted score from 0 (worst on every weighted metric) to 1 (best), each
+/// metric min-max normalized across the successful `results`
+///
+/// None for a result with no successful runs. A metric on which every
+/// provider ties counts as best for all of them.
+pub fn score(
+    result: &BenchmarkResult,
+    results: &[BenchmarkResult],
+    weights: &ScoreWeights,
+) -> Option<f64> {
+    if !result.is_success() || weights.total() <= 0.0 {
+        return None;
+    }
+    let normalized = |metric: fn(&AggregatedMetrics) -> f64, higher_is_better: bool| {
+        let (min, max) = results
+            .iter()
+            .filter(|r| r.is_success())
+            .map(|r| metric(&r.metrics))
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), v| {
+                (lo.min(v), hi.max(v))
+            });
+        if max <= min {
+            return 1.0;
+        }
+        let position = (metric(&result.metrics) - min) / (max - min);
+        if higher_is_better {
+            position
+        } else {
+            1.0 - position
+        }
+    };
+
+    let weighted = weights.speed * normalized(|m| m.avg_tokens_per_sec, true)
+        + weights.cost * normalized(|m| m.cost_per_request_usd, false)
+        + weights.ttft * normalized(|m| m.avg_ttft_ms, false)
+        + weights.latency * normalized(|m| m.avg_latency_ms, false);
+    Some(weighted / weights.total())
+}
+
+/// Results sorted by score, best first; failed providers go last in their
+/// original order
+pub fn rank(results: Vec<BenchmarkResult>, weights: &ScoreWeights) -> Vec<BenchmarkResult> {
+    let scores: Vec<f64> = results
+        .iter()
+        .map(|r| score(r, &results, weights).unwrap_or(f64::NEG_INFINITY))
+        .collect();
+    let mut scored: Vec<(f64, BenchmarkResult)> = scores.into_iter().zip(results).collect();
+    scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+    scored.into_iter().map(|(_, result)| result).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::benchmark::SingleRunResult;
+
+    fn result(provider: &str, ttft_ms: u64, latency_ms: u64, cost_usd: f64) -> BenchmarkResult {
+        let run = SingleRunResult {
+            cost_usd,
+            ..SingleRunResult::test_run(ttft_ms, latency_ms)
+        };
+        BenchmarkResult::test_result(provider, vec![run])
+    }
+
+    #[test]
+    fn test_parse_weights() {
+        let weights: ScoreWeights = "speed=5, cost=3,ttft=2".parse().unwrap();
+        assert_eq!((weights.speed, weights.cost, weights.ttft), (5.0, 3.0, 2.0));
+        assert_eq!(weights.to_string(), "speed 50%, cost 30%, ttft 20%");
+
+        assert!("speed=0.5,quality=1".parse::<ScoreWeights>().is_err());
+        assert!("speed=-1".parse::<ScoreWeights>().is_err());
+        assert!("speed=0".parse::<ScoreWeights>().is_err());
+        assert!("speed".parse::<ScoreWeights>().is_err());
+    }
+
+    #[test]
+    fn test_rank_by_weighted_score() {
+        let mut failed = result("broken", 10, 100, 0.0);
+        failed.raw_results.clear();
+        failed.metrics = AggregatedMetrics::from_raw(&[]);
+        // Fast and pricey, slow and cheap, and in between on both
+        let results = vec![
+            failed,
+            result("slow", 300, 2000, 0.001),
+            result("fast", 100, 500, 0.010),
+            result("middle", 200, 1000, 0.003),
+        ];
+
+        let speed_first: ScoreWeights = "speed=1".parse().unwrap();
+        assert_eq!(score(&results[2], &results, &speed_first), Some(1.0));
+        assert_eq!(score(&results[1], &results, &speed_first), Some(0.0));
+        assert_eq!(score(&results[0], &results, &speed_first), None);
+
+        let order = |weights: &str| -> Vec<String> {
+            rank(results.clone(), &weights.parse().unwrap())
+                .into_iter()
+                .map(|r| r.provider)
+                .collect()
+        };
+        assert_eq!(order("speed=1"), ["fast", "middle", "slow", "broken"]);
+        assert_eq!(order("cost=1"), ["slow", "middle", "fast", "broken"]);
+        // Middle trades off best: 0.5 * 1/3 + 0.5 * 7/9 beats either extreme's 0.5
+        assert_eq!(order("speed=0.5,cost=0.5")[0], "middle");
+    }
+}
@@ -0,0 +1,92 @@
+//! Prompt templates with `{name}` variable substitution.
+//!
+//! A variable is a name of ASCII letters, digits, and underscores (not
+//! starting with a digit) wrapped in braces, e.g. `{topic}`. Any other brace,
+//! such as `{ }` in code or JSON, is kept as written.
+
+use std::collections::BTreeMap;
+use thiserror::Error;
+
+/// Errors that can occur while rendering a prompt template
+#[derive(Debug, Error, PartialEq)]
+pub enum TemplateError {
+    #[error("Unresolved prompt variable(s): {} (set with --var NAME=VALUE)", .0.join(", "))]
+    Unresolved(Vec<String>),
+}
+
+/// Replace each `{name}` in `template` with its value from `vars`
+///
+/// Every variable must resolve; all missing names are reported together.
+pub fn render_template(
+    template: &str,
+    vars: &BTreeMap<String, String>,
+) -> Result<String, TemplateError> {
+    let mut output = String::with_capacity(template.len());
+    let mut missing: Vec<String> = Vec::new();
+    let mut rest = template;
+
+    while let Some(open) = rest.find('{') {
+        output.push_str(&rest[..open]);
+        let after = &rest[open + 1..];
+        match after.find('}').map(|close| &after[..close]) {
+            Some(name) if is_variable_name(name) => {
+                match vars.get(name) {
+                    Some(value) => output.push_str(value),
+                    None if !missing.iter().any(|m| m == name) => missing.push(name.to_string()),
+                    None => {}
+                }
+                rest = &after[name.len() + 1..];
+            }
+            _ => {
+                output.push('{');
+                rest = after;
+            }
+        }
+    }
+    output.push_str(rest);
+
+    if missing.is_empty() {
+        Ok(output)
+    } else {
+        Err(TemplateError::Unresolved(missing))
+    }
+}
+
+fn is_variable_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_template() {
+        let vars: BTreeMap<String, String> = [
+            ("topic".to_string(), "AI".to_string()),
+            ("n".to_string(), "3".to_string()),
+        ]
+        .into();
+
+        assert_eq!(
+            render_template("Summarize {n} facts about {topic}: {topic}!", &vars).unwrap(),
+            "Summarize 3 facts about AI: AI!"
+        );
+        // Braces that are not variables pass through
+        assert_eq!(
+            render_template("fn f() { {topic} } {\"k\": 1} {", &vars).unwrap(),
+            "fn f() { AI } {\"k\": 1} {"
+        );
+        assert_eq!(
+            render_template("{who} met {topic} and {who} {when}", &vars),
+            Err(TemplateError::Unresolved(vec![
+                "who".to_string(),
+                "when".to_string()
+            ]))
+        );
+    }
+}
@@ -1,6 +1,10 @@
 //! Command-line interface definitions using clap.
 
-use clap::{Parser, Subcommand, ValueEnum};
+use crate::benchmark::{DEFAULT_MAX_ITERATIONS, DEFAULT_MAX_TOKENS_BUFFER, ScoreWeights};
+use crate::pricing::PricingMode;
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
 
 /// LLM inference benchmarking tool - compare speed, latency, and cost across providers
 #[derive(Parser, Debug)]
@@ -11,44 +15,584 @@ pub struct Cli {
     #[arg(short, long, global = true)]
     pub verbose: bool,
 
+    /// Print only the formatted results (implies --yes)
+    #[arg(short, long, global = true, conflicts_with = "verbose")]
+    pub quiet: bool,
+
+    /// Config file with default settings (default: ./speed-kings.toml, then
+    /// $XDG_CONFIG_HOME/speed-kings.toml)
+    #[arg(long, global = true, value_name = "PATH")]
+    pub config: Option<PathBuf>,
+
+    /// TOML file of per-model rates overriding the built-in pricing
+    #[arg(long, global = true, value_name = "PATH")]
+    pub pricing_file: Option<PathBuf>,
+
+    /// Discard cached `/models` listings (kept for 24 hours) and re-query
+    #[arg(long, global = true)]
+    pub refresh_models: bool,
+
+    /// Load environment variables (e.g. API keys) from this file instead of
+    /// ./.env; variables already set in the environment take precedence
+    #[arg(long, global = true, value_name = "PATH")]
+    pub env_file: Option<PathBuf>,
+
+    /// Disable colored output (also set by a non-empty NO_COLOR)
+    #[arg(long, global = true)]
+    pub no_color: bool,
+
     #[command(subcommand)]
     pub command: Commands,
 }
 
+impl Cli {
+    /// Whether output may use ANSI colors and styling
+    ///
+    /// Follows the https://no-color.org convention: any non-empty `NO_COLOR`
+    /// value disables color, as does `--no-color`.
+    pub fn color(&self) -> bool {
+        !self.no_color && std::env::var_os("NO_COLOR").is_none_or(|v| v.is_empty())
+    }
+}
+
+// Parsed once per process, so boxing the large benchmark variant buys nothing
+#[allow(clippy::large_enum_variant)]
 #[derive(Subcommand, Debug)]
 pub enum Commands {
     /// Run inference benchmarks across providers
-    Benchmark {
-        /// Providers to benchmark (comma-separated, or "all")
-        #[arg(short, long, default_value = "all")]
-        providers: String,
+    Benchmark(BenchmarkArgs),
 
-        /// Number of iterations per provider
-        #[arg(short, long, default_value = "1")]
-        iterations: u32,
+    /// List available providers and their status
+    List(ListArgs),
 
-        /// Test prompt size
-        #[arg(short, long, default_value = "short", value_enum)]
-        size: PromptSize,
+    /// Show pricing information for all providers
+    Pricing,
 
-        /// Output format
-        #[arg(short, long, default_value = "table", value_enum)]
-        output: OutputFormat,
+    /// Check each provider's API key and reachability with a 1-token request
+    Validate(ValidateArgs),
 
-        /// Skip cost confirmation prompt
-        #[arg(long)]
-        yes: bool,
-    },
+    /// List the models each provider can serve (LiteLLM: every routable
+    /// model and its upstream)
+    Models(ModelsArgs),
 
-    /// List available providers and their status
-    List,
+    /// Print the JSON Schema of `--output json`
+    #[cfg(feature = "schema")]
+    #[command(hide = true)]
+    Schema,
+}
 
-    /// Show pricing information for all providers
-    Pricing,
+/// Options for the `list` subcommand
+#[derive(Args, Debug, Clone)]
+pub struct ListArgs {
+    /// Print every known provider as a JSON array, with whether it is
+    /// configured, instead of the human-readable catalog
+    #[arg(long)]
+    pub json: bool,
+}
+
+/// Options for the `validate` subcommand
+#[derive(Args, Debug, Clone)]
+pub struct ValidateArgs {
+    /// Providers to validate (comma-separated, groups allowed, or "all")
+    /// [default: the config file's providers, else all]
+    #[arg(short, long)]
+    pub providers: Option<String>,
+}
+
+/// Options for the `models` subcommand
+#[derive(Args, Debug, Clone)]
+pub struct ModelsArgs {
+    /// Providers to list (comma-separated, groups allowed, or "all")
+    /// [default: the config file's providers, else all]
+    #[arg(short, long)]
+    pub providers: Option<String>,
+}
+
+/// Default --tolerance for --assert-baseline (percent)
+pub const DEFAULT_TOLERANCE_PCT: f64 = 10.0;
+
+/// Options for the `benchmark` subcommand
+#[derive(Args, Debug, Clone)]
+pub struct BenchmarkArgs {
+    /// Providers to benchmark (comma-separated, or "all") [default: all];
+    /// group aliases like "fast-chips" or "chinese" expand to their members
+    #[arg(short, long)]
+    pub providers: Option<String>,
+
+    /// Read the providers to benchmark from a file (one per line, `#`
+    /// comments allowed) instead of --providers
+    #[arg(long, value_name = "PATH", conflicts_with = "providers")]
+    pub providers_file: Option<PathBuf>,
+
+    /// Model to request from every provider, overriding their configured
+    /// models (e.g. when several serve the same open-weights model)
+    #[arg(long, value_name = "NAME")]
+    pub model: Option<String>,
+
+    /// Number of iterations per provider [default: 1]
+    #[arg(short, long)]
+    pub iterations: Option<u32>,
+
+    /// Keep iterating until the coefficient of variation of tok/s is at most
+    /// this (e.g. 0.05), treating --iterations as the minimum
+    #[arg(long, value_name = "CV")]
+    pub sample_stddev_target: Option<f64>,
+
+    /// Iteration cap for --sample-stddev-target [default: 20]
+    #[arg(long, value_name = "N", requires = "sample_stddev_target")]
+    pub max_iterations: Option<u32>,
+
+    /// Repeat the whole benchmark N times and report run-to-run spread [default: 1]
+    #[arg(long, value_name = "N")]
+    pub runs: Option<u32>,
+
+    /// Workload to benchmark: chat completions or embeddings [default: chat]
+    #[arg(long, value_enum)]
+    pub mode: Option<BenchmarkMode>,
+
+    /// Report all iterations together, or the first request (cold start)
+    /// and the rest (warm) as separate rows [default: combined]
+    #[arg(long, value_enum)]
+    pub report: Option<ReportMode>,
+
+    /// Stream responses, request them whole, or benchmark each provider both
+    /// ways as two rows; only OpenAI-style APIs can turn streaming off
+    /// [default: on]
+    #[arg(long, value_enum)]
+    pub stream: Option<StreamMode>,
+
+    /// Benchmark tool calling: offer a get_weather function with a prompt
+    /// that calls it, timing the tool_calls response (OpenAI-style APIs only)
+    #[arg(long)]
+    pub tools: bool,
+
+    /// Test prompt size [default: short]
+    #[arg(short, long, value_enum)]
+    pub size: Option<PromptSize>,
+
+    /// Read the prompt from stdin instead of using a built-in one; the cost
+    /// confirmation is then answered on the terminal (or skipped with --yes)
+    #[arg(long, conflicts_with_all = ["size", "seed_prompts"])]
+    pub prompt_stdin: bool,
+
+    /// Prompt text with `{name}` variables filled in from --var, e.g.
+    /// "Summarize the following article about {topic}"
+    #[arg(
+        long,
+        value_name = "TEXT",
+        conflicts_with_all = ["size", "seed_prompts", "prompt_stdin"]
+    )]
+    pub prompt_template: Option<String>,
+
+    /// Value for a `{name}` variable in --prompt-template, --prompt-stdin, or
+    /// --seed-prompts text (repeatable)
+    #[arg(long = "var", value_name = "NAME=VALUE", value_parser = parse_var)]
+    pub vars: Vec<(String, String)>,
+
+    /// Cycle iterations through prompts from a directory (one file per prompt)
+    /// or a JSONL file (strings or {"prompt": ...} objects)
+    #[arg(long, value_name = "PATH")]
+    pub seed_prompts: Option<PathBuf>,
+
+    /// Replay a conversation from a JSON file of {"role", "content"}
+    /// messages, benchmarking each user turn as its own row with the earlier
+    /// messages as history
+    #[arg(
+        long,
+        value_name = "PATH",
+        conflicts_with_all = ["seed_prompts", "prompt_stdin", "prompt_template", "tools"]
+    )]
+    pub conversation: Option<PathBuf>,
+
+    /// Output format [default: table]
+    #[arg(short, long, value_enum)]
+    pub output: Option<OutputFormat>,
+
+    /// Force every provider to generate this many output tokens (sets
+    /// max_tokens, and min_tokens/ignore_eos where the API supports it)
+    #[arg(long, value_name = "N")]
+    pub output_tokens: Option<u32>,
+
+    /// Headroom added to the prompt's expected output length to form
+    /// max_tokens; runs that reach it are flagged as possibly truncated
+    /// [default: 50]
+    #[arg(long, value_name = "N", conflicts_with = "output_tokens")]
+    pub max_tokens_buffer: Option<u32>,
+
+    /// Benchmark each provider at several output lengths (e.g.
+    /// 50,200,500,1000), forcing each like --output-tokens, and chart tok/s
+    /// against max_tokens
+    #[arg(
+        long,
+        value_name = "N,N,...",
+        value_delimiter = ',',
+        conflicts_with_all = ["output_tokens", "max_tokens_buffer"]
+    )]
+    pub max_tokens_sweep: Vec<u32>,
+
+    /// Maximum concurrent requests per provider [default: 1]
+    #[arg(long)]
+    pub concurrency: Option<u32>,
+
+    /// Per-provider overrides of --concurrency, e.g. "cerebras=8,groq=2"
+    #[arg(
+        long,
+        value_name = "PROVIDER=N",
+        value_delimiter = ',',
+        value_parser = parse_provider_limit
+    )]
+    pub provider_concurrency: Vec<(String, u32)>,
+
+    /// Stagger the first wave of concurrent requests over this window (ms)
+    #[arg(long, value_name = "MS")]
+    pub ramp_up_ms: Option<u64>,
+
+    /// Find each provider's rate limit instead of benchmarking: double
+    /// concurrency from 1 up to this ceiling, sending --iterations requests
+    /// per slot at each level, until a level draws HTTP 429, then report the
+    /// throughput of the level below
+    #[arg(
+        long,
+        value_name = "MAX_CONCURRENCY",
+        conflicts_with_all = [
+            "concurrency",
+            "provider_concurrency",
+            "ramp_up_ms",
+            "runs",
+            "report",
+            "sample_stddev_target",
+            "max_tokens_sweep",
+            "conversation",
+            "warmup",
+            "retries",
+            "rank",
+            "assert_baseline",
+        ]
+    )]
+    pub find_limit: Option<u32>,
+
+    /// Benchmark providers in a random order each run, to spread out bias
+    /// from shared resources warming up or throttling over the sweep;
+    /// results are still listed in the usual order
+    #[arg(long)]
+    pub shuffle: bool,
+
+    /// Seed for --shuffle, to reproduce a previous order [default: random,
+    /// printed with --verbose]
+    #[arg(long, value_name = "N", requires = "shuffle")]
+    pub seed: Option<u64>,
+
+    /// Untimed warmup requests per provider before measuring [default: 0]
+    #[arg(long, value_name = "N")]
+    pub warmup: Option<u32>,
+
+    /// Load local models once before the timed sweep so model load time is
+    /// excluded from measured runs
+    #[arg(long)]
+    pub warmup_shared: bool,
+
+    /// Retry an iteration up to N times on network errors, timeouts, or
+    /// stalled streams, backing off exponentially between attempts; all
+    /// attempts share an Idempotency-Key where supported [default: 0]
+    #[arg(long, value_name = "N")]
+    pub retries: Option<u32>,
+
+    /// Stop launching iterations after this many seconds and report the
+    /// results completed so far, marked as truncated
+    #[arg(long, value_name = "SECS")]
+    pub max_duration: Option<u64>,
+
+    /// Stop launching a provider's iterations once its measured runs have
+    /// cost this much (USD), so one expensive provider cannot use up the
+    /// budget of the rest; its results are marked as cost-capped
+    #[arg(long, value_name = "USD")]
+    pub max_cost_per_provider: Option<f64>,
+
+    /// Per-request timeout in milliseconds for every provider, replacing
+    /// their built-in defaults (60-300s)
+    #[arg(long, value_name = "MS")]
+    pub timeout: Option<u64>,
+
+    /// Open a new connection for every request instead of reusing pooled
+    /// ones, so each TTFT includes DNS, TCP, and TLS setup
+    #[arg(long, conflicts_with = "pool_idle_timeout")]
+    pub fresh_connection: bool,
+
+    /// Seconds an idle connection stays pooled for reuse [default: 90]
+    #[arg(long, value_name = "SECS")]
+    pub pool_idle_timeout: Option<u64>,
+
+    /// Abort an iteration if no response data arrives within this many ms
+    /// (streaming providers only)
+    #[arg(long, value_name = "MS")]
+    pub ttft_timeout_ms: Option<u64>,
+
+    /// Abort an iteration if a stream that has started sends nothing for
+    /// this many ms, instead of waiting for the request timeout (streaming
+    /// providers only)
+    #[arg(long, value_name = "MS")]
+    pub stall_timeout_ms: Option<u64>,
+
+    /// Rate card for cost figures: standard, cached (prompt-cache input
+    /// rates), or batch [default: standard]
+    #[arg(long, value_enum)]
+    pub pricing_mode: Option<PricingMode>,
+
+    /// Add a "% of fastest" throughput column to table and markdown output
+    #[arg(long)]
+    pub relative: bool,
+
+    /// Compare every provider's tok/s, latency, and cost against this one
+    /// in table and markdown output
+    #[arg(long, value_name = "PROVIDER")]
+    pub baseline: Option<String>,
+
+    /// Rank providers by a weighted score of min-max normalized metrics
+    /// (speed, cost, ttft, latency), e.g. "speed=0.5,cost=0.3,ttft=0.2";
+    /// adds a Score column and sorts by it
+    #[arg(long, value_name = "WEIGHTS")]
+    pub rank: Option<ScoreWeights>,
+
+    /// Columns to show, in order, in table and markdown output (e.g.
+    /// provider,tok_per_sec,cost) [default: all that apply]
+    #[arg(long, value_enum, value_delimiter = ',')]
+    pub columns: Vec<Column>,
+
+    /// Keep per-iteration raw_results in JSON output (aggregates only by default)
+    #[arg(long)]
+    pub include_raw: bool,
+
+    /// Write JSON output on a single line instead of pretty-printed
+    #[arg(long)]
+    pub json_compact: bool,
+
+    /// Count responses with no text or no output tokens as successes rather
+    /// than failures (for endpoints that legitimately return nothing)
+    #[arg(long)]
+    pub allow_empty: bool,
+
+    /// Keep each provider's first generated answer for spot-checking (in
+    /// JSON, and previewed in the table notes with --verbose)
+    #[arg(long)]
+    pub capture_output: bool,
+
+    /// Skip cost confirmation prompt
+    #[arg(long)]
+    pub yes: bool,
+
+    /// Exclude latency outliers beyond N standard deviations from averages
+    #[arg(
+        long,
+        value_name = "STDDEVS",
+        num_args = 0..=1,
+        default_missing_value = "2.0",
+        value_parser = parse_stddevs
+    )]
+    pub trim_outliers: Option<f64>,
+
+    /// Providers to skip (comma-separated), applied after --providers
+    #[arg(long, value_delimiter = ',')]
+    pub exclude: Vec<String>,
+
+    /// Check availability up front and quietly skip unreachable providers
+    #[arg(long)]
+    pub only_available: bool,
+
+    /// Show a live dashboard while benchmarking, then print the results
+    #[cfg(feature = "tui")]
+    #[arg(long)]
+    pub tui: bool,
+
+    /// Fail (exit code 3) if any provider's tok/s or TTFT regressed beyond
+    /// --tolerance against a saved `--output json` result
+    #[arg(long, value_name = "PATH")]
+    pub assert_baseline: Option<PathBuf>,
+
+    /// Allowed regression for --assert-baseline, e.g. "10%" [default: 10%]
+    #[arg(long, value_name = "PCT", value_parser = parse_tolerance, requires = "assert_baseline")]
+    pub tolerance: Option<f64>,
+
+    /// Exit with code 1 if any provider failed (exit code 2 is always used
+    /// when every provider failed)
+    #[arg(long)]
+    pub fail_on_error: bool,
+}
+
+impl BenchmarkArgs {
+    /// Provider filter, defaulting to all providers
+    pub fn providers(&self) -> &str {
+        self.providers.as_deref().unwrap_or("all")
+    }
+
+    /// Iterations per provider, defaulting to 1
+    pub fn iterations(&self) -> u32 {
+        self.iterations.unwrap_or(1)
+    }
+
+    /// Allowed regression for --assert-baseline as a fraction, defaulting to 10%
+    pub fn tolerance(&self) -> f64 {
+        self.tolerance.unwrap_or(DEFAULT_TOLERANCE_PCT) / 100.0
+    }
+
+    /// Iteration cap for --sample-stddev-target, defaulting to 20
+    pub fn max_iterations(&self) -> u32 {
+        self.max_iterations.unwrap_or(DEFAULT_MAX_ITERATIONS)
+    }
+
+    /// Repeated benchmark runs, defaulting to a single run
+    pub fn runs(&self) -> u32 {
+        self.runs.unwrap_or(1)
+    }
+
+    /// Workload mode, defaulting to chat
+    pub fn mode(&self) -> BenchmarkMode {
+        self.mode.unwrap_or_default()
+    }
+
+    /// How iterations are reported, defaulting to combined
+    pub fn report(&self) -> ReportMode {
+        self.report.unwrap_or_default()
+    }
+
+    /// Streaming mode, defaulting to on
+    pub fn stream(&self) -> StreamMode {
+        self.stream.unwrap_or_default()
+    }
+
+    /// Prompt size, defaulting to short
+    pub fn size(&self) -> PromptSize {
+        self.size.unwrap_or(PromptSize::Short)
+    }
+
+    /// Output format, defaulting to a terminal table
+    pub fn output(&self) -> OutputFormat {
+        self.output.unwrap_or(OutputFormat::Table)
+    }
+
+    /// max_tokens headroom, defaulting to 50 tokens
+    pub fn max_tokens_buffer(&self) -> u32 {
+        self.max_tokens_buffer.unwrap_or(DEFAULT_MAX_TOKENS_BUFFER)
+    }
+
+    /// Concurrent requests per provider, defaulting to sequential
+    pub fn concurrency(&self) -> u32 {
+        self.concurrency.unwrap_or(1)
+    }
+
+    /// Ramp-up window in milliseconds, defaulting to none
+    pub fn ramp_up_ms(&self) -> u64 {
+        self.ramp_up_ms.unwrap_or(0)
+    }
+
+    /// Pricing mode, defaulting to standard rates
+    pub fn pricing_mode(&self) -> PricingMode {
+        self.pricing_mode.unwrap_or_default()
+    }
+
+    /// Warmup requests per provider, defaulting to none
+    pub fn warmup(&self) -> u32 {
+        self.warmup.unwrap_or(0)
+    }
+
+    /// Retries per failed iteration, defaulting to none
+    pub fn retries(&self) -> u32 {
+        self.retries.unwrap_or(0)
+    }
+}
+
+/// Parse a `NAME=VALUE` prompt variable
+fn parse_var(s: &str) -> Result<(String, String), String> {
+    let (name, value) = s
+        .split_once('=')
+        .ok_or_else(|| format!("expected NAME=VALUE, got '{}'", s))?;
+    Ok((name.trim().to_string(), value.to_string()))
+}
+
+/// Parse a `PROVIDER=N` pair with N at least 1
+fn parse_provider_limit(s: &str) -> Result<(String, u32), String> {
+    let (provider, limit) = s
+        .split_once('=')
+        .ok_or_else(|| format!("expected PROVIDER=N, got '{}'", s))?;
+    let limit: u32 = limit
+        .trim()
+        .parse()
+        .map_err(|_| format!("invalid limit '{}' for {}", limit, provider))?;
+    if limit == 0 {
+        return Err(format!("limit for {} must be at least 1", provider));
+    }
+    Ok((provider.trim().to_string(), limit))
+}
+
+/// Parse a percentage such as "10%" or "10"
+fn parse_tolerance(s: &str) -> Result<f64, String> {
+    let pct: f64 = s
+        .trim()
+        .trim_end_matches('%')
+        .parse()
+        .map_err(|_| format!("expected a percentage like 10%, got '{}'", s))?;
+    if !(0.0..=100.0).contains(&pct) {
+        return Err(format!(
+            "tolerance must be between 0% and 100%, got '{}'",
+            s
+        ));
+    }
+    Ok(pct)
+}
+
+/// Parse a positive number of standard deviations
+fn parse_stddevs(s: &str) -> Result<f64, String> {
+    let n: f64 = s
+        .trim()
+        .parse()
+        .map_err(|_| format!("expected a number of standard deviations, got '{}'", s))?;
+    if n.is_nan() || n <= 0.0 {
+        return Err(format!("must be greater than 0, got '{}'", s));
+    }
+    Ok(n)
+}
+
+/// Workload being benchmarked
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[derive(ValueEnum, Serialize, Deserialize, Clone, Debug, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum BenchmarkMode {
+    /// Streaming chat completions (TTFT and output tokens/sec)
+    #[default]
+    Chat,
+    /// Embeddings (latency and input tokens/sec; no TTFT)
+    Embeddings,
+}
+
+/// How each provider's iterations are reported
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[derive(ValueEnum, Serialize, Deserialize, Clone, Debug, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum ReportMode {
+    /// All iterations aggregated together
+    #[default]
+    Combined,
+    /// The first iteration as the cold start, the rest as warm steady state
+    ColdWarm,
+}
+
+/// Whether chat responses are streamed
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[derive(ValueEnum, Serialize, Deserialize, Clone, Debug, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum StreamMode {
+    /// Stream every response (measures real time to first token)
+    #[default]
+    On,
+    /// Ask for the whole response as one JSON body where supported
+    Off,
+    /// Benchmark streaming providers once each way
+    Both,
 }
 
 /// Test prompt size - affects token count and cost
-#[derive(ValueEnum, Clone, Debug, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[derive(ValueEnum, Serialize, Deserialize, Clone, Debug, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
 pub enum PromptSize {
     /// ~50 output tokens, minimal cost
     Short,
@@ -70,7 +614,8 @@ impl PromptSize {
 }
 
 /// Output format for benchmark results
-#[derive(ValueEnum, Clone, Debug, Copy, PartialEq, Eq)]
+#[derive(ValueEnum, Deserialize, Clone, Debug, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
 pub enum OutputFormat {
     /// Terminal table with aligned columns
     Table,
@@ -80,4 +625,41 @@ pub enum OutputFormat {
     Markdown,
     /// CSV for spreadsheets
     Csv,
+    /// InfluxDB line protocol, one point per provider (for Telegraf/Grafana)
+    Influx,
+}
+
+/// Column of the table and markdown output, selectable with `--columns`
+#[derive(ValueEnum, Deserialize, Clone, Debug, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+#[value(rename_all = "snake_case")]
+pub enum Column {
+    /// Provider display name
+    Provider,
+    /// Model benchmarked
+    Model,
+    /// Average time to first token
+    Ttft,
+    /// 95th percentile time to first token
+    TtftP95,
+    /// Average tokens per second
+    TokPerSec,
+    /// Average tokens per second scaled by the success rate
+    EffectiveTokPerSec,
+    /// Throughput as a percentage of the fastest provider
+    Relative,
+    /// Ratios against the --baseline provider
+    Baseline,
+    /// Average total latency
+    Latency,
+    /// Model load time
+    Load,
+    /// Total cost of the runs
+    Cost,
+    /// Share of attempted iterations that succeeded
+    Success,
+    /// Sparkline of tok/s across --runs
+    Trend,
+    /// Weighted score from --rank (0-100)
+    Score,
 }
@@ -0,0 +1,500 @@
+//! Config file support for default benchmark settings.
+//!
+//! Settings are resolved with the precedence: explicit CLI flags, then the
+//! config file, then built-in defaults. The file is `speed-kings.toml`, looked
+//! up in the current directory and then in `$XDG_CONFIG_HOME` (falling back to
+//! `~/.config`), unless `--config <path>` points at a specific file.
+//!
+//! ```toml
+//! [benchmark]
+//! providers = "cerebras,groq,local"
+//! iterations = 3
+//! size = "medium"
+//! output = "markdown"
+//!
+//! # Cap in-flight requests per provider (overrides `concurrency`)
+//! [benchmark.provider_concurrency]
+//! cerebras = 8
+//! groq = 2
+//!
+//! # Any OpenAI-compatible endpoint, selectable as `--providers acme`
+//! [[provider]]
+//! name = "acme"
+//! base_url = "https://api.acme.ai/v1"
+//! api_key_env = "ACME_API_KEY"
+//! default_model = "acme-large"
+//! input_per_million = 0.20
+//! output_per_million = 0.60
+//! ```
+
+use crate::benchmark::ScoreWeights;
+use crate::cli::{
+    BenchmarkArgs, BenchmarkMode, Column, OutputFormat, PromptSize, ReportMode, StreamMode,
+};
+use crate::pricing::PricingMode;
+use crate::providers::{BUILTIN_PROVIDERS, PROVIDER_GROUPS, ProviderDefinition};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+/// Config file name searched for in the current and config directories
+pub const CONFIG_FILE_NAME: &str = "speed-kings.toml";
+
+/// File of environment variables (e.g. API keys) read from the current
+/// directory at startup
+pub const ENV_FILE_NAME: &str = ".env";
+
+/// User config directory: `$XDG_CONFIG_HOME`, falling back to `~/.config`
+pub fn config_dir() -> Option<PathBuf> {
+    std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+}
+
+/// Errors that can occur while loading the config file
+#[derive(Debug, Error)]
+pub enum ConfigError {
+    #[error("Failed to read config file {path}: {source}")]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+
+    #[error("Invalid config file {path}: {source}")]
+    Parse {
+        path: PathBuf,
+        source: toml::de::Error,
+    },
+
+    #[error(
+        "Provider name {name:?} in config file {path} is already taken by another provider or group"
+    )]
+    ProviderName { path: PathBuf, name: String },
+
+    #[error("Providers file {0} lists no providers")]
+    EmptyProviders(PathBuf),
+
+    #[error("Failed to load env file {path}: {source}")]
+    EnvFile {
+        path: PathBuf,
+        source: dotenvy::Error,
+    },
+}
+
+/// Top-level config file contents
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Defaults for the `benchmark` subcommand
+    pub benchmark: BenchmarkDefaults,
+    /// OpenAI-compatible endpoints defined by `[[provider]]` tables
+    #[serde(rename = "provider")]
+    pub providers: Vec<ProviderDefinition>,
+}
+
+/// Defaults for `benchmark` flags; unset fields fall through to built-in defaults
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BenchmarkDefaults {
+    pub providers: Option<String>,
+    pub providers_file: Option<PathBuf>,
+    pub exclude: Option<Vec<String>>,
+    pub only_available: Option<bool>,
+    pub model: Option<String>,
+    pub iterations: Option<u32>,
+    pub sample_stddev_target: Option<f64>,
+    pub max_iterations: Option<u32>,
+    pub runs: Option<u32>,
+    pub max_tokens_sweep: Option<Vec<u32>>,
+    pub mode: Option<BenchmarkMode>,
+    pub report: Option<ReportMode>,
+    pub stream: Option<StreamMode>,
+    pub tools: Option<bool>,
+    pub size: Option<PromptSize>,
+    pub seed_prompts: Option<PathBuf>,
+    pub conversation: Option<PathBuf>,
+    pub prompt_template: Option<String>,
+    pub vars: Option<BTreeMap<String, String>>,
+    pub output: Option<OutputFormat>,
+    pub pricing_mode: Option<PricingMode>,
+    pub relative: Option<bool>,
+    pub baseline: Option<String>,
+    pub rank: Option<ScoreWeights>,
+    pub columns: Option<Vec<Column>>,
+    pub include_raw: Option<bool>,
+    pub json_compact: Option<bool>,
+    pub allow_empty: Option<bool>,
+    pub capture_output: Option<bool>,
+    pub yes: Option<bool>,
+    pub fail_on_error: Option<bool>,
+    pub assert_baseline: Option<PathBuf>,
+    /// Percent, like --tolerance
+    pub tolerance: Option<f64>,
+    pub trim_outliers: Option<f64>,
+    pub output_tokens: Option<u32>,
+    pub max_tokens_buffer: Option<u32>,
+    pub concurrency: Option<u32>,
+    pub provider_concurrency: Option<BTreeMap<String, u32>>,
+    pub ramp_up_ms: Option<u64>,
+    pub warmup: Option<u32>,
+    pub warmup_shared: Option<bool>,
+    pub shuffle: Option<bool>,
+    pub seed: Option<u64>,
+    pub retries: Option<u32>,
+    pub max_duration: Option<u64>,
+    pub max_cost_per_provider: Option<f64>,
+    pub timeout: Option<u64>,
+    pub fresh_connection: Option<bool>,
+    pub pool_idle_timeout: Option<u64>,
+    pub ttft_timeout_ms: Option<u64>,
+    pub stall_timeout_ms: Option<u64>,
+}
+
+impl BenchmarkDefaults {
+    /// Make relative file paths relative to `dir`, the config file's
+    /// directory, rather than to wherever the benchmark is run from
+    fn resolve_paths(&mut self, dir: &Path) {
+        for path in [
+            &mut self.providers_file,
+            &mut self.seed_prompts,
+            &mut self.conversation,
+            &mut self.assert_baseline,
+        ]
+        .into_iter()
+        .flatten()
+        {
+            *path = dir.join(&*path);
+        }
+    }
+}
+
+impl Config {
+    /// Load the config from an explicit path, or search the default locations
+    ///
+    /// An explicit path must exist; a missing file in the default locations
+    /// simply yields the built-in defaults.
+    pub fn load(path: Option<&Path>) -> Result<Self, ConfigError> {
+        match path {
+            Some(path) => Self::from_file(path),
+            None => match Self::search_paths().into_iter().find(|p| p.is_file()) {
+                Some(path) => Self::from_file(&path),
+                None => Ok(Self::default()),
+            },
+        }
+    }
+
+    /// Parse a config file at the given path
+    pub fn from_file(path: &Path) -> Result<Self, ConfigError> {
+        let contents = std::fs::read_to_string(path).map_err(|source| ConfigError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+
+        let mut config: Self = toml::from_str(&contents).map_err(|source| ConfigError::Parse {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        if let Some(dir) = path.parent() {
+            config.benchmark.resolve_paths(dir);
+        }
+        match config.taken_provider_name() {
+            Some(name) => Err(ConfigError::ProviderName {
+                path: path.to_path_buf(),
+                name: name.to_string(),
+            }),
+            None => Ok(config),
+        }
+    }
+
+    /// First defined provider name that would shadow a built-in provider, a
+    /// group, `all`, or an earlier definition
+    fn taken_provider_name(&self) -> Option<&str> {
+        self.providers
+            .iter()
+            .enumerate()
+            .find_map(|(i, definition)| {
+                let name = definition.name.as_str();
+                let taken = name == "all"
+                    || BUILTIN_PROVIDERS.contains(&name)
+                    || PROVIDER_GROUPS.iter().any(|(group, _)| *group == name)
+                    || self.providers[..i].iter().any(|d| d.name == name);
+                taken.then_some(name)
+            })
+    }
+
+    /// Candidate config file locations, in search order
+    fn search_paths() -> Vec<PathBuf> {
+        let mut paths = vec![PathBuf::from(CONFIG_FILE_NAME)];
+        if let Some(dir) = config_dir() {
+            paths.push(dir.join(CONFIG_FILE_NAME));
+        }
+        paths
+    }
+
+    /// Fill any benchmark flags not given on the command line from the file
+    pub fn apply_to(&self, args: &mut BenchmarkArgs) {
+        let file = &self.benchmark;
+
+        // --providers and --providers-file are one setting; either on the
+        // command line overrides both in the file
+        if args.providers.is_none() && args.providers_file.is_none() {
+            args.providers = file.providers.clone();
+            args.providers_file = file.providers_file.clone();
+        }
+        if args.exclude.is_empty() {
+            args.exclude = file.exclude.clone().unwrap_or_default();
+        }
+        args.only_available = args.only_available || file.only_available.unwrap_or(false);
+        args.model = args.model.take().or_else(|| file.model.clone());
+        args.iterations = args.iterations.or(file.iterations);
+        args.sample_stddev_target = args.sample_stddev_target.or(file.sample_stddev_target);
+        args.max_iterations = args.max_iterations.or(file.max_iterations);
+        args.runs = args.runs.or(file.runs);
+        if args.max_tokens_sweep.is_empty() && args.output_tokens.is_none() {
+            args.max_tokens_sweep = file.max_tokens_sweep.clone().unwrap_or_default();
+        }
+        args.mode = args.mode.or(file.mode);
+        args.report = args.report.or(file.report);
+        args.stream = args.stream.or(file.stream);
+        args.tools = args.tools || file.tools.unwrap_or(false);
+        args.size = args.size.or(file.size);
+        // The prompt source is one setting; --prompt-stdin, --prompt-template,
+        // --seed-prompts, or --conversation on the command line overrides the
+        // file's
+        if !args.prompt_stdin
+            && args.prompt_template.is_none()
+            && args.seed_prompts.is_none()
+            && args.conversation.is_none()
+        {
+            args.seed_prompts = file.seed_prompts.clone();
+            args.prompt_template = file.prompt_template.clone();
+            args.conversation = file.conversation.clone();
+        }
+        for (name, value) in file.vars.iter().flatten() {
+            if !args.vars.iter().any(|(n, _)| n == name) {
+                args.vars.push((name.clone(), value.clone()));
+            }
+        }
+        args.output = args.output.or(file.output);
+        args.pricing_mode = args.pricing_mode.or(file.pricing_mode);
+        args.relative = args.relative || file.relative.unwrap_or(false);
+        args.baseline = args.baseline.take().or_else(|| file.baseline.clone());
+        args.rank = args.rank.or(file.rank);
+        if args.columns.is_empty() {
+            args.columns = file.columns.clone().unwrap_or_default();
+        }
+        args.include_raw = args.include_raw || file.include_raw.unwrap_or(false);
+        args.json_compact = args.json_compact || file.json_compact.unwrap_or(false);
+        args.allow_empty = args.allow_empty || file.allow_empty.unwrap_or(false);
+        args.capture_output = args.capture_output || file.capture_output.unwrap_or(false);
+        args.yes = args.yes || file.yes.unwrap_or(false);
+        args.fail_on_error = args.fail_on_error || file.fail_on_error.unwrap_or(false);
+        args.assert_baseline = args
+            .assert_baseline
+            .take()
+            .or_else(|| file.assert_baseline.clone());
+        args.tolerance = args.tolerance.or(file.tolerance);
+        args.trim_outliers = args.trim_outliers.or(file.trim_outliers);
+        args.output_tokens = args.output_tokens.or(file.output_tokens);
+        args.max_tokens_buffer = args.max_tokens_buffer.or(file.max_tokens_buffer);
+        args.concurrency = args.concurrency.or(file.concurrency);
+        // Limits given on the command line win per provider
+        for (provider, limit) in file.provider_concurrency.iter().flatten() {
+            if !args.provider_concurrency.iter().any(|(p, _)| p == provider) {
+                args.provider_concurrency.push((provider.clone(), *limit));
+            }
+        }
+        args.ramp_up_ms = args.ramp_up_ms.or(file.ramp_up_ms);
+        args.warmup = args.warmup.or(file.warmup);
+        args.warmup_shared = args.warmup_shared || file.warmup_shared.unwrap_or(false);
+        args.shuffle = args.shuffle || file.shuffle.unwrap_or(false);
+        args.seed = args.seed.or(file.seed);
+        args.retries = args.retries.or(file.retries);
+        args.max_duration = args.max_duration.or(file.max_duration);
+        args.max_cost_per_provider = args.max_cost_per_provider.or(file.max_cost_per_provider);
+        args.timeout = args.timeout.or(file.timeout);
+        args.fresh_connection = args.fresh_connection || file.fresh_connection.unwrap_or(false);
+        args.pool_idle_timeout = args.pool_idle_timeout.or(file.pool_idle_timeout);
+        args.ttft_timeout_ms = args.ttft_timeout_ms.or(file.ttft_timeout_ms);
+        args.stall_timeout_ms = args.stall_timeout_ms.or(file.stall_timeout_ms);
+    }
+}
+
+/// Load an env file into the environment
+///
+/// Reads `path` if given (it must exist), else `.env` in the current
+/// directory if present. Variables already set in the environment win over
+/// the file. Call before any other threads start.
+pub fn load_env_file(path: Option<&Path>) -> Result<(), ConfigError> {
+    let path = match path {
+        Some(path) => path.to_path_buf(),
+        None => {
+            let default = PathBuf::from(ENV_FILE_NAME);
+            if !default.is_file() {
+                return Ok(());
+            }
+            default
+        }
+    };
+    dotenvy::from_path_iter(&path)
+        .and_then(|vars| vars.load())
+        .map_err(|source| ConfigError::EnvFile { path, source })
+}
+
+/// Read a providers file into a comma-separated `--providers` filter
+///
+/// Names are one per line (commas also separate); `#` starts a comment.
+pub fn load_providers_file(path: &Path) -> Result<String, ConfigError> {
+    let contents = std::fs::read_to_string(path).map_err(|source| ConfigError::Io {
+        path: path.to_path_buf(),
+        source,
+    })?;
+
+    let names = parse_provider_list(&contents);
+    if names.is_empty() {
+        return Err(ConfigError::EmptyProviders(path.to_path_buf()));
+    }
+    Ok(names.join(","))
+}
+
+fn parse_provider_list(contents: &str) -> Vec<&str> {
+    contents
+        .lines()
+        .map(|line| line.split('#').next().unwrap_or_default())
+        .flat_map(|line| line.split(','))
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    fn benchmark_args(argv: &[&str]) -> BenchmarkArgs {
+        let mut full = vec!["speed-kings", "benchmark"];
+        full.extend_from_slice(argv);
+        match crate::cli::Cli::parse_from(full).command {
+            crate::cli::Commands::Benchmark(args) => args,
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_cli_overrides_file_overrides_defaults() {
+        let config: Config = toml::from_str(
+            r#"
+            [benchmark]
+            providers = "groq,cerebras"
+            iterations = 5
+            size = "medium"
+            "#,
+        )
+        .unwrap();
+
+        let mut args = benchmark_args(&["--iterations", "2"]);
+        config.apply_to(&mut args);
+
+        assert_eq!(args.iterations(), 2);
+        assert_eq!(args.providers(), "groq,cerebras");
+        assert_eq!(args.size(), PromptSize::Medium);
+        assert_eq!(args.output(), OutputFormat::Table);
+    }
+
+    #[test]
+    fn test_providers_file_parsing_and_precedence() {
+        let names = parse_provider_list(
+            "# canonical comparison set\ncerebras\n  groq  # fastest\n\nlocal,local-rtx\n",
+        );
+        assert_eq!(names, ["cerebras", "groq", "local", "local-rtx"]);
+
+        let config: Config =
+            toml::from_str("[benchmark]\nproviders = \"groq\"\nproviders_file = \"team.txt\"\n")
+                .unwrap();
+        let mut args = benchmark_args(&["--providers-file", "mine.txt"]);
+        config.apply_to(&mut args);
+        assert_eq!(args.providers, None);
+        assert_eq!(args.providers_file, Some(PathBuf::from("mine.txt")));
+    }
+
+    #[test]
+    fn test_file_paths_resolve_against_config_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(CONFIG_FILE_NAME);
+        std::fs::write(
+            &path,
+            "[benchmark]\nproviders_file = \"team.txt\"\nseed_prompts = \"/srv/prompts.jsonl\"\n",
+        )
+        .unwrap();
+
+        let config = Config::from_file(&path).unwrap();
+
+        assert_eq!(
+            config.benchmark.providers_file,
+            Some(dir.path().join("team.txt"))
+        );
+        // Absolute paths are kept as they are
+        assert_eq!(
+            config.benchmark.seed_prompts,
+            Some(PathBuf::from("/srv/prompts.jsonl"))
+        );
+    }
+
+    #[test]
+    fn test_provider_concurrency_merges_per_provider() {
+        let config: Config =
+            toml::from_str("[benchmark.provider_concurrency]\ncerebras = 8\ngroq = 4\n").unwrap();
+
+        let mut args = benchmark_args(&["--provider-concurrency", "groq=2"]);
+        config.apply_to(&mut args);
+
+        let mut limits = args.provider_concurrency.clone();
+        limits.sort();
+        assert_eq!(
+            limits,
+            [("cerebras".to_string(), 8), ("groq".to_string(), 2)]
+        );
+    }
+
+    #[test]
+    fn test_provider_definitions() {
+        let config: Config = toml::from_str(
+            r#"
+            [[provider]]
+            name = "acme"
+            base_url = "https://api.acme.ai/v1"
+            api_key_env = "ACME_API_KEY"
+            default_model = "acme-large"
+            input_per_million = 0.2
+            output_per_million = 0.6
+
+            [[provider]]
+            name = "lab"
+            base_url = "http://gpu-box:8000/v1"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.providers.len(), 2);
+        assert_eq!(
+            config.providers[0].default_model.as_deref(),
+            Some("acme-large")
+        );
+        assert_eq!(config.providers[1].api_key_env, None);
+        assert_eq!(config.providers[1].output_per_million, 0.0);
+        assert_eq!(config.taken_provider_name(), None);
+
+        for name in ["groq", "fast-chips", "all", "acme"] {
+            let mut clash = config.clone();
+            clash.providers[1].name = name.to_string();
+            assert_eq!(clash.taken_provider_name(), Some(name));
+        }
+    }
+
+    #[test]
+    fn test_unknown_keys_rejected() {
+        let result: Result<Config, _> = toml::from_str("[benchmark]\niterationz = 3\n");
+        assert!(result.is_err());
+    }
+}
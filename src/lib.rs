@@ -6,10 +6,15 @@
 
 pub mod benchmark;
 pub mod cli;
+pub mod config;
 pub mod output;
 pub mod pricing;
 pub mod providers;
+#[cfg(feature = "tui")]
+pub mod tui;
 
-pub use benchmark::{BenchmarkConfig, BenchmarkResult, BenchmarkRunner};
-pub use cli::{Cli, Commands, OutputFormat, PromptSize};
-pub use providers::{InferenceProvider, InferenceRequest, InferenceResponse, ProviderError};
+pub use benchmark::{BenchmarkConfig, BenchmarkResult, BenchmarkRunner, benchmark_single};
+pub use cli::{BenchmarkArgs, Cli, Commands, OutputFormat, PromptSize};
+pub use providers::{
+    InferenceProvider, InferenceRequest, InferenceResponse, ProviderError, ProviderRegistry,
+};
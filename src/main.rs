@@ -1,54 +1,139 @@
 //! Speed Kings - LLM Inference Benchmarking Tool
+//!
+//! Exit codes for `benchmark`:
+//! - 0: all providers succeeded (or partial failure without `--fail-on-error`)
+//! - 1: some providers failed and `--fail-on-error` was set, or invalid setup
+//! - 2: no provider produced a successful result
+//! - 3: a provider regressed beyond `--tolerance` with `--assert-baseline`
+//!
+//! `validate` exits with 1 if any selected provider fails its check.
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use clap::Parser;
-use speed_kings::benchmark::{BenchmarkConfig, BenchmarkRunner};
-use speed_kings::cli::{Cli, Commands, OutputFormat};
-use speed_kings::output::format_results;
-use speed_kings::pricing::format_pricing_table;
-use speed_kings::providers::ProviderRegistry;
-use std::io::{self, Write};
+use futures::future::join_all;
+use speed_kings::benchmark::{
+    BenchmarkConfig, BenchmarkResult, BenchmarkRunner, CostEstimate, EtaTracker, ProgressEvent,
+    TestPrompt, find_limit, find_regressions, limit_steps, load_conversation, load_corpus, rank,
+    render_template, turn_count,
+};
+use speed_kings::cli::{
+    BenchmarkArgs, BenchmarkMode, Cli, Commands, ModelsArgs, OutputFormat, ReportMode, StreamMode,
+    ValidateArgs,
+};
+use speed_kings::config::{Config, load_env_file, load_providers_file};
+use speed_kings::output::{
+    JsonOutput, OutputOptions, format_economics, format_latency_breakdown, format_limits,
+    format_max_tokens_sweep, format_results, summarize,
+};
+use speed_kings::pricing::{
+    PricingMode, format_pricing_table, load_pricing_file, staleness_warning,
+};
+use speed_kings::providers::{
+    ConnectionPool, InferenceProvider, PROVIDER_GROUPS, ProviderDefinition, ProviderRegistry,
+    model_cache, provider_group, redact, set_connection_pool, set_request_timeout,
+    shared_endpoints, validate_provider,
+};
+use std::collections::BTreeMap;
+use std::io::{self, BufRead, Read, Write};
+use std::process::ExitCode;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
 use tracing_subscriber::EnvFilter;
 
-#[tokio::main]
-async fn main() -> Result<()> {
-    // Initialize logging
+/// Some requested providers failed (only with `--fail-on-error`)
+const EXIT_PARTIAL_FAILURE: u8 = 1;
+/// Every requested provider failed
+const EXIT_TOTAL_FAILURE: u8 = 2;
+/// A provider regressed against `--assert-baseline`
+const EXIT_REGRESSION: u8 = 3;
+
+fn main() -> Result<ExitCode> {
+    let cli = Cli::parse();
+
+    // No other threads exist yet; the runtime is started below
+    load_env_file(cli.env_file.as_deref())?;
+
+    tokio::runtime::Runtime::new()?.block_on(run(cli))
+}
+
+async fn run(cli: Cli) -> Result<ExitCode> {
+    let color = cli.color();
+
+    // Initialize logging; --verbose enables per-iteration logs unless RUST_LOG is set
+    let filter = match std::env::var(EnvFilter::DEFAULT_ENV) {
+        Err(_) if cli.verbose => EnvFilter::new("speed_kings=info"),
+        _ => EnvFilter::from_default_env(),
+    };
     tracing_subscriber::fmt()
-        .with_env_filter(EnvFilter::from_default_env())
+        .with_env_filter(filter)
+        .with_writer(io::stderr)
+        .with_ansi(color)
         .init();
 
-    let cli = Cli::parse();
+    if let Some(path) = &cli.pricing_file {
+        load_pricing_file(path)?;
+    }
+    if cli.refresh_models {
+        model_cache::clear()?;
+    }
 
     match cli.command {
-        Commands::Benchmark {
-            providers,
-            iterations,
-            size,
-            output,
-            yes,
-        } => {
-            run_benchmark(&providers, iterations, size, output, yes, cli.verbose).await?;
-        }
-        Commands::List => {
-            list_providers();
+        Commands::Benchmark(mut args) => {
+            let config = Config::load(cli.config.as_deref())?;
+            config.apply_to(&mut args);
+            if let Some(path) = &args.providers_file {
+                args.providers = Some(load_providers_file(path)?);
+            }
+            // There is no one to answer the cost prompt without its echo
+            args.yes |= cli.quiet;
+            return run_benchmark(args, &config.providers, cli.verbose, color).await;
+        }
+        Commands::List(args) => {
+            let config = Config::load(cli.config.as_deref())?;
+            if args.json {
+                let registry = ProviderRegistry::with_definitions(&config.providers);
+                let statuses = registry.statuses(&config.providers);
+                println!("{}", serde_json::to_string_pretty(&statuses)?);
+            } else {
+                list_providers(&config);
+            }
         }
         Commands::Pricing => {
             println!("{}", format_pricing_table());
         }
+        Commands::Validate(args) => {
+            let config = Config::load(cli.config.as_deref())?;
+            return validate(args, &config).await;
+        }
+        Commands::Models(args) => {
+            let config = Config::load(cli.config.as_deref())?;
+            return list_models(args, &config).await;
+        }
+        #[cfg(feature = "schema")]
+        Commands::Schema => {
+            println!("{}", speed_kings::output::json_schema());
+        }
     }
 
-    Ok(())
+    Ok(ExitCode::SUCCESS)
 }
 
 async fn run_benchmark(
-    provider_filter: &str,
-    iterations: u32,
-    size: speed_kings::cli::PromptSize,
-    output_format: OutputFormat,
-    skip_confirm: bool,
+    args: BenchmarkArgs,
+    definitions: &[ProviderDefinition],
     verbose: bool,
-) -> Result<()> {
-    let registry = ProviderRegistry::new();
+    color: bool,
+) -> Result<ExitCode> {
+    // Clients take their timeout at construction, so set it before the registry
+    if let Some(ms) = args.timeout {
+        set_request_timeout(Duration::from_millis(ms));
+    }
+    if args.fresh_connection {
+        set_connection_pool(ConnectionPool::Fresh);
+    } else if let Some(secs) = args.pool_idle_timeout {
+        set_connection_pool(ConnectionPool::IdleTimeout(Duration::from_secs(secs)));
+    }
+    let registry = ProviderRegistry::with_definitions(definitions);
 
     if registry.is_empty() {
         eprintln!("No providers available.\n");
@@ -67,6 +152,12 @@ async fn run_benchmark(
         eprintln!("  export ZAI_API_KEY=...");
         eprintln!("  export MOONSHOT_API_KEY=...");
         eprintln!();
+        eprintln!("  # Hyperscaler clouds");
+        eprintln!("  export AZURE_OPENAI_ENDPOINT=...");
+        eprintln!("  export AZURE_OPENAI_KEY=...");
+        eprintln!("  export AZURE_OPENAI_DEPLOYMENT=...");
+        eprintln!("  export VERTEX_PROJECT=...  # plus GOOGLE_APPLICATION_CREDENTIALS off GCP");
+        eprintln!();
         eprintln!("  # Aggregators / Proxies");
         eprintln!("  export OPENROUTER_API_KEY=...");
         eprintln!("  export LITELLM_URL=http://localhost:4000/v1/chat/completions");
@@ -80,73 +171,431 @@ async fn run_benchmark(
     }
 
     // Filter providers based on input
-    let providers: Vec<_> = if provider_filter == "all" {
-        registry.all()
-    } else {
-        let names: Vec<&str> = provider_filter.split(',').map(|s| s.trim()).collect();
-        let mut filtered = Vec::new();
-        for name in names {
-            if let Some(provider) = registry.get(name) {
-                filtered.push(provider);
-            } else {
-                eprintln!(
-                    "Warning: Provider '{}' not available. Available: {:?}",
-                    name,
-                    registry.available()
-                );
-            }
+    let mut providers = resolve_providers(&registry, args.providers(), &args.exclude);
+
+    // Optionally drop unreachable providers before benchmarking
+    let mut skipped_unavailable = 0;
+    if args.only_available {
+        let checks = join_all(providers.iter().map(|p| p.is_available())).await;
+        let before = providers.len();
+        let mut checks = checks.into_iter();
+        providers.retain(|_| checks.next().unwrap_or(false));
+        skipped_unavailable = before - providers.len();
+    }
+
+    if args.tools {
+        if args.mode() != BenchmarkMode::Chat {
+            anyhow::bail!("--tools applies to chat mode only");
         }
-        filtered
-    };
+        let (with_tools, without): (Vec<_>, Vec<_>) =
+            providers.into_iter().partition(|p| p.supports_tools());
+        if !without.is_empty() {
+            let names: Vec<&str> = without.iter().map(|p| p.name()).collect();
+            eprintln!(
+                "Warning: {} cannot be offered tools; skipping",
+                names.join(", ")
+            );
+        }
+        providers = with_tools;
+    }
+
+    if args.conversation.is_some() {
+        if args.mode() != BenchmarkMode::Chat {
+            anyhow::bail!("--conversation applies to chat mode only");
+        }
+        if args.tools {
+            anyhow::bail!("--conversation cannot be combined with --tools");
+        }
+        let (with_history, without): (Vec<_>, Vec<_>) =
+            providers.into_iter().partition(|p| p.supports_history());
+        if !without.is_empty() {
+            let names: Vec<&str> = without.iter().map(|p| p.name()).collect();
+            eprintln!(
+                "Warning: {} cannot be sent conversation history; skipping",
+                names.join(", ")
+            );
+        }
+        providers = with_history;
+    }
 
     if providers.is_empty() {
         eprintln!("No matching providers found.");
         std::process::exit(1);
     }
+    // Layered proxies can register one backend under several names
+    for (host, names) in shared_endpoints(&providers) {
+        eprintln!(
+            "Warning: {} all point at {} and may benchmark the same backend",
+            names.join(", "),
+            host
+        );
+    }
+    if let Some(baseline) = &args.baseline
+        && !providers.iter().any(|p| p.name() == baseline)
+    {
+        anyhow::bail!(
+            "--baseline {} is not among the benchmarked providers",
+            baseline
+        );
+    }
 
+    if args.report() == ReportMode::ColdWarm {
+        if args.warmup() > 0 || args.warmup_shared {
+            anyhow::bail!(
+                "--report cold-warm measures the first request as the cold start; \
+                 drop --warmup and --warmup-shared"
+            );
+        }
+        if args.iterations() < 2 {
+            anyhow::bail!("--report cold-warm needs --iterations 2 or more");
+        }
+    }
+    if !args.max_tokens_sweep.is_empty() {
+        if args.mode() != BenchmarkMode::Chat {
+            anyhow::bail!("--max-tokens-sweep applies to chat mode only");
+        }
+        if args.max_tokens_sweep.contains(&0) {
+            anyhow::bail!("--max-tokens-sweep values must be greater than 0");
+        }
+    }
+    if let Some(max) = args.find_limit {
+        if max == 0 {
+            anyhow::bail!("--find-limit must be greater than 0");
+        }
+        if args.mode() != BenchmarkMode::Chat {
+            anyhow::bail!("--find-limit applies to chat mode only");
+        }
+        if args.stream() == StreamMode::Both {
+            anyhow::bail!("--find-limit needs --stream on or off");
+        }
+        if !matches!(args.output(), OutputFormat::Table | OutputFormat::Json) {
+            anyhow::bail!("--find-limit supports table and json output only");
+        }
+        #[cfg(feature = "tui")]
+        if args.tui {
+            anyhow::bail!("--find-limit cannot be combined with --tui");
+        }
+    }
+    if args
+        .max_cost_per_provider
+        .is_some_and(|usd| usd.is_nan() || usd <= 0.0)
+    {
+        anyhow::bail!("--max-cost-per-provider must be greater than 0");
+    }
+
+    if args.stream() != StreamMode::On {
+        if args.mode() != BenchmarkMode::Chat {
+            anyhow::bail!("--stream applies to chat mode only");
+        }
+        let stream_only: Vec<&str> = providers
+            .iter()
+            .filter(|p| !p.supports_non_streaming())
+            .map(|p| p.name())
+            .collect();
+        if !stream_only.is_empty() {
+            eprintln!(
+                "Warning: {} can only stream; benchmarking streamed only",
+                stream_only.join(", ")
+            );
+        }
+    }
+
+    // Read the baseline up front so a bad path fails before any spend
+    let blessed = match &args.assert_baseline {
+        Some(path) => Some(load_baseline(path)?),
+        None => None,
+    };
+
+    let vars: BTreeMap<String, String> = args.vars.iter().cloned().collect();
     let config = BenchmarkConfig {
-        iterations,
-        prompt_size: size,
-        timeout_ms: 60_000,
+        iterations: args.iterations(),
+        target_cv: args.sample_stddev_target,
+        max_iterations: args.max_iterations(),
+        mode: args.mode(),
+        model: args.model.clone(),
+        report: args.report(),
+        stream: args.stream(),
+        tools: args.tools,
+        prompt_size: args.size(),
+        timeout_ms: args.timeout,
+        output_tokens: args.output_tokens,
+        max_tokens_buffer: args.max_tokens_buffer(),
+        trim_outliers: args.trim_outliers,
+        concurrency: args.concurrency(),
+        provider_concurrency: args.provider_concurrency.iter().cloned().collect(),
+        ramp_up_ms: args.ramp_up_ms(),
+        warmup: args.warmup(),
+        max_retries: args.retries(),
+        pricing_mode: args.pricing_mode(),
+        warmup_shared: args.warmup_shared,
+        shuffle_seed: args
+            .shuffle
+            .then(|| args.seed.unwrap_or_else(|| fastrand::u64(..))),
+        max_duration_secs: args.max_duration,
+        max_cost_per_provider_usd: args.max_cost_per_provider,
+        ttft_timeout_ms: args.ttft_timeout_ms,
+        stall_timeout_ms: args.stall_timeout_ms,
+        fresh_connection: args.fresh_connection,
+        pool_idle_timeout_secs: args.pool_idle_timeout,
+        runs: args.runs(),
+        max_tokens_sweep: args.max_tokens_sweep.clone(),
+        allow_empty: args.allow_empty,
+        capture_output: args.capture_output,
+        custom_prompt: custom_prompt(&args, &vars)?,
+        corpus: match &args.seed_prompts {
+            Some(path) => load_corpus(path)?
+                .iter()
+                .map(|prompt| apply_vars(prompt, &vars))
+                .collect::<Result<_>>()?,
+            None => Vec::new(),
+        },
+        conversation: match &args.conversation {
+            Some(path) => load_conversation(path)?,
+            None => Vec::new(),
+        },
+        turn: None,
     };
 
-    let runner = BenchmarkRunner::new(providers.clone(), config);
+    let runner = BenchmarkRunner::new(providers.clone(), config.clone());
 
-    // Estimate and confirm cost
-    let estimated_cost = runner.estimate_cost();
+    // Estimate and confirm cost; --find-limit sends --iterations requests per
+    // slot at every level, assuming none is rate limited
+    let estimated_cost = match args.find_limit {
+        Some(max) => {
+            let requests: u32 = limit_steps(max).iter().sum();
+            let config = BenchmarkConfig {
+                iterations: config.iterations * requests,
+                ..config.clone()
+            };
+            BenchmarkRunner::new(providers.clone(), config).estimate_cost()
+        }
+        None => runner.estimate_cost(),
+    };
 
-    if !skip_confirm && estimated_cost > 0.0 {
+    if needs_confirmation(args.yes, &estimated_cost) {
         println!("Benchmark configuration:");
         println!(
             "  Providers: {:?}",
             providers.iter().map(|p| p.name()).collect::<Vec<_>>()
         );
-        println!("  Iterations: {}", iterations);
-        println!("  Prompt size: {:?}", size);
-        println!("  Estimated cost: ${:.4}", estimated_cost);
+        if args.mode() != BenchmarkMode::Chat {
+            println!("  Mode: {:?}", args.mode());
+        }
+        if let Some(model) = &args.model {
+            println!("  Model: {} (every provider)", model);
+        }
+        if args.report() == ReportMode::ColdWarm {
+            println!("  Report: first request as cold start, the rest as warm");
+        }
+        match args.stream() {
+            StreamMode::On => {}
+            StreamMode::Off => println!("  Streaming: off"),
+            StreamMode::Both => println!("  Streaming: both ways, as separate rows"),
+        }
+        if args.tools {
+            println!("  Tools: get_weather offered; every answer must call it");
+        }
+        match args.sample_stddev_target {
+            Some(cv) => println!(
+                "  Iterations: {} to {} (until tok/s CV <= {})",
+                args.iterations(),
+                config.iteration_cap(),
+                cv
+            ),
+            None => println!("  Iterations: {}", args.iterations()),
+        }
+        if args.runs() > 1 {
+            println!("  Runs: {}", args.runs());
+        }
+        if args.concurrency() > 1 {
+            println!("  Concurrency: {}", args.concurrency());
+        }
+        if let Some(max) = args.find_limit {
+            println!(
+                "  Find limit: concurrency 1 to {}, {} requests per slot at each level",
+                max,
+                args.iterations()
+            );
+        }
+        for (provider, limit) in &config.provider_concurrency {
+            println!("  Concurrency ({}): {}", provider, limit);
+        }
+        match &config.custom_prompt {
+            Some(prompt) => println!(
+                "  Prompt: {} (~{} input tokens)",
+                prompt.name, prompt.expected_input_tokens
+            ),
+            None => println!("  Prompt size: {:?}", args.size()),
+        }
+        if let Some(path) = &args.seed_prompts {
+            println!(
+                "  Prompt corpus: {} ({} prompts)",
+                path.display(),
+                config.corpus.len()
+            );
+        }
+        if let Some(path) = &args.conversation {
+            println!(
+                "  Conversation: {} ({} turns)",
+                path.display(),
+                turn_count(&config.conversation)
+            );
+        }
+        if let Some(tokens) = args.output_tokens {
+            println!("  Output tokens: {}", tokens);
+        }
+        if !args.max_tokens_sweep.is_empty() {
+            let values: Vec<String> = args.max_tokens_sweep.iter().map(u32::to_string).collect();
+            println!("  Max tokens sweep: {}", values.join(", "));
+        }
+        if let Some(buffer) = args.max_tokens_buffer {
+            println!("  Max tokens buffer: {}", buffer);
+        }
+        if let Some(seed) = config.shuffle_seed {
+            println!("  Provider order: shuffled each run (--seed {})", seed);
+        }
+        if args.warmup() > 0 {
+            println!("  Warmup requests: {}", args.warmup());
+        }
+        if args.retries() > 0 {
+            println!("  Retries: up to {}", args.retries());
+        }
+        if let Some(secs) = args.max_duration {
+            println!("  Max duration: {}s", secs);
+        }
+        if let Some(usd) = args.max_cost_per_provider {
+            println!("  Cost cap: ${:.4} per provider", usd);
+        }
+        if let Some(ms) = args.timeout {
+            println!("  Request timeout: {}ms", ms);
+        }
+        if let Some(ms) = args.ttft_timeout_ms {
+            println!("  First token timeout: {}ms", ms);
+        }
+        if let Some(ms) = args.stall_timeout_ms {
+            println!("  Stall timeout: {}ms", ms);
+        }
+        if args.fresh_connection {
+            println!("  Connections: new per request");
+        } else if let Some(secs) = args.pool_idle_timeout {
+            println!("  Connections: pooled, {}s idle timeout", secs);
+        }
+        if args.pricing_mode() != PricingMode::Standard {
+            println!("  Pricing mode: {:?}", args.pricing_mode());
+        }
+        if let Some(weights) = &args.rank {
+            println!("  Rank by: {}", weights);
+        }
+        if let Some(path) = &args.assert_baseline {
+            println!(
+                "  Regression baseline: {} (tolerance {:.0}%)",
+                path.display(),
+                args.tolerance() * 100.0
+            );
+        }
+        println!(
+            "  Estimated cost: ${:.4} (range ${:.4} - ${:.4})",
+            estimated_cost.expected, estimated_cost.min, estimated_cost.max
+        );
+        if let Some(warning) = staleness_warning() {
+            println!("  Warning: {}", warning);
+        }
         println!();
 
         print!("Proceed? [y/N] ");
         io::stdout().flush()?;
 
         let mut input = String::new();
-        io::stdin().read_line(&mut input)?;
+        if args.prompt_stdin {
+            // The prompt used up stdin, so the answer comes from the terminal
+            let tty = std::fs::File::open("/dev/tty").context(
+                "--prompt-stdin needs --yes when no terminal is available to confirm the cost",
+            )?;
+            io::BufReader::new(tty).read_line(&mut input)?;
+        } else {
+            io::stdin().read_line(&mut input)?;
+        }
 
         if !input.trim().eq_ignore_ascii_case("y") {
             println!("Cancelled.");
-            return Ok(());
+            return Ok(ExitCode::SUCCESS);
         }
     }
 
+    if let Some(max) = args.find_limit {
+        return find_limits(&providers, &config, max, &args, verbose).await;
+    }
+
     if verbose {
+        if let Some(seed) = config.shuffle_seed {
+            println!("Shuffling provider order each run (--seed {})", seed);
+        }
         println!("Starting benchmark...\n");
     }
 
+    // Percent done and time left after each iteration (the dashboard
+    // replaces this callback with its own)
+    let runner = if verbose {
+        let eta = Mutex::new(EtaTracker::new(runner.planned_iterations()));
+        runner.with_progress(move |event| {
+            let mut eta = eta.lock().unwrap();
+            eta.record(event);
+            if matches!(
+                event,
+                ProgressEvent::IterationComplete { .. } | ProgressEvent::IterationFailed { .. }
+            ) {
+                println!("  {}", eta);
+            }
+        })
+    } else {
+        runner
+    };
+
     // Run benchmarks
+    let started = Instant::now();
+    #[cfg(feature = "tui")]
+    let results = if args.tui {
+        let names: Vec<(String, String)> = providers
+            .iter()
+            .map(|p| (p.name().to_string(), p.display_name().to_string()))
+            .collect();
+        match speed_kings::tui::run(runner, &names).await? {
+            Some(results) => results,
+            None => {
+                println!("Cancelled.");
+                return Ok(ExitCode::SUCCESS);
+            }
+        }
+    } else {
+        runner.run().await
+    };
+    #[cfg(not(feature = "tui"))]
     let results = runner.run().await;
+    let elapsed = started.elapsed();
+    let results = match &args.rank {
+        Some(weights) => rank(results, weights),
+        None => results,
+    };
 
     // Output results
-    println!("{}", format_results(&results, output_format));
+    let options = OutputOptions {
+        relative: args.relative,
+        baseline: args.baseline.clone(),
+        rank: args.rank,
+        columns: args.columns.clone(),
+        include_raw: args.include_raw,
+        json_compact: args.json_compact,
+        verbose,
+        no_color: !color,
+        total_duration_ms: Some(elapsed.as_millis() as u64),
+    };
+    println!(
+        "{}",
+        format_results(&results, args.output(), &config, &options)
+    );
+    if !config.max_tokens_sweep.is_empty() && args.output() == OutputFormat::Table {
+        println!("{}", format_max_tokens_sweep(&results));
+    }
 
     // Summary
     let total_cost: f64 = results.iter().map(|r| r.metrics.total_cost_usd).sum();
@@ -155,14 +604,316 @@ async fn run_benchmark(
     if verbose {
         println!("\nSummary:");
         println!("  Providers tested: {}/{}", successful, results.len());
+        if args.only_available {
+            println!("  Skipped (unavailable): {}", skipped_unavailable);
+        }
         println!("  Total cost: ${:.4}", total_cost);
+        println!("  Wall-clock time: {:.1}s", elapsed.as_secs_f64());
+        let total_tokens = summarize(&results).total_output_tokens;
+        if total_tokens > 0 && !elapsed.is_zero() {
+            println!(
+                "  Aggregate throughput: {:.0} tok/s across all providers",
+                total_tokens as f64 / elapsed.as_secs_f64()
+            );
+        }
+
+        for result in &results {
+            if let Some(limit) = result.latest_rate_limit() {
+                let fmt = |v: Option<u64>| v.map_or("?".to_string(), |n| n.to_string());
+                println!(
+                    "  {} rate limit remaining: {} requests, {} tokens",
+                    result.display_name,
+                    fmt(limit.remaining_requests),
+                    fmt(limit.remaining_tokens)
+                );
+            }
+        }
+
+        for result in results
+            .iter()
+            .filter(|r| r.metrics.avg_response_bytes > 0.0)
+        {
+            println!(
+                "  {} traffic per request: {:.0} B sent, {:.0} B received",
+                result.display_name,
+                result.metrics.avg_request_bytes,
+                result.metrics.avg_response_bytes
+            );
+        }
+
+        for section in [
+            format_economics(&results),
+            format_latency_breakdown(&results),
+        ] {
+            if !section.is_empty() {
+                println!("\n{}", section.trim_end());
+            }
+        }
+    }
+
+    let regressions = match &blessed {
+        Some(baseline) => find_regressions(&results, baseline, args.tolerance()),
+        None => Vec::new(),
+    };
+    if !regressions.is_empty() {
+        eprintln!(
+            "\nRegressed beyond {:.0}% of the baseline:",
+            args.tolerance() * 100.0
+        );
+        for regression in &regressions {
+            eprintln!("  {}", regression);
+        }
+    }
+
+    if successful == 0 {
+        Ok(ExitCode::from(EXIT_TOTAL_FAILURE))
+    } else if !regressions.is_empty() {
+        Ok(ExitCode::from(EXIT_REGRESSION))
+    } else if args.fail_on_error && successful < results.len() {
+        Ok(ExitCode::from(EXIT_PARTIAL_FAILURE))
+    } else {
+        Ok(ExitCode::SUCCESS)
+    }
+}
+
+/// Ramp each provider's concurrency until it is rate limited and print what
+/// it sustained
+async fn find_limits(
+    providers: &[&dyn InferenceProvider],
+    config: &BenchmarkConfig,
+    max_concurrency: u32,
+    args: &BenchmarkArgs,
+    verbose: bool,
+) -> Result<ExitCode> {
+    if verbose {
+        println!("Finding rate limits...\n");
+    }
+    let mut results = Vec::new();
+    for provider in providers {
+        results.push(find_limit(*provider, config, max_concurrency).await);
+    }
+
+    if args.output() == OutputFormat::Json {
+        let json = if args.json_compact {
+            serde_json::to_string(&results)?
+        } else {
+            serde_json::to_string_pretty(&results)?
+        };
+        println!("{}", json);
+    } else {
+        print!("{}", format_limits(&results));
+    }
+
+    if results.iter().all(|r| r.sustained().is_none()) {
+        Ok(ExitCode::from(EXIT_TOTAL_FAILURE))
+    } else {
+        Ok(ExitCode::SUCCESS)
+    }
+}
+
+/// Results from a saved `--output json` document for `--assert-baseline`
+fn load_baseline(path: &std::path::Path) -> Result<Vec<BenchmarkResult>> {
+    let contents = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read baseline {}", path.display()))?;
+    let output: JsonOutput = serde_json::from_str(&contents)
+        .with_context(|| format!("Failed to parse baseline {}", path.display()))?;
+    Ok(output.results.into_owned())
+}
+
+/// Prompt from --prompt-template or --prompt-stdin, with variables filled in
+fn custom_prompt(
+    args: &BenchmarkArgs,
+    vars: &BTreeMap<String, String>,
+) -> Result<Option<TestPrompt>> {
+    if args.prompt_template.is_some() && args.seed_prompts.is_some() {
+        anyhow::bail!("--prompt-template and --seed-prompts cannot both be set");
+    }
+    if let Some(template) = &args.prompt_template {
+        let text = render_template(template, vars)?;
+        return Ok(Some(TestPrompt::custom(
+            "template",
+            text,
+            args.output_tokens,
+        )));
+    }
+    if args.prompt_stdin {
+        let text = apply_vars(&read_prompt(io::stdin().lock())?, vars)?;
+        return Ok(Some(TestPrompt::custom("stdin", text, args.output_tokens)));
+    }
+    if !vars.is_empty() && args.seed_prompts.is_none() {
+        anyhow::bail!("--var needs --prompt-template, --prompt-stdin, or --seed-prompts");
+    }
+    Ok(None)
+}
+
+/// Fill variables into user-supplied prompt text, leaving it untouched when
+/// no --var was given
+fn apply_vars(text: &str, vars: &BTreeMap<String, String>) -> Result<String> {
+    if vars.is_empty() {
+        return Ok(text.to_string());
+    }
+    Ok(render_template(text, vars)?)
+}
+
+/// Whether to show the cost and ask before running: only for paid runs
+/// without --yes
+fn needs_confirmation(yes: bool, estimated_cost: &CostEstimate) -> bool {
+    !yes && !estimated_cost.is_free()
+}
+
+/// Read the whole prompt for `--prompt-stdin`
+fn read_prompt(mut reader: impl Read) -> Result<String> {
+    let mut text = String::new();
+    reader.read_to_string(&mut text)?;
+    let text = text.trim();
+    if text.is_empty() {
+        anyhow::bail!("--prompt-stdin: no prompt received on stdin");
     }
 
-    Ok(())
+    Ok(text.to_string())
 }
 
-fn list_providers() {
-    let registry = ProviderRegistry::new();
+/// Resolve the `--providers` filter and `--exclude` list against the registry
+fn resolve_providers<'a>(
+    registry: &'a ProviderRegistry,
+    provider_filter: &str,
+    exclude: &[String],
+) -> Vec<&'a dyn InferenceProvider> {
+    let mut providers: Vec<_> = if provider_filter == "all" {
+        registry.all()
+    } else {
+        let mut filtered: Vec<&dyn InferenceProvider> = Vec::new();
+        for name in provider_filter.split(',').map(|s| s.trim()) {
+            if let Some(members) = provider_group(name) {
+                // Unconfigured group members are skipped quietly
+                for provider in members.into_iter().filter_map(|m| registry.get(m)) {
+                    if !filtered.iter().any(|p| p.name() == provider.name()) {
+                        filtered.push(provider);
+                    }
+                }
+            } else if let Some(provider) = registry.get(name) {
+                filtered.push(provider);
+            } else {
+                eprintln!(
+                    "Warning: Provider '{}' not available. Available: {:?}",
+                    name,
+                    registry.available()
+                );
+            }
+        }
+        filtered
+    };
+
+    for name in exclude.iter().map(|s| s.trim()) {
+        if registry.get(name).is_none() {
+            eprintln!(
+                "Warning: Excluded provider '{}' is not configured. Available: {:?}",
+                name,
+                registry.available()
+            );
+        }
+    }
+    providers.retain(|p| !exclude.iter().any(|name| name.trim() == p.name()));
+
+    providers
+}
+
+/// `--providers` filter of a non-benchmark subcommand, else the config
+/// file's provider list, else all
+fn provider_filter(providers: &Option<String>, config: &Config) -> Result<String> {
+    Ok(match (providers, &config.benchmark.providers_file) {
+        (Some(providers), _) => providers.clone(),
+        (None, Some(path)) => load_providers_file(path)?,
+        (None, None) => config
+            .benchmark
+            .providers
+            .clone()
+            .unwrap_or_else(|| "all".to_string()),
+    })
+}
+
+/// Validate credentials of the selected providers, failing if any is not OK
+async fn validate(args: ValidateArgs, config: &Config) -> Result<ExitCode> {
+    let registry = ProviderRegistry::with_definitions(&config.providers);
+    let filter = provider_filter(&args.providers, config)?;
+    let exclude = config.benchmark.exclude.clone().unwrap_or_default();
+    let providers = resolve_providers(&registry, &filter, &exclude);
+    if providers.is_empty() {
+        eprintln!("No matching providers configured.");
+        return Ok(ExitCode::from(EXIT_PARTIAL_FAILURE));
+    }
+
+    let statuses = join_all(providers.iter().map(|p| validate_provider(*p))).await;
+    let name_width = providers.iter().map(|p| p.name().len()).max().unwrap_or(0);
+    for (provider, status) in providers.iter().zip(&statuses) {
+        println!(
+            "  {:<width$}  {}",
+            provider.name(),
+            status,
+            width = name_width
+        );
+    }
+
+    let failed = statuses.iter().filter(|s| !s.is_ok()).count();
+    if failed > 0 {
+        eprintln!(
+            "\n{} of {} provider(s) failed validation",
+            failed,
+            statuses.len()
+        );
+        return Ok(ExitCode::from(EXIT_PARTIAL_FAILURE));
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Print the models each selected provider can serve, marking its default
+async fn list_models(args: ModelsArgs, config: &Config) -> Result<ExitCode> {
+    let registry = ProviderRegistry::with_definitions(&config.providers);
+    let filter = provider_filter(&args.providers, config)?;
+    let exclude = config.benchmark.exclude.clone().unwrap_or_default();
+    let providers = resolve_providers(&registry, &filter, &exclude);
+    if providers.is_empty() {
+        eprintln!("No matching providers configured.");
+        return Ok(ExitCode::from(EXIT_PARTIAL_FAILURE));
+    }
+
+    let listings = join_all(providers.iter().map(|p| p.list_models())).await;
+    let mut failed = 0;
+    for (provider, listing) in providers.iter().zip(listings) {
+        println!("  {} ({})", provider.display_name(), provider.name());
+        let models = match listing {
+            Ok(models) => models,
+            Err(e) => {
+                println!("    failed: {}", redact(&e.to_string()));
+                failed += 1;
+                continue;
+            }
+        };
+        let default = provider.default_model();
+        for model in &models {
+            let marker = if model.id == default {
+                " (default)"
+            } else {
+                ""
+            };
+            match model.upstreams.as_slice() {
+                [] => println!("    {}{}", model.id, marker),
+                upstreams => println!("    {}{} -> {}", model.id, marker, upstreams.join(", ")),
+            }
+        }
+        if !models.iter().any(|m| m.id == default) {
+            println!("    Warning: default model {} is not listed", default);
+        }
+    }
+
+    if failed > 0 {
+        return Ok(ExitCode::from(EXIT_PARTIAL_FAILURE));
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+fn list_providers(config: &Config) {
+    let registry = ProviderRegistry::with_definitions(&config.providers);
 
     println!("Available Providers");
     println!("===================\n");
@@ -177,12 +928,24 @@ fn list_providers() {
         println!();
         println!("  # NVIDIA GPU clouds");
         println!("  FIREWORKS_API_KEY      - Fireworks inference");
+        println!("  FIREWORKS_MODEL        - Fireworks model (default: llama-v3p1-70b-instruct)");
         println!("  TOGETHER_API_KEY       - Together AI inference");
         println!();
         println!("  # Native model providers");
         println!("  DEEPSEEK_API_KEY       - DeepSeek inference");
+        println!("  DEEPSEEK_MODEL         - DeepSeek model (default: deepseek-chat)");
         println!("  ZAI_API_KEY            - Z.ai (Zhipu) GLM inference");
+        println!("  ZAI_MODEL              - Z.ai model (default: glm-4.7)");
         println!("  MOONSHOT_API_KEY       - Moonshot Kimi inference");
+        println!("  MOONSHOT_MODEL         - Moonshot model (default: kimi-k2.5)");
+        println!();
+        println!("  # Hyperscaler clouds");
+        println!("  AZURE_OPENAI_ENDPOINT  - Azure OpenAI resource endpoint");
+        println!("  AZURE_OPENAI_KEY       - Azure OpenAI API key");
+        println!("  AZURE_OPENAI_DEPLOYMENT - Azure OpenAI deployment name");
+        println!("  VERTEX_PROJECT         - GCP project for Vertex AI (Gemini)");
+        println!("  VERTEX_REGION          - Vertex AI region (default: us-central1)");
+        println!("  GOOGLE_APPLICATION_CREDENTIALS - Vertex service account key file");
         println!();
         println!("  # Aggregators / Proxies");
         println!("  OPENROUTER_API_KEY     - OpenRouter aggregator");
@@ -191,12 +954,24 @@ fn list_providers() {
         println!();
         println!("  # Custom endpoint");
         println!("  OPENAI_COMPATIBLE_URL  - Custom OpenAI-compatible endpoint");
+        println!("  OPENAI_COMPATIBLE_MODE - chat (default) or completions for base models");
+        println!("  ([[provider]] tables in speed-kings.toml define further endpoints)");
         println!();
         println!("  # Local Ollama (multiple instances)");
         println!("  OLLAMA_URL             - Primary Ollama (default: http://localhost:11434)");
         println!("  OLLAMA_MODEL           - Model for primary (default: llama3.1:8b)");
         println!("  OLLAMA_RTX_URL         - Secondary RTX machine Ollama URL");
         println!("  OLLAMA_RTX_MODEL       - Model for RTX (default: llama3.1:8b)");
+        println!("  OLLAMA_RTX_NAME        - Display name for RTX (default: Local RTX (Ollama))");
+        println!("  OLLAMA_OPENAI_MODE     - true to stream chat from /v1 (default: false)");
+        println!("  OLLAMA_RTX_OPENAI_MODE - Same for RTX only (default: OLLAMA_OPENAI_MODE)");
+        println!();
+        println!("  # Embeddings (--mode embeddings)");
+        println!("  OPENAI_COMPATIBLE_EMBEDDING_MODEL - Custom endpoint (default: chat model)");
+        println!("  TOGETHER_EMBEDDING_MODEL  - Together AI (default: BAAI/bge-large-en-v1.5)");
+        println!("  FIREWORKS_EMBEDDING_MODEL - Fireworks (nomic-ai/nomic-embed-text-v1.5)");
+        println!("  OLLAMA_EMBEDDING_MODEL    - Both Ollama instances (default: nomic-embed-text)");
+        println!("  OLLAMA_RTX_EMBEDDING_MODEL - RTX Ollama (default: OLLAMA_EMBEDDING_MODEL)");
         return;
     }
 
@@ -214,4 +989,37 @@ fn list_providers() {
         }
         println!();
     }
+
+    println!("Provider groups (usable in --providers):");
+    for (group, members) in PROVIDER_GROUPS {
+        println!("  {:<14} {}", group, members.join(", "));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_free_runs_skip_confirmation() {
+        let free = CostEstimate::default();
+        let paid = CostEstimate {
+            min: 0.01,
+            expected: 0.01,
+            max: 0.02,
+        };
+
+        assert!(!needs_confirmation(false, &free));
+        assert!(needs_confirmation(false, &paid));
+        assert!(!needs_confirmation(true, &paid));
+    }
+
+    #[test]
+    fn test_prompt_is_read_without_yes() {
+        assert_eq!(
+            read_prompt("  my prompt\n".as_bytes()).unwrap(),
+            "my prompt"
+        );
+        assert!(read_prompt("\n".as_bytes()).is_err());
+    }
 }
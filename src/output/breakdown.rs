@@ -0,0 +1,143 @@
+//! ASCII latency breakdown bars for verbose output.
+
+use crate::benchmark::BenchmarkResult;
+use crate::cli::BenchmarkMode;
+
+/// Width of the bar for the slowest provider
+const BAR_WIDTH: usize = 40;
+
+const PROMPT_CHAR: char = '.';
+const QUEUE_CHAR: char = '~';
+const TTFT_CHAR: char = '#';
+const GENERATION_CHAR: char = '=';
+
+/// Average latency split into prompt send, provider-reported queue wait,
+/// the rest of the time to first token, and generation
+fn phases(result: &BenchmarkResult) -> [f64; 4] {
+    let metrics = &result.metrics;
+    let prompt = metrics.avg_time_to_prompt_ms;
+    let ttft = metrics.avg_ttft_ms;
+    let queue = metrics.avg_queue_time_ms.unwrap_or(0.0).min(ttft);
+    let generation = (metrics.avg_latency_ms - prompt - ttft).max(0.0);
+    [prompt, queue, ttft - queue, generation]
+}
+
+/// One stacked bar per successful chat result, scaled so the slowest total
+/// latency spans the full width
+///
+/// The queue phase is only shown when some provider reports queue time.
+pub fn format_latency_breakdown(results: &[BenchmarkResult]) -> String {
+    let rows: Vec<_> = results
+        .iter()
+        .filter(|r| r.is_success() && r.mode == BenchmarkMode::Chat)
+        .map(|r| (r, phases(r)))
+        .collect();
+    let slowest = rows
+        .iter()
+        .map(|(_, p)| p.iter().sum::<f64>())
+        .fold(0.0, f64::max);
+    if rows.is_empty() || slowest <= 0.0 {
+        return String::new();
+    }
+    let name_width = rows
+        .iter()
+        .map(|(r, _)| r.display_name.chars().count())
+        .max()
+        .unwrap_or(0);
+    let has_queue = rows
+        .iter()
+        .any(|(r, _)| r.metrics.avg_queue_time_ms.is_some());
+
+    let mut output = if has_queue {
+        format!(
+            "Latency breakdown ({} prompt send | {} queue | {} TTFT | {} generation):\n",
+            PROMPT_CHAR, QUEUE_CHAR, TTFT_CHAR, GENERATION_CHAR
+        )
+    } else {
+        format!(
+            "Latency breakdown ({} prompt send | {} TTFT | {} generation):\n",
+            PROMPT_CHAR, TTFT_CHAR, GENERATION_CHAR
+        )
+    };
+    for (result, phases) in rows {
+        let mut bar = String::new();
+        let chars = [PROMPT_CHAR, QUEUE_CHAR, TTFT_CHAR, GENERATION_CHAR];
+        for (ms, c) in phases.iter().zip(chars) {
+            let mut len = (ms / slowest * BAR_WIDTH as f64).round() as usize;
+            // Keep short but non-zero phases visible
+            if len == 0 && *ms >= 1.0 {
+                len = 1;
+            }
+            bar.extend(std::iter::repeat_n(c, len));
+        }
+        let [prompt, queue, ttft, generation] = phases;
+        let times = if has_queue {
+            format!(
+                "{:.0} + {:.0} + {:.0} + {:.0} ms",
+                prompt, queue, ttft, generation
+            )
+        } else {
+            format!("{:.0} + {:.0} + {:.0} ms", prompt, ttft, generation)
+        };
+        output.push_str(&format!(
+            "  {:<width$}  {:<bar_width$}  {}\n",
+            result.display_name,
+            bar,
+            times,
+            width = name_width,
+            bar_width = BAR_WIDTH + 2,
+        ));
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::benchmark::{AggregatedMetrics, SingleRunResult};
+
+    fn result(name: &str, prompt_ms: u64, ttft_ms: u64, latency_ms: u64) -> BenchmarkResult {
+        let run = SingleRunResult {
+            time_to_prompt_ms: prompt_ms,
+            output_tokens: 50,
+            output_words: 40,
+            output_chars: 200,
+            ..SingleRunResult::test_run(ttft_ms, latency_ms)
+        };
+        BenchmarkResult {
+            display_name: name.to_string(),
+            ..BenchmarkResult::test_result(name, vec![run])
+        }
+    }
+
+    #[test]
+    fn test_latency_breakdown_is_proportional() {
+        let output = format_latency_breakdown(&[
+            result("Slow", 100, 300, 1000),
+            result("Fast", 0, 100, 500),
+        ]);
+        let lines: Vec<&str> = output.lines().collect();
+
+        assert_eq!(lines.len(), 3);
+        let slow = format!("{}{}{}", ".".repeat(4), "#".repeat(12), "=".repeat(24));
+        assert!(lines[1].contains(&slow));
+        assert!(lines[1].ends_with("100 + 300 + 600 ms"));
+        let fast = format!(" {}{} ", "#".repeat(4), "=".repeat(16));
+        assert!(lines[2].contains(&fast));
+    }
+
+    #[test]
+    fn test_reported_queue_time_is_split_from_ttft() {
+        let mut queued = result("Queued", 0, 400, 1000);
+        queued.raw_results[0].queue_time_ms = Some(100);
+        queued.metrics = AggregatedMetrics::from_raw(&queued.raw_results);
+        let output = format_latency_breakdown(&[queued, result("Other", 0, 100, 500)]);
+        let lines: Vec<&str> = output.lines().collect();
+
+        assert!(lines[0].contains("~ queue"));
+        let queued = format!(" {}{}{} ", "~".repeat(4), "#".repeat(12), "=".repeat(24));
+        assert!(lines[1].contains(&queued), "{}", lines[1]);
+        assert!(lines[1].ends_with("0 + 100 + 300 + 600 ms"));
+        assert!(lines[2].ends_with("0 + 0 + 100 + 400 ms"));
+    }
+}
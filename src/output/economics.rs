@@ -0,0 +1,39 @@
+//! Per-provider cost summary for verbose output.
+
+use crate::benchmark::BenchmarkResult;
+
+/// Cost per request and output tokens per dollar for each successful
+/// result, cheapest per token first
+pub fn format_economics(results: &[BenchmarkResult]) -> String {
+    let mut rows: Vec<&BenchmarkResult> = results.iter().filter(|r| r.is_success()).collect();
+    if rows.is_empty() {
+        return String::new();
+    }
+    // Free providers (no tokens-per-dollar figure) sort first
+    rows.sort_by(|a, b| {
+        let per_dollar =
+            |r: &BenchmarkResult| r.metrics.output_tokens_per_dollar.unwrap_or(f64::INFINITY);
+        per_dollar(b).total_cmp(&per_dollar(a))
+    });
+    let name_width = rows
+        .iter()
+        .map(|r| r.display_name.chars().count())
+        .max()
+        .unwrap_or(0);
+
+    let mut output = String::from("Economics:\n");
+    for result in rows {
+        let per_dollar = match result.metrics.output_tokens_per_dollar {
+            Some(tokens) => format!("{:.0} output tokens/$", tokens),
+            None => "free".to_string(),
+        };
+        output.push_str(&format!(
+            "  {:<width$}  ${:.6}/request  {}\n",
+            result.display_name,
+            result.metrics.cost_per_request_usd,
+            per_dollar,
+            width = name_width,
+        ));
+    }
+    output
+}
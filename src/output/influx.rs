@@ -0,0 +1,139 @@
+//! InfluxDB line protocol output, one point per provider.
+
+use crate::benchmark::BenchmarkResult;
+use crate::cli::BenchmarkMode;
+
+/// Measurement name for every point
+const MEASUREMENT: &str = "inference";
+
+/// One line-protocol point per provider, timestamped in nanoseconds
+///
+/// Tags are the provider, model, and mode, plus `non_streaming`, `turn`, and
+/// `output_tokens` when set so variant rows stay separate series; fields
+/// reuse the aggregated metrics.
+/// A provider with no successful runs only reports `runs` and
+/// `success_rate`, so dashboards do not plot its zeroed averages.
+pub(crate) fn format_influx(results: &[BenchmarkResult]) -> String {
+    let mut output = String::new();
+    for result in results {
+        let metrics = &result.metrics;
+        let mode = match result.mode {
+            BenchmarkMode::Chat => "chat",
+            BenchmarkMode::Embeddings => "embeddings",
+        };
+
+        let turn = result.turn.map(|t| t.to_string());
+        let output_tokens = result.requested_output_tokens.map(|n| n.to_string());
+
+        let mut line = MEASUREMENT.to_string();
+        for (key, value) in [
+            ("provider", result.provider.as_str()),
+            ("model", result.model.as_str()),
+            ("mode", mode),
+            (
+                "non_streaming",
+                if result.non_streaming { "true" } else { "" },
+            ),
+            ("turn", turn.as_deref().unwrap_or_default()),
+            (
+                "output_tokens",
+                output_tokens.as_deref().unwrap_or_default(),
+            ),
+        ] {
+            // Empty tag values are not allowed
+            if !value.is_empty() {
+                line.push_str(&format!(",{}={}", key, escape_tag(value)));
+            }
+        }
+
+        let mut fields = vec![
+            format!("runs={}i", metrics.run_count),
+            format!("success_rate={}", metrics.success_rate),
+        ];
+        if result.is_success() {
+            if result.mode == BenchmarkMode::Chat {
+                fields.push(format!("ttft={}", metrics.avg_ttft_ms));
+                fields.push(format!("ttft_p95={}", metrics.p95_ttft_ms));
+            }
+            fields.extend([
+                format!("tok_per_sec={}", metrics.avg_tokens_per_sec),
+                format!("latency={}", metrics.avg_latency_ms),
+                format!("latency_p95={}", metrics.p95_latency_ms),
+                format!("cost={}", metrics.total_cost_usd),
+            ]);
+            if let Some(ms) = metrics.model_load_time_ms {
+                fields.push(format!("load_time={}i", ms));
+            }
+        }
+
+        line.push(' ');
+        line.push_str(&fields.join(","));
+        if let Some(ns) = result.timestamp.timestamp_nanos_opt() {
+            line.push_str(&format!(" {}", ns));
+        }
+        output.push_str(&line);
+        output.push('\n');
+    }
+    output
+}
+
+/// Escape commas, equals signs, and spaces in a tag value
+fn escape_tag(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        if matches!(c, ',' | '=' | ' ') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::benchmark::{AggregatedMetrics, SingleRunResult};
+    use chrono::{TimeZone, Utc};
+
+    #[test]
+    fn test_influx_line_protocol() {
+        let run = SingleRunResult {
+            cost_usd: 0.0003,
+            ..SingleRunResult::test_run(120, 1000)
+        };
+        let result = BenchmarkResult {
+            display_name: "Groq".to_string(),
+            model: "llama 3.1,8b=fast".to_string(),
+            timestamp: Utc.timestamp_opt(1_700_000_000, 5).unwrap(),
+            ..BenchmarkResult::test_result("groq", vec![run])
+        };
+        let mut failed = result.clone();
+        failed.provider = "cerebras".to_string();
+        failed.raw_results.clear();
+        failed.metrics = AggregatedMetrics::from_raw(&[]).with_attempts(2);
+        let mut variant = failed.clone();
+        variant.non_streaming = true;
+        variant.turn = Some(2);
+        variant.requested_output_tokens = Some(256);
+
+        let output = format_influx(&[result, failed, variant]);
+        let lines: Vec<&str> = output.lines().collect();
+
+        assert_eq!(
+            lines[0],
+            "inference,provider=groq,model=llama\\ 3.1\\,8b\\=fast,mode=chat \
+             runs=1i,success_rate=1,ttft=120,ttft_p95=120,tok_per_sec=100,\
+             latency=1000,latency_p95=1000,cost=0.0003 1700000000000000005"
+        );
+        assert_eq!(
+            lines[1],
+            "inference,provider=cerebras,model=llama\\ 3.1\\,8b\\=fast,mode=chat \
+             runs=0i,success_rate=0 1700000000000000005"
+        );
+        assert!(
+            lines[2].contains(",mode=chat,non_streaming=true,turn=2,output_tokens=256 "),
+            "{}",
+            lines[2]
+        );
+    }
+}
@@ -0,0 +1,101 @@
+//! Rate-limit discovery report for `--find-limit`.
+
+use crate::benchmark::LimitResult;
+
+/// Each provider's concurrency levels, then the throughput it sustained
+/// below the first rate-limited level
+pub fn format_limits(results: &[LimitResult]) -> String {
+    let mut output = String::from("Rate limit discovery:\n");
+    for result in results {
+        output.push_str(&format!("  {} ({})\n", result.display_name, result.model));
+        for step in &result.steps {
+            output.push_str(&format!(
+                "    {:>4} concurrent: {:>4}/{:<4} ok, {:>4} rate limited, \
+                 {:>7.2} req/s, {:>8.1} tok/s\n",
+                step.concurrency,
+                step.succeeded,
+                step.requests,
+                step.rate_limited,
+                step.requests_per_sec,
+                step.tokens_per_sec
+            ));
+        }
+
+        let sustained = result.sustained().map(|step| {
+            format!(
+                "{:.2} req/s, {:.1} tok/s at concurrency {}",
+                step.requests_per_sec, step.tokens_per_sec, step.concurrency
+            )
+        });
+        let summary = match (result.breaking_concurrency, sustained) {
+            (Some(limit), Some(sustained)) => {
+                format!(
+                    "Rate limited at concurrency {}; sustained {}",
+                    limit, sustained
+                )
+            }
+            (Some(limit), None) => format!("Rate limited at concurrency {}", limit),
+            (None, Some(sustained)) => format!("Not rate limited; best {}", sustained),
+            (None, None) => "No successful requests".to_string(),
+        };
+        output.push_str(&format!("    {}\n", summary));
+        if let Some(reason) = &result.stopped {
+            output.push_str(&format!("    Stopped: {}\n", reason));
+        }
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::benchmark::LimitStep;
+
+    fn step(concurrency: u32, rate_limited: u32) -> LimitStep {
+        LimitStep {
+            concurrency,
+            requests: concurrency,
+            succeeded: concurrency - rate_limited,
+            rate_limited,
+            failed: 0,
+            elapsed_ms: 1000,
+            requests_per_sec: (concurrency - rate_limited) as f64,
+            tokens_per_sec: (concurrency - rate_limited) as f64 * 100.0,
+            cost_usd: 0.0,
+        }
+    }
+
+    #[test]
+    fn test_limits_report_sustained_throughput() {
+        let limited = LimitResult {
+            provider: "groq".to_string(),
+            display_name: "Groq".to_string(),
+            model: "llama".to_string(),
+            steps: vec![step(1, 0), step(2, 0), step(4, 3)],
+            breaking_concurrency: Some(4),
+            stopped: None,
+        };
+        let unavailable = LimitResult {
+            provider: "local".to_string(),
+            display_name: "Local".to_string(),
+            model: "llama".to_string(),
+            steps: Vec::new(),
+            breaking_concurrency: None,
+            stopped: Some("Provider not available".to_string()),
+        };
+
+        let output = format_limits(&[limited, unavailable]);
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines[1], "  Groq (llama)");
+        assert_eq!(
+            lines[4],
+            "       4 concurrent:    1/4    ok,    3 rate limited,    1.00 req/s,    100.0 tok/s"
+        );
+        assert_eq!(
+            lines[5],
+            "    Rate limited at concurrency 4; sustained 2.00 req/s, 200.0 tok/s at concurrency 2"
+        );
+        assert_eq!(lines[7], "    No successful requests");
+        assert_eq!(lines[8], "    Stopped: Provider not available");
+    }
+}
@@ -1,43 +1,347 @@
 //! Output formatting for benchmark results.
 
+mod breakdown;
+mod economics;
+mod influx;
+mod limit;
+mod sparkline;
+mod summary;
+mod sweep;
 mod table;
 
+pub use breakdown::format_latency_breakdown;
+pub use economics::format_economics;
+pub use limit::format_limits;
+pub use summary::{Summary, summarize};
+pub use sweep::format_max_tokens_sweep;
 pub use table::format_table;
 
-use crate::benchmark::BenchmarkResult;
-use crate::cli::OutputFormat;
+use crate::benchmark::{BenchmarkConfig, BenchmarkResult, ResultStatus, ScoreWeights, score};
+use crate::cli::{Column, OutputFormat};
 use chrono::Utc;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+
+/// Version of the JSON output shape, bumped only on breaking changes
+pub const JSON_SCHEMA_VERSION: &str = "1";
+
+/// Presentation options that do not affect the benchmark itself
+#[derive(Debug, Clone, Default)]
+pub struct OutputOptions {
+    /// Add a "% of fastest" throughput column to tables
+    pub relative: bool,
+    /// Provider ID every other provider is compared against in tables
+    pub baseline: Option<String>,
+    /// Weights for a Score column ranking the providers
+    pub rank: Option<ScoreWeights>,
+    /// Table and markdown columns in order; empty shows every one that applies
+    pub columns: Vec<Column>,
+    /// Keep per-iteration `raw_results` in JSON output
+    pub include_raw: bool,
+    /// Write JSON on a single line instead of pretty-printed
+    pub json_compact: bool,
+    /// Add detail notes to tables (e.g. captured output previews)
+    pub verbose: bool,
+    /// Render tables without ANSI colors or bold text
+    pub no_color: bool,
+    /// Wall-clock time of the whole benchmark, recorded in the JSON envelope
+    pub total_duration_ms: Option<u64>,
+}
+
+/// Rows for tables: a result with cold/warm metrics becomes a "(cold)" row
+/// and a "(warm)" row, each with its own runs
+pub(crate) fn cold_warm_rows(results: &[BenchmarkResult]) -> Cow<'_, [BenchmarkResult]> {
+    if results.iter().all(|r| r.cold_warm.is_none()) {
+        return Cow::Borrowed(results);
+    }
+    let mut rows = Vec::new();
+    for result in results {
+        let Some(split) = &result.cold_warm else {
+            rows.push(result.clone());
+            continue;
+        };
+        for (phase, metrics, cold) in [("cold", &split.cold, true), ("warm", &split.warm, false)] {
+            let mut row = result.clone();
+            row.display_name = format!("{} ({})", result.display_name, phase);
+            row.metrics = metrics.clone();
+            row.raw_results.retain(|r| r.cold == cold);
+            row.cold_warm = None;
+            rows.push(row);
+        }
+    }
+    Cow::Owned(rows)
+}
 
 /// Format benchmark results according to the specified output format
-pub fn format_results(results: &[BenchmarkResult], format: OutputFormat) -> String {
+pub fn format_results(
+    results: &[BenchmarkResult],
+    format: OutputFormat,
+    config: &BenchmarkConfig,
+    options: &OutputOptions,
+) -> String {
     match format {
-        OutputFormat::Table => format_table(results),
-        OutputFormat::Json => format_json(results),
-        OutputFormat::Markdown => format_markdown(results),
+        OutputFormat::Table => format_table(results, options),
+        OutputFormat::Json => format_json(results, config, options),
+        OutputFormat::Markdown => format_markdown(results, options),
         OutputFormat::Csv => format_csv(results),
+        OutputFormat::Influx => influx::format_influx(results),
     }
 }
 
 /// JSON output with full metadata
-#[derive(Serialize)]
-struct JsonOutput<'a> {
-    timestamp: String,
-    version: &'static str,
-    results: &'a [BenchmarkResult],
+///
+/// `schema_version` tracks the shape of this document independently of the
+/// crate version; additive changes keep it, renames and removals bump it.
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[derive(Debug, Serialize, Deserialize)]
+pub struct JsonOutput<'a> {
+    pub schema_version: Cow<'a, str>,
+    pub timestamp: String,
+    /// Crate version that produced the output
+    pub version: Cow<'a, str>,
+    /// Settings the results were produced with
+    pub config: Cow<'a, BenchmarkConfig>,
+    /// Wall-clock time of the whole benchmark, all providers and runs
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub total_duration_ms: Option<u64>,
+    /// Whether every provider's status is `ok`
+    #[serde(default)]
+    pub all_succeeded: bool,
+    /// Providers that hit errors, in result order
+    #[serde(default)]
+    pub errors: Vec<ErrorSummary>,
+    pub results: Cow<'a, [BenchmarkResult]>,
+}
+
+/// Errors of one provider, summarized for the JSON envelope
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ErrorSummary {
+    pub provider: String,
+    /// `partial` or `failed`
+    pub status: ResultStatus,
+    /// Number of errors in the provider's result
+    pub count: usize,
+    /// First error, usually the one that explains the rest
+    pub first_error: String,
+}
+
+/// JSON Schema of [`JsonOutput`], pretty-printed
+#[cfg(feature = "schema")]
+pub fn json_schema() -> String {
+    let schema = schemars::schema_for!(JsonOutput<'static>);
+    serde_json::to_string_pretty(&schema).unwrap_or_else(|e| format!("JSON error: {}", e))
 }
 
-fn format_json(results: &[BenchmarkResult]) -> String {
+fn format_json(
+    results: &[BenchmarkResult],
+    config: &BenchmarkConfig,
+    options: &OutputOptions,
+) -> String {
+    let errors = results
+        .iter()
+        .filter_map(|r| {
+            Some(ErrorSummary {
+                provider: r.provider.clone(),
+                status: r.status,
+                count: r.errors.len(),
+                first_error: r.errors.first()?.clone(),
+            })
+        })
+        .collect();
+    // Per-iteration results dominate the size of large runs
+    let results = if options.include_raw {
+        Cow::Borrowed(results)
+    } else {
+        Cow::Owned(
+            results
+                .iter()
+                .map(|r| BenchmarkResult {
+                    raw_results: Vec::new(),
+                    ..r.clone()
+                })
+                .collect(),
+        )
+    };
     let output = JsonOutput {
+        schema_version: Cow::Borrowed(JSON_SCHEMA_VERSION),
         timestamp: Utc::now().to_rfc3339(),
-        version: env!("CARGO_PKG_VERSION"),
+        version: Cow::Borrowed(env!("CARGO_PKG_VERSION")),
+        config: Cow::Borrowed(config),
+        total_duration_ms: options.total_duration_ms,
+        all_succeeded: results.iter().all(|r| r.status == ResultStatus::Ok),
+        errors,
         results,
     };
 
-    serde_json::to_string_pretty(&output).unwrap_or_else(|e| format!("JSON error: {}", e))
+    let json = if options.json_compact {
+        serde_json::to_string(&output)
+    } else {
+        serde_json::to_string_pretty(&output)
+    };
+    json.unwrap_or_else(|e| format!("JSON error: {}", e))
+}
+
+/// Throughput as a percentage of the fastest successful result
+pub(crate) fn percent_of_fastest(result: &BenchmarkResult, results: &[BenchmarkResult]) -> f64 {
+    let fastest = results
+        .iter()
+        .filter(|r| r.is_success())
+        .map(|r| r.metrics.avg_tokens_per_sec)
+        .fold(0.0, f64::max);
+    if fastest > 0.0 {
+        result.metrics.avg_tokens_per_sec / fastest * 100.0
+    } else {
+        0.0
+    }
+}
+
+/// The baseline result, if one was chosen and it succeeded
+pub(crate) fn baseline_result<'a>(
+    results: &'a [BenchmarkResult],
+    options: &OutputOptions,
+) -> Option<&'a BenchmarkResult> {
+    let id = options.baseline.as_deref()?;
+    results
+        .iter()
+        .find(|r| r.provider == id)
+        .filter(|r| r.is_success())
+}
+
+/// Throughput, latency, and cost relative to the baseline, e.g.
+/// "2.3× tok/s, 0.5× latency, 0.6× cost"
+///
+/// Cost is left out when the baseline is free.
+pub(crate) fn versus_baseline(result: &BenchmarkResult, baseline: &BenchmarkResult) -> String {
+    if result.provider == baseline.provider {
+        return "baseline".to_string();
+    }
+    let ratio = |value: f64, reference: f64| {
+        if reference > 0.0 {
+            Some(value / reference)
+        } else {
+            None
+        }
+    };
+    let (ours, theirs) = (&result.metrics, &baseline.metrics);
+    let mut parts = Vec::new();
+    if let Some(r) = ratio(ours.avg_tokens_per_sec, theirs.avg_tokens_per_sec) {
+        parts.push(format!("{:.1}× tok/s", r));
+    }
+    if let Some(r) = ratio(ours.avg_latency_ms, theirs.avg_latency_ms) {
+        parts.push(format!("{:.1}× latency", r));
+    }
+    if let Some(r) = ratio(ours.cost_per_request_usd, theirs.cost_per_request_usd) {
+        parts.push(format!("{:.1}× cost", r));
+    }
+    if parts.is_empty() {
+        "-".to_string()
+    } else {
+        parts.join(", ")
+    }
+}
+
+/// Note explaining why a requested baseline column is missing
+pub(crate) fn baseline_failed_note(
+    results: &[BenchmarkResult],
+    options: &OutputOptions,
+) -> Option<String> {
+    let id = options.baseline.as_deref()?;
+    baseline_result(results, options).is_none().then(|| {
+        format!(
+            "Baseline {} produced no successful runs; showing absolute values only",
+            id
+        )
+    })
+}
+
+/// Columns to render: the `--columns` selection, else each of `defaults`
+/// that applies to these results
+pub(crate) fn select_columns(
+    results: &[BenchmarkResult],
+    options: &OutputOptions,
+    defaults: &[Column],
+) -> Vec<Column> {
+    // Without a successful baseline there is nothing to compare against;
+    // baseline_failed_note explains the gap
+    let has_baseline = baseline_result(results, options).is_some();
+    let has_score = options.rank.is_some();
+    if !options.columns.is_empty() {
+        return options
+            .columns
+            .iter()
+            .copied()
+            .filter(|&c| match c {
+                Column::Baseline => has_baseline,
+                Column::Score => has_score,
+                _ => true,
+            })
+            .collect();
+    }
+    defaults
+        .iter()
+        .copied()
+        .filter(|column| match column {
+            Column::Relative => options.relative,
+            Column::Baseline => has_baseline,
+            Column::Load => results.iter().any(|r| load_time_ms(r).is_some()),
+            // Success rate only tells something once there are several attempts
+            Column::Success => results.iter().any(|r| r.metrics.attempted_count > 1),
+            // Same as tok/s unless some provider had failures
+            Column::EffectiveTokPerSec => results
+                .iter()
+                .any(|r| r.is_success() && r.metrics.success_rate < 1.0),
+            Column::Trend => results.iter().any(|r| run_history(r).len() > 1),
+            Column::Score => has_score,
+            _ => true,
+        })
+        .collect()
+}
+
+/// Average tok/s of each successful `--runs` run, oldest first
+pub(crate) fn run_history(result: &BenchmarkResult) -> &[f64] {
+    result
+        .run_spread
+        .as_ref()
+        .map_or(&[], |spread| &spread.per_run_tokens_per_sec)
 }
 
-fn format_markdown(results: &[BenchmarkResult]) -> String {
+/// `--rank` score of a result on a 0-100 scale, "-" if it has none
+pub(crate) fn score_cell(
+    result: &BenchmarkResult,
+    results: &[BenchmarkResult],
+    options: &OutputOptions,
+) -> String {
+    options
+        .rank
+        .and_then(|weights| score(result, results, &weights))
+        .map_or("-".to_string(), |s| format!("{:.0}", s * 100.0))
+}
+
+/// Model load time to show in a Load column: the in-run load, or the
+/// up-front `--warmup-shared` preload
+pub(crate) fn load_time_ms(result: &BenchmarkResult) -> Option<u64> {
+    result.metrics.model_load_time_ms.or(result.preload_time_ms)
+}
+
+/// Markdown columns when `--columns` is not given
+const MARKDOWN_COLUMNS: &[Column] = &[
+    Column::Provider,
+    Column::Model,
+    Column::Score,
+    Column::Ttft,
+    Column::TokPerSec,
+    Column::EffectiveTokPerSec,
+    Column::Relative,
+    Column::Baseline,
+    Column::Latency,
+    Column::Load,
+    Column::Cost,
+    Column::Trend,
+];
+
+fn format_markdown(results: &[BenchmarkResult], options: &OutputOptions) -> String {
+    let results = &*cold_warm_rows(results);
     let mut output = String::new();
 
     output.push_str("# Inference Benchmark Results\n\n");
@@ -46,26 +350,71 @@ fn format_markdown(results: &[BenchmarkResult]) -> String {
         Utc::now().format("%Y-%m-%d %H:%M UTC")
     ));
 
-    output.push_str("| Provider | Model | TTFT | Throughput | Latency | Cost |\n");
-    output.push_str("|----------|-------|------|------------|---------|------|\n");
+    let baseline = baseline_result(results, options);
+    let columns = select_columns(results, options, MARKDOWN_COLUMNS);
+
+    let header: Vec<String> = columns
+        .iter()
+        .map(|column| match column {
+            Column::Provider => "Provider".to_string(),
+            Column::Model => "Model".to_string(),
+            Column::Ttft => "TTFT".to_string(),
+            Column::TtftP95 => "TTFT p95".to_string(),
+            Column::TokPerSec => "Throughput".to_string(),
+            Column::EffectiveTokPerSec => "Effective".to_string(),
+            Column::Relative => "% of Fastest".to_string(),
+            Column::Baseline => {
+                baseline.map_or(String::new(), |b| format!("vs {}", b.display_name))
+            }
+            Column::Latency => "Latency".to_string(),
+            Column::Load => "Load".to_string(),
+            Column::Cost => "Cost".to_string(),
+            Column::Success => "Success".to_string(),
+            Column::Trend => "Trend".to_string(),
+            Column::Score => "Score".to_string(),
+        })
+        .collect();
+    push_markdown_row(&mut output, &header);
+    for h in &header {
+        output.push('|');
+        output.push_str(&"-".repeat(h.len() + 2));
+    }
+    output.push_str("|\n");
 
     for result in results {
-        if result.is_success() {
-            output.push_str(&format!(
-                "| {} | {} | {}ms | {:.0} tok/s | {}ms | ${:.4} |\n",
-                result.display_name,
-                result.model,
-                result.metrics.avg_ttft_ms as u64,
-                result.metrics.avg_tokens_per_sec,
-                result.metrics.avg_latency_ms as u64,
-                result.metrics.total_cost_usd,
-            ));
-        } else {
-            output.push_str(&format!(
-                "| {} | {} | - | - | - | - |\n",
-                result.display_name, result.model,
-            ));
-        }
+        let success = result.is_success();
+        let cell = |value: String| if success { value } else { "-".to_string() };
+
+        let row: Vec<String> = columns
+            .iter()
+            .map(|column| match column {
+                Column::Provider => result.display_name.clone(),
+                Column::Model => result.model.clone(),
+                Column::Ttft => cell(format!("{}ms", result.metrics.avg_ttft_ms as u64)),
+                Column::TtftP95 => cell(format!("{}ms", result.metrics.p95_ttft_ms as u64)),
+                Column::TokPerSec => {
+                    cell(format!("{:.0} tok/s", result.metrics.avg_tokens_per_sec))
+                }
+                Column::EffectiveTokPerSec => cell(format!(
+                    "{:.0} tok/s",
+                    result.metrics.effective_tokens_per_sec
+                )),
+                Column::Relative => cell(format!("{:.0}%", percent_of_fastest(result, results))),
+                Column::Baseline => {
+                    baseline.map_or(String::new(), |b| cell(versus_baseline(result, b)))
+                }
+                Column::Latency => cell(format!("{}ms", result.metrics.avg_latency_ms as u64)),
+                Column::Load => {
+                    load_time_ms(result).map_or("-".to_string(), |ms| format!("{}ms", ms))
+                }
+                Column::Cost => cell(format!("${:.4}", result.metrics.total_cost_usd)),
+                Column::Success if result.metrics.attempted_count == 0 => "-".to_string(),
+                Column::Success => format!("{:.0}%", result.metrics.success_rate * 100.0),
+                Column::Trend => sparkline::sparkline(run_history(result)),
+                Column::Score => score_cell(result, results, options),
+            })
+            .collect();
+        push_markdown_row(&mut output, &row);
     }
 
     // Add notes section for model load times
@@ -73,6 +422,10 @@ fn format_markdown(results: &[BenchmarkResult]) -> String {
         .iter()
         .any(|r| r.metrics.model_load_time_ms.is_some());
 
+    if let Some(note) = baseline_failed_note(results, options) {
+        output.push_str(&format!("\n*{}*\n", note));
+    }
+
     if has_load_times {
         output.push_str("\n**Notes:**\n");
         for result in results {
@@ -88,16 +441,28 @@ fn format_markdown(results: &[BenchmarkResult]) -> String {
     output
 }
 
+fn push_markdown_row<S: AsRef<str>>(output: &mut String, cells: &[S]) {
+    output.push('|');
+    for cell in cells {
+        output.push(' ');
+        output.push_str(cell.as_ref());
+        output.push_str(" |");
+    }
+    output.push('\n');
+}
+
 fn format_csv(results: &[BenchmarkResult]) -> String {
     let mut output = String::new();
 
     // Header
-    output.push_str("provider,model,ttft_ms,tokens_per_sec,latency_ms,cost_usd,runs\n");
+    output.push_str(
+        "provider,model,ttft_ms,tokens_per_sec,latency_ms,cost_usd,runs,success_rate,load_ms,words_per_sec,chars_per_sec,effective_tokens_per_sec,non_streaming,turn,requested_output_tokens\n",
+    );
 
     // Data rows
     for result in results {
         output.push_str(&format!(
-            "{},{},{:.0},{:.1},{:.0},{:.6},{}\n",
+            "{},{},{:.0},{:.1},{:.0},{:.6},{},{:.3},{},{:.1},{:.1},{:.1},{},{},{}\n",
             result.provider,
             result.model,
             result.metrics.avg_ttft_ms,
@@ -105,8 +470,304 @@ fn format_csv(results: &[BenchmarkResult]) -> String {
             result.metrics.avg_latency_ms,
             result.metrics.total_cost_usd,
             result.metrics.run_count,
+            result.metrics.success_rate,
+            load_time_ms(result).map_or(String::new(), |ms| ms.to_string()),
+            result.metrics.avg_words_per_sec,
+            result.metrics.avg_chars_per_sec,
+            result.metrics.effective_tokens_per_sec,
+            result.non_streaming,
+            result.turn.map_or(String::new(), |t| t.to_string()),
+            result
+                .requested_output_tokens
+                .map_or(String::new(), |n| n.to_string()),
         ));
     }
 
     output
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::benchmark::{AggregatedMetrics, ColdWarmMetrics, ResultStatus, SingleRunResult};
+    use crate::cli::PromptSize;
+
+    #[test]
+    fn test_json_envelope_round_trip() {
+        let config = BenchmarkConfig {
+            iterations: 3,
+            prompt_size: PromptSize::Medium,
+            concurrency: 2,
+            ..Default::default()
+        };
+
+        let json = format_json(&[], &config, &OutputOptions::default());
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        for field in [
+            "schema_version",
+            "timestamp",
+            "version",
+            "config",
+            "all_succeeded",
+            "errors",
+            "results",
+        ] {
+            assert!(value.get(field).is_some(), "missing field {}", field);
+        }
+        assert_eq!(value["config"]["iterations"], 3);
+        assert_eq!(value["config"]["prompt_size"], "medium");
+        assert_eq!(value["config"]["concurrency"], 2);
+
+        let parsed: JsonOutput = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.schema_version, JSON_SCHEMA_VERSION);
+        assert_eq!(parsed.config.iterations, 3);
+        assert!(parsed.results.is_empty());
+    }
+
+    #[test]
+    fn test_raw_results_only_with_include_raw() {
+        let run = SingleRunResult {
+            time_to_prompt_ms: 5,
+            output_tokens: 50,
+            output_words: 40,
+            output_chars: 200,
+            ..SingleRunResult::test_run(50, 500)
+        };
+        let result = BenchmarkResult {
+            display_name: "Groq".to_string(),
+            ..BenchmarkResult::test_result("groq", vec![run])
+        };
+        let config = BenchmarkConfig::default();
+        let results = std::slice::from_ref(&result);
+
+        let slim = format_json(results, &config, &OutputOptions::default());
+        let parsed: JsonOutput = serde_json::from_str(&slim).unwrap();
+        assert!(parsed.results[0].raw_results.is_empty());
+        assert!(parsed.results[0].is_success());
+
+        let options = OutputOptions {
+            include_raw: true,
+            ..Default::default()
+        };
+        let full = format_json(results, &config, &options);
+        let parsed: JsonOutput = serde_json::from_str(&full).unwrap();
+        assert_eq!(parsed.results[0].raw_results.len(), 1);
+    }
+
+    #[test]
+    fn test_json_compact_is_one_line() {
+        let config = BenchmarkConfig::default();
+        let options = OutputOptions {
+            json_compact: true,
+            total_duration_ms: Some(1234),
+            ..Default::default()
+        };
+
+        let compact = format_json(&[], &config, &options);
+        assert!(!compact.contains('\n'));
+        let parsed: JsonOutput = serde_json::from_str(&compact).unwrap();
+        assert_eq!(parsed.schema_version, JSON_SCHEMA_VERSION);
+        assert_eq!(parsed.total_duration_ms, Some(1234));
+
+        assert!(format_json(&[], &config, &OutputOptions::default()).contains('\n'));
+    }
+
+    fn chat_result(provider: &str, latency_ms: u64, cost_usd: f64) -> BenchmarkResult {
+        let run = SingleRunResult {
+            cost_usd,
+            ..SingleRunResult::test_run(50, latency_ms)
+        };
+        BenchmarkResult::test_result(provider, vec![run])
+    }
+
+    #[test]
+    fn test_json_reports_status() {
+        let mut partial = chat_result("cerebras", 500, 0.0);
+        partial.errors = vec!["Iteration 2: Rate limited by provider".to_string()];
+        partial.status = ResultStatus::Partial;
+        let mut failed = chat_result("local", 500, 0.0);
+        failed.raw_results.clear();
+        failed.errors = vec!["Provider not available".to_string()];
+        failed.status = ResultStatus::Failed;
+        let config = BenchmarkConfig::default();
+        let options = OutputOptions::default();
+
+        let json = format_json(&[chat_result("groq", 500, 0.0)], &config, &options);
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["all_succeeded"], true);
+        assert_eq!(value["results"][0]["status"], "ok");
+
+        let results = [chat_result("groq", 500, 0.0), partial, failed];
+        let parsed: JsonOutput =
+            serde_json::from_str(&format_json(&results, &config, &options)).unwrap();
+        assert!(!parsed.all_succeeded);
+        assert_eq!(parsed.errors.len(), 2);
+        assert_eq!(parsed.errors[0].status, ResultStatus::Partial);
+        assert_eq!(parsed.errors[1].provider, "local");
+        assert_eq!(parsed.errors[1].first_error, "Provider not available");
+        assert_eq!(parsed.results[2].status, ResultStatus::Failed);
+    }
+
+    #[test]
+    fn test_json_without_status_derives_it() {
+        let mut partial = chat_result("cerebras", 500, 0.0);
+        partial.errors = vec!["Iteration 2: Rate limited by provider".to_string()];
+        let mut failed = chat_result("local", 500, 0.0);
+        failed.raw_results.clear();
+        failed.metrics = AggregatedMetrics::from_raw(&[]);
+        failed.errors = vec!["Provider not available".to_string()];
+        let results = [chat_result("groq", 500, 0.0), partial, failed];
+
+        // As written before results recorded a status, and without raw results
+        let json = format_json(
+            &results,
+            &BenchmarkConfig::default(),
+            &OutputOptions::default(),
+        );
+        let mut value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        for result in value["results"].as_array_mut().unwrap() {
+            result.as_object_mut().unwrap().remove("status");
+        }
+
+        let parsed: JsonOutput = serde_json::from_value(value).unwrap();
+        let statuses: Vec<_> = parsed.results.iter().map(|r| r.status).collect();
+        assert_eq!(
+            statuses,
+            [
+                ResultStatus::Ok,
+                ResultStatus::Partial,
+                ResultStatus::Failed
+            ]
+        );
+    }
+
+    #[test]
+    fn test_versus_baseline_ratios() {
+        let mut failed = chat_result("local", 1000, 0.0);
+        failed.raw_results.clear();
+        failed.metrics = AggregatedMetrics::from_raw(&[]);
+        let results = [
+            chat_result("groq", 1000, 0.01),
+            chat_result("cerebras", 250, 0.006),
+            failed,
+        ];
+        let options = OutputOptions {
+            baseline: Some("groq".to_string()),
+            ..Default::default()
+        };
+
+        let baseline = baseline_result(&results, &options).unwrap();
+        assert_eq!(versus_baseline(&results[0], baseline), "baseline");
+        assert_eq!(
+            versus_baseline(&results[1], baseline),
+            "4.0× tok/s, 0.2× latency, 0.6× cost"
+        );
+        assert!(format_markdown(&results, &options).contains("| vs GROQ |"));
+
+        // A failed baseline drops the comparison instead of dividing by zero
+        let options = OutputOptions {
+            baseline: Some("local".to_string()),
+            ..Default::default()
+        };
+        assert!(baseline_result(&results, &options).is_none());
+        assert!(
+            baseline_failed_note(&results, &options)
+                .unwrap()
+                .starts_with("Baseline local")
+        );
+    }
+
+    #[test]
+    fn test_cold_warm_rows() {
+        let mut result = chat_result("groq", 500, 0.0);
+        let mut cold = result.raw_results[0].clone();
+        cold.cold = true;
+        cold.total_latency_ms = 2000;
+        result.raw_results.insert(0, cold);
+        result.metrics = AggregatedMetrics::from_raw(&result.raw_results);
+        result.cold_warm = ColdWarmMetrics::from_raw(&result.raw_results, None);
+        let results = [result, chat_result("cerebras", 250, 0.0)];
+
+        let rows = cold_warm_rows(&results);
+        let names: Vec<&str> = rows.iter().map(|r| r.display_name.as_str()).collect();
+        assert_eq!(names, ["GROQ (cold)", "GROQ (warm)", "CEREBRAS"]);
+        assert_eq!(rows[0].metrics.avg_latency_ms, 2000.0);
+        assert_eq!(rows[1].metrics.avg_latency_ms, 500.0);
+        assert_eq!(rows[1].raw_results.len(), 1);
+
+        // Results without the split are passed through untouched
+        assert!(matches!(cold_warm_rows(&results[1..]), Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn test_selected_columns() {
+        let mut failed = chat_result("cerebras", 1000, 0.0);
+        failed.raw_results.clear();
+        failed.metrics = AggregatedMetrics::from_raw(&[]).with_attempts(2);
+        let results = [chat_result("groq", 500, 0.002), failed];
+
+        let defaults = select_columns(&results, &OutputOptions::default(), MARKDOWN_COLUMNS);
+        assert_eq!(
+            defaults,
+            [
+                Column::Provider,
+                Column::Model,
+                Column::Ttft,
+                Column::TokPerSec,
+                Column::Latency,
+                Column::Cost
+            ]
+        );
+
+        // A baseline column only appears with a successful baseline
+        let options = OutputOptions {
+            columns: vec![
+                Column::Provider,
+                Column::Baseline,
+                Column::Cost,
+                Column::Success,
+            ],
+            baseline: Some("cerebras".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            select_columns(&results, &options, MARKDOWN_COLUMNS),
+            [Column::Provider, Column::Cost, Column::Success]
+        );
+        let markdown = format_markdown(&results, &options);
+        assert!(markdown.contains("| Provider | Cost | Success |\n"));
+        assert!(markdown.contains("| GROQ | $0.0020 | 100% |\n"));
+        assert!(markdown.contains("| CEREBRAS | - | 0% |\n"));
+    }
+
+    #[test]
+    fn test_csv_distinguishes_variant_rows() {
+        let streamed =
+            BenchmarkResult::test_result("groq", vec![SingleRunResult::test_run(50, 500)]);
+        let unstreamed = BenchmarkResult {
+            non_streaming: true,
+            turn: Some(2),
+            requested_output_tokens: Some(256),
+            ..streamed.clone()
+        };
+
+        let output = format_csv(&[streamed, unstreamed]);
+        let lines: Vec<&str> = output.lines().collect();
+        assert!(lines[0].ends_with(",non_streaming,turn,requested_output_tokens"));
+        assert!(lines[1].ends_with(",false,,"), "{}", lines[1]);
+        assert!(lines[2].ends_with(",true,2,256"), "{}", lines[2]);
+    }
+
+    #[cfg(feature = "schema")]
+    #[test]
+    fn test_json_schema_describes_results() {
+        let schema: serde_json::Value = serde_json::from_str(&json_schema()).unwrap();
+        assert_eq!(schema["title"], "JsonOutput");
+        for name in ["BenchmarkConfig", "BenchmarkResult", "AggregatedMetrics"] {
+            assert!(schema["$defs"].get(name).is_some(), "missing {}", name);
+        }
+        // Fields the runner never serializes stay out of the contract
+        let config = &schema["$defs"]["BenchmarkConfig"]["properties"];
+        assert!(config.get("corpus").is_none());
+    }
+}
@@ -0,0 +1,46 @@
+//! Unicode sparklines for per-run throughput history.
+
+/// Block characters from lowest to highest
+const BLOCKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// Most recent values drawn; older runs scroll off the left
+pub(crate) const SPARKLINE_LEN: usize = 20;
+
+/// One block per value, scaled between the smallest and largest of the last
+/// `SPARKLINE_LEN` values
+///
+/// A flat series is drawn at mid height so it does not read as a drop.
+pub(crate) fn sparkline(values: &[f64]) -> String {
+    let recent = &values[values.len().saturating_sub(SPARKLINE_LEN)..];
+    let min = recent.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = recent.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    let top = (BLOCKS.len() - 1) as f64;
+    recent
+        .iter()
+        .map(|&value| {
+            let level = if max > min {
+                ((value - min) / (max - min) * top).round()
+            } else {
+                top / 2.0
+            };
+            BLOCKS[level as usize]
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sparkline_scales_recent_values() {
+        assert_eq!(sparkline(&[100.0, 160.0, 140.0, 240.0]), "▁▄▃█");
+        assert_eq!(sparkline(&[50.0, 50.0]), "▄▄");
+        assert_eq!(sparkline(&[]), "");
+
+        let long: Vec<f64> = (0..30).map(f64::from).collect();
+        let line = sparkline(&long);
+        assert_eq!(line.chars().count(), SPARKLINE_LEN);
+        assert!(line.starts_with('▁') && line.ends_with('█'));
+    }
+}
@@ -0,0 +1,117 @@
+//! Bottom-line summary across all successful providers.
+
+use crate::benchmark::BenchmarkResult;
+
+/// Totals and extremes across the successful providers of a benchmark
+#[derive(Debug, Clone, PartialEq)]
+pub struct Summary {
+    /// Providers with at least one successful run
+    pub provider_count: usize,
+    /// Median of the providers' average tok/s
+    pub median_tokens_per_sec: f64,
+    /// Display name of the provider with the highest average tok/s
+    pub fastest: Option<String>,
+    /// Display name of the provider with the lowest average tok/s
+    pub slowest: Option<String>,
+    /// Cost of every provider's runs combined (USD)
+    pub total_cost_usd: f64,
+    /// Output tokens generated across all runs, reasoning included
+    pub total_output_tokens: u64,
+}
+
+/// Summarize the successful results
+pub fn summarize(results: &[BenchmarkResult]) -> Summary {
+    let mut successes: Vec<&BenchmarkResult> = results.iter().filter(|r| r.is_success()).collect();
+    successes.sort_by(|a, b| {
+        a.metrics
+            .avg_tokens_per_sec
+            .total_cmp(&b.metrics.avg_tokens_per_sec)
+    });
+
+    let median_tokens_per_sec = match successes.len() {
+        0 => 0.0,
+        n if n % 2 == 1 => successes[n / 2].metrics.avg_tokens_per_sec,
+        n => {
+            (successes[n / 2 - 1].metrics.avg_tokens_per_sec
+                + successes[n / 2].metrics.avg_tokens_per_sec)
+                / 2.0
+        }
+    };
+
+    Summary {
+        provider_count: successes.len(),
+        median_tokens_per_sec,
+        fastest: successes.last().map(|r| r.display_name.clone()),
+        slowest: successes.first().map(|r| r.display_name.clone()),
+        total_cost_usd: successes.iter().map(|r| r.metrics.total_cost_usd).sum(),
+        total_output_tokens: successes
+            .iter()
+            .flat_map(|r| &r.raw_results)
+            .map(|run| (run.output_tokens + run.reasoning_tokens) as u64)
+            .sum(),
+    }
+}
+
+/// Summary block for the table output; empty unless several providers
+/// succeeded
+pub(crate) fn format_summary(results: &[BenchmarkResult]) -> String {
+    let summary = summarize(results);
+    if summary.provider_count < 2 {
+        return String::new();
+    }
+    let (Some(fastest), Some(slowest)) = (&summary.fastest, &summary.slowest) else {
+        return String::new();
+    };
+    format!(
+        "All {} providers: median {:.0} tok/s, fastest {}, slowest {}, \
+         {} tokens generated for ${:.4}",
+        summary.provider_count,
+        summary.median_tokens_per_sec,
+        fastest,
+        slowest,
+        summary.total_output_tokens,
+        summary.total_cost_usd
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::benchmark::{AggregatedMetrics, SingleRunResult};
+
+    fn result(name: &str, latency_ms: u64, cost_usd: f64) -> BenchmarkResult {
+        let run = SingleRunResult {
+            cost_usd,
+            ..SingleRunResult::test_run(50, latency_ms)
+        };
+        BenchmarkResult {
+            display_name: name.to_string(),
+            ..BenchmarkResult::test_result(&name.to_lowercase(), vec![run])
+        }
+    }
+
+    #[test]
+    fn test_summarize_across_providers() {
+        let mut failed = result("Broken", 1000, 0.0);
+        failed.raw_results.clear();
+        failed.metrics = AggregatedMetrics::from_raw(&[]);
+        let results = [
+            result("Slow", 2000, 0.01),
+            result("Fast", 250, 0.02),
+            failed,
+            result("Middle", 1000, 0.03),
+        ];
+
+        let summary = summarize(&results);
+        assert_eq!(summary.provider_count, 3);
+        assert_eq!(summary.fastest.as_deref(), Some("Fast"));
+        assert_eq!(summary.slowest.as_deref(), Some("Slow"));
+        assert_eq!(summary.median_tokens_per_sec, 100.0);
+        assert_eq!(summary.total_output_tokens, 300);
+        assert!((summary.total_cost_usd - 0.06).abs() < 1e-9);
+
+        // A lone provider has nothing to summarize
+        assert!(format_summary(&results[..1]).is_empty());
+        assert!(format_summary(&results).starts_with("All 3 providers: median 100 tok/s"));
+    }
+}
@@ -0,0 +1,107 @@
+//! Throughput against output length for `--max-tokens-sweep`.
+
+use super::sparkline::sparkline;
+use crate::benchmark::BenchmarkResult;
+
+/// Tok/s at each swept `max_tokens` value, grouped by provider in result
+/// order, with a sparkline of the curve
+///
+/// Rows without a requested output length are left out; empty if none
+/// have one.
+pub fn format_max_tokens_sweep(results: &[BenchmarkResult]) -> String {
+    let mut providers: Vec<(&str, Vec<&BenchmarkResult>)> = Vec::new();
+    for result in results
+        .iter()
+        .filter(|r| r.requested_output_tokens.is_some())
+    {
+        match providers.iter_mut().find(|(p, _)| *p == result.provider) {
+            Some((_, rows)) => rows.push(result),
+            None => providers.push((&result.provider, vec![result])),
+        }
+    }
+    if providers.is_empty() {
+        return String::new();
+    }
+
+    let mut output = String::from("Throughput by max_tokens:\n");
+    for (provider, mut rows) in providers {
+        rows.sort_by_key(|r| r.requested_output_tokens);
+        let curve: Vec<f64> = rows
+            .iter()
+            .filter(|r| r.is_success())
+            .map(|r| r.metrics.avg_tokens_per_sec)
+            .collect();
+        output.push_str(&format!("  {} {}\n", provider, sparkline(&curve)));
+        for row in rows {
+            let max_tokens = row.requested_output_tokens.unwrap_or_default();
+            if row.is_success() {
+                output.push_str(&format!(
+                    "    {:>6} max_tokens: {:>6.0} output tokens, {:>8.1} tok/s\n",
+                    max_tokens,
+                    avg_output_tokens(row),
+                    row.metrics.avg_tokens_per_sec
+                ));
+            } else {
+                output.push_str(&format!("    {:>6} max_tokens: failed\n", max_tokens));
+            }
+        }
+    }
+    output
+}
+
+/// Mean output tokens per run, to show where generation stopped short
+fn avg_output_tokens(result: &BenchmarkResult) -> f64 {
+    let runs = &result.raw_results;
+    if runs.is_empty() {
+        return 0.0;
+    }
+    runs.iter().map(|r| r.output_tokens as f64).sum::<f64>() / runs.len() as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::benchmark::{AggregatedMetrics, SingleRunResult};
+
+    fn result(max_tokens: u32, latency_ms: u64) -> BenchmarkResult {
+        let run = SingleRunResult {
+            output_tokens: max_tokens,
+            hit_max_tokens: true,
+            ..SingleRunResult::test_run(50, latency_ms)
+        };
+        BenchmarkResult {
+            display_name: format!("Groq (max_tokens {})", max_tokens),
+            requested_output_tokens: Some(max_tokens),
+            ..BenchmarkResult::test_result("groq", vec![run])
+        }
+    }
+
+    #[test]
+    fn test_sweep_lists_throughput_per_max_tokens() {
+        let mut failed = result(1000, 1000);
+        failed.raw_results.clear();
+        failed.metrics = AggregatedMetrics::from_raw(&[]);
+        // 50 tokens in 500ms, 200 in 1s, 500 in 2s
+        let results = [
+            result(200, 1000),
+            result(50, 500),
+            result(500, 2000),
+            failed,
+        ];
+
+        let output = format_max_tokens_sweep(&results);
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines[0], "Throughput by max_tokens:");
+        assert_eq!(lines[1], "  groq ▁▆█");
+        assert_eq!(
+            lines[2],
+            "        50 max_tokens:     50 output tokens,    100.0 tok/s"
+        );
+        assert!(lines[3].starts_with("       200 max_tokens"));
+        assert_eq!(lines[5], "      1000 max_tokens: failed");
+
+        let mut unswept = result(50, 500);
+        unswept.requested_output_tokens = None;
+        assert!(format_max_tokens_sweep(&[unswept]).is_empty());
+    }
+}
@@ -1,51 +1,80 @@
 //! Terminal table formatting for benchmark results.
 
+use super::sparkline::sparkline;
+use super::summary::format_summary;
+use super::{
+    OutputOptions, baseline_failed_note, baseline_result, cold_warm_rows, load_time_ms,
+    percent_of_fastest, run_history, score_cell, select_columns, versus_baseline,
+};
 use crate::benchmark::BenchmarkResult;
+use crate::cli::{BenchmarkMode, Column};
 use comfy_table::{Attribute, Cell, Color, ContentArrangement, Table, presets::UTF8_FULL};
 
 /// Format benchmark results as a terminal table
-pub fn format_table(results: &[BenchmarkResult]) -> String {
+pub fn format_table(combined: &[BenchmarkResult], options: &OutputOptions) -> String {
+    let results = &*cold_warm_rows(combined);
     let mut table = Table::new();
 
+    let baseline = baseline_result(results, options);
+    let columns = select_columns(results, options, TABLE_COLUMNS);
+
+    let header: Vec<Cell> = columns
+        .iter()
+        .map(|column| {
+            let title = match column {
+                Column::Provider => "Provider".to_string(),
+                Column::Model => "Model".to_string(),
+                Column::Ttft => "TTFT".to_string(),
+                Column::TtftP95 => "TTFT p95".to_string(),
+                Column::TokPerSec => "Tok/sec".to_string(),
+                Column::EffectiveTokPerSec => "Eff. tok/s".to_string(),
+                Column::Relative => "% Fastest".to_string(),
+                Column::Baseline => {
+                    baseline.map_or(String::new(), |b| format!("vs {}", b.display_name))
+                }
+                Column::Latency => "Latency".to_string(),
+                Column::Load => "Load".to_string(),
+                Column::Cost => "Cost".to_string(),
+                Column::Success => "Success".to_string(),
+                Column::Trend => "Trend".to_string(),
+                Column::Score => "Score".to_string(),
+            };
+            Cell::new(title).add_attribute(Attribute::Bold)
+        })
+        .collect();
+
     table
         .load_preset(UTF8_FULL)
         .set_content_arrangement(ContentArrangement::Dynamic)
-        .set_header(vec![
-            Cell::new("Provider").add_attribute(Attribute::Bold),
-            Cell::new("Model").add_attribute(Attribute::Bold),
-            Cell::new("TTFT").add_attribute(Attribute::Bold),
-            Cell::new("Tok/sec").add_attribute(Attribute::Bold),
-            Cell::new("Latency").add_attribute(Attribute::Bold),
-            Cell::new("Cost").add_attribute(Attribute::Bold),
-        ]);
+        .set_header(header);
+    if options.no_color {
+        table.force_no_tty();
+    }
 
     for result in results {
-        if result.is_success() {
-            table.add_row(vec![
-                Cell::new(&result.display_name),
-                Cell::new(&result.model),
-                Cell::new(format!("{}ms", result.metrics.avg_ttft_ms as u64)),
-                Cell::new(format!("{:.0}", result.metrics.avg_tokens_per_sec)),
-                Cell::new(format!("{}ms", result.metrics.avg_latency_ms as u64)),
-                Cell::new(format!("${:.4}", result.metrics.total_cost_usd)),
-            ]);
-        } else {
-            // Show failed providers with error indication
-            table.add_row(vec![
-                Cell::new(&result.display_name),
-                Cell::new(&result.model),
-                Cell::new("-").fg(Color::Red),
-                Cell::new("-").fg(Color::Red),
-                Cell::new("-").fg(Color::Red),
-                Cell::new("-").fg(Color::Red),
-            ]);
-        }
+        let row: Vec<Cell> = columns
+            .iter()
+            .map(|&column| {
+                if result.is_success() {
+                    success_cell(column, result, results, baseline, options)
+                } else {
+                    failure_cell(column, result)
+                }
+            })
+            .collect();
+        table.add_row(row);
     }
 
     let mut output = table.to_string();
 
+    let summary = format_summary(combined);
+    if !summary.is_empty() {
+        output.push('\n');
+        output.push_str(&summary);
+    }
+
     // Add notes for model load times and errors
-    let mut notes = Vec::new();
+    let mut notes: Vec<String> = baseline_failed_note(results, options).into_iter().collect();
 
     for result in results {
         if let Some(load_time) = result.metrics.model_load_time_ms {
@@ -55,6 +84,92 @@ pub fn format_table(results: &[BenchmarkResult]) -> String {
             ));
         }
 
+        if let Some(preload_time) = result.preload_time_ms {
+            notes.push(format!(
+                "{}: Model preloaded in {}ms before timing",
+                result.display_name, preload_time
+            ));
+        }
+
+        if result.metrics.trimmed_count > 0 {
+            notes.push(format!(
+                "{}: {} outlier run(s) excluded from averages",
+                result.display_name, result.metrics.trimmed_count
+            ));
+        }
+
+        if let Some(dimensions) = result.metrics.embedding_dimensions {
+            notes.push(format!(
+                "{}: {}-dimensional embeddings; Tok/sec counts input tokens",
+                result.display_name, dimensions
+            ));
+        }
+
+        if result.metrics.avg_reasoning_tokens > 0.0 {
+            notes.push(format!(
+                "{}: {:.0} reasoning tokens per run on average, included in Tok/sec and cost",
+                result.display_name, result.metrics.avg_reasoning_tokens
+            ));
+        }
+
+        if result.metrics.server_timed_count > 0 {
+            notes.push(format!(
+                "{}: TTFT and Tok/sec from server-reported timings in {}/{} runs",
+                result.display_name, result.metrics.server_timed_count, result.metrics.run_count
+            ));
+        }
+
+        let capped = result.max_tokens_hits();
+        if capped > 0 {
+            notes.push(format!(
+                "{}: {}/{} runs truncated at max_tokens (see --max-tokens-buffer)",
+                result.display_name, capped, result.metrics.run_count
+            ));
+        }
+
+        if result.truncated {
+            notes.push(format!(
+                "{}: truncated by --max-duration; averages cover {} completed iteration(s)",
+                result.display_name,
+                result.raw_results.len()
+            ));
+        }
+
+        if result.cost_capped {
+            notes.push(format!(
+                "{}: stopped at --max-cost-per-provider; averages cover {} iteration(s)",
+                result.display_name,
+                result.raw_results.len()
+            ));
+        }
+
+        if let Some(corpus_size) = result.corpus_size {
+            notes.push(format!(
+                "{}: averaged over a corpus of {} prompts",
+                result.display_name, corpus_size
+            ));
+        }
+
+        if let Some(spread) = &result.run_spread {
+            notes.push(format!(
+                "{}: {:.0} ± {:.1} tok/s mean of {} run averages",
+                result.display_name,
+                spread.mean_tokens_per_sec,
+                spread.std_dev_tokens_per_sec,
+                spread.per_run_tokens_per_sec.len()
+            ));
+        }
+
+        if options.verbose
+            && let Some(sample) = &result.output_sample
+        {
+            notes.push(format!(
+                "{}: output \"{}\"",
+                result.display_name,
+                preview(sample)
+            ));
+        }
+
         if !result.errors.is_empty() {
             for error in &result.errors {
                 notes.push(format!("{}: {}", result.display_name, error));
@@ -71,3 +186,118 @@ pub fn format_table(results: &[BenchmarkResult]) -> String {
 
     output
 }
+
+/// Table columns when `--columns` is not given
+const TABLE_COLUMNS: &[Column] = &[
+    Column::Provider,
+    Column::Model,
+    Column::Score,
+    Column::Ttft,
+    Column::TtftP95,
+    Column::TokPerSec,
+    Column::EffectiveTokPerSec,
+    Column::Relative,
+    Column::Baseline,
+    Column::Latency,
+    Column::Load,
+    Column::Cost,
+    Column::Success,
+    Column::Trend,
+];
+
+/// Cell for a provider with at least one successful run
+fn success_cell(
+    column: Column,
+    result: &BenchmarkResult,
+    results: &[BenchmarkResult],
+    baseline: Option<&BenchmarkResult>,
+    options: &OutputOptions,
+) -> Cell {
+    // Embeddings have no first token
+    let ttft = |ms: f64| match result.mode {
+        BenchmarkMode::Chat => format!("{}ms", ms as u64),
+        BenchmarkMode::Embeddings => "n/a".to_string(),
+    };
+    match column {
+        Column::Provider => Cell::new(&result.display_name),
+        Column::Model => Cell::new(&result.model),
+        Column::Ttft => Cell::new(ttft(result.metrics.avg_ttft_ms)),
+        Column::TtftP95 => Cell::new(ttft(result.metrics.p95_ttft_ms)),
+        Column::TokPerSec => Cell::new(throughput_cell(result, options)),
+        Column::EffectiveTokPerSec => {
+            Cell::new(format!("{:.0}", result.metrics.effective_tokens_per_sec))
+        }
+        Column::Relative => Cell::new(format!("{:.0}%", percent_of_fastest(result, results))),
+        Column::Baseline => {
+            Cell::new(baseline.map_or(String::new(), |b| versus_baseline(result, b)))
+        }
+        Column::Latency => Cell::new(format!("{}ms", result.metrics.avg_latency_ms as u64)),
+        Column::Load => load_cell(result),
+        Column::Cost => Cell::new(format!("${:.4}", result.metrics.total_cost_usd)),
+        Column::Success => {
+            let rate = result.metrics.success_rate;
+            let cell = Cell::new(format!("{:.0}%", rate * 100.0));
+            if rate < 1.0 {
+                cell.fg(Color::Yellow)
+            } else {
+                cell
+            }
+        }
+        Column::Trend => Cell::new(sparkline(run_history(result))),
+        Column::Score => Cell::new(score_cell(result, results, options)),
+    }
+}
+
+/// Cell for a provider whose runs all failed, with error indication
+fn failure_cell(column: Column, result: &BenchmarkResult) -> Cell {
+    match column {
+        Column::Provider => Cell::new(&result.display_name),
+        Column::Model => Cell::new(&result.model),
+        Column::Load => load_cell(result),
+        // Earlier runs may have succeeded
+        Column::Trend => Cell::new(sparkline(run_history(result))),
+        Column::Success if result.metrics.attempted_count > 0 => Cell::new("0%").fg(Color::Red),
+        _ => Cell::new("-").fg(Color::Red),
+    }
+}
+
+/// Average tok/s, with the 95% confidence margin in verbose mode
+fn throughput_cell(result: &BenchmarkResult, options: &OutputOptions) -> String {
+    let avg = format!("{:.0}", result.metrics.avg_tokens_per_sec);
+    match result.metrics.tokens_per_sec_margin() {
+        Some(margin) if options.verbose => format!("{} (±{:.0})", avg, margin),
+        _ => avg,
+    }
+}
+
+/// Longest output preview shown in notes, in characters
+const PREVIEW_CHARS: usize = 80;
+
+/// Single-line preview of generated text, cut at `PREVIEW_CHARS`
+fn preview(text: &str) -> String {
+    let flat = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    match flat.char_indices().nth(PREVIEW_CHARS) {
+        Some((cut, _)) => format!("{}…", &flat[..cut]),
+        None => flat,
+    }
+}
+
+/// Load time cell; a load can be recorded even when every iteration failed
+fn load_cell(result: &BenchmarkResult) -> Cell {
+    match load_time_ms(result) {
+        Some(ms) => Cell::new(format!("{}ms", ms)),
+        None => Cell::new("-"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_preview_flattens_and_truncates() {
+        assert_eq!(preview("Hello,\n\n  world"), "Hello, world");
+        let long = "é".repeat(100);
+        assert_eq!(preview(&long), format!("{}…", "é".repeat(PREVIEW_CHARS)));
+    }
+}
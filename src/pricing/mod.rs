@@ -1,11 +1,58 @@
 //! Pricing data for inference providers.
+//!
+//! Built-in rates can be overridden per model with a TOML pricing file
+//! (`--pricing-file`) keyed by provider id:
+//!
+//! ```toml
+//! [groq.models."llama3-70b-8192"]
+//! input_per_million = 0.59
+//! output_per_million = 0.79
+//! ```
 
+use chrono::{NaiveDate, Utc};
+use clap::ValueEnum;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use thiserror::Error;
+
+/// Date the built-in rates were last checked against provider price lists
+pub const PRICING_UPDATED: &str = "2025-01-15";
+
+/// Age in days after which the built-in rates are flagged as stale
+const STALE_AFTER_DAYS: i64 = 90;
+
+/// Rates loaded from `--pricing-file`, layered over the built-in defaults
+static PRICING_OVERRIDES: OnceLock<HashMap<String, ProviderPricing>> = OnceLock::new();
+
+/// Built-in rates, built on first lookup
+static DEFAULT_PRICING: OnceLock<HashMap<String, ProviderPricing>> = OnceLock::new();
+
+/// Errors that can occur while loading a pricing file
+#[derive(Debug, Error)]
+pub enum PricingError {
+    #[error("Failed to read pricing file {path}: {source}")]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+
+    #[error("Invalid pricing file {path}: {source}")]
+    Parse {
+        path: PathBuf,
+        source: toml::de::Error,
+    },
+
+    #[error("A pricing file has already been loaded")]
+    AlreadyLoaded,
+}
 
 /// Pricing information for a provider
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProviderPricing {
+    /// Display name (may be omitted in a pricing file)
+    #[serde(default)]
     pub name: String,
     pub models: HashMap<String, ModelPricing>,
 }
@@ -17,9 +64,50 @@ pub struct ModelPricing {
     pub input_per_million: f64,
     /// USD per 1M output tokens
     pub output_per_million: f64,
+    /// USD per 1M input tokens served from the provider's prompt cache
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cached_input_per_million: Option<f64>,
+    /// Multiplier applied to both rates for batch API requests (e.g. 0.5)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub batch_multiplier: Option<f64>,
+}
+
+/// Which rate card to apply when computing costs
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[derive(ValueEnum, Serialize, Deserialize, Clone, Debug, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum PricingMode {
+    /// Regular on-demand rates
+    #[default]
+    Standard,
+    /// Input tokens billed at the prompt-cache rate
+    Cached,
+    /// Batch API rates
+    Batch,
 }
 
-/// Get default pricing data (January 2025)
+impl PricingMode {
+    /// Adjust a provider's standard (input, output) rates for this mode
+    ///
+    /// Models without a known discount for the mode keep their standard rates.
+    pub fn apply(self, provider: &str, model: &str, standard: (f64, f64)) -> (f64, f64) {
+        let (input, output) = standard;
+        let Some(rates) = model_rates(provider, model) else {
+            return standard;
+        };
+
+        match self {
+            PricingMode::Standard => standard,
+            PricingMode::Cached => (rates.cached_input_per_million.unwrap_or(input), output),
+            PricingMode::Batch => {
+                let multiplier = rates.batch_multiplier.unwrap_or(1.0);
+                (input * multiplier, output * multiplier)
+            }
+        }
+    }
+}
+
+/// Get default pricing data (see [`PRICING_UPDATED`])
 pub fn default_pricing() -> HashMap<String, ProviderPricing> {
     let mut pricing = HashMap::new();
 
@@ -34,6 +122,8 @@ pub fn default_pricing() -> HashMap<String, ProviderPricing> {
                     ModelPricing {
                         input_per_million: 0.10,
                         output_per_million: 0.10,
+                        cached_input_per_million: None,
+                        batch_multiplier: None,
                     },
                 ),
                 (
@@ -41,6 +131,8 @@ pub fn default_pricing() -> HashMap<String, ProviderPricing> {
                     ModelPricing {
                         input_per_million: 0.02,
                         output_per_million: 0.02,
+                        cached_input_per_million: None,
+                        batch_multiplier: None,
                     },
                 ),
             ]
@@ -60,6 +152,8 @@ pub fn default_pricing() -> HashMap<String, ProviderPricing> {
                     ModelPricing {
                         input_per_million: 0.05,
                         output_per_million: 0.08,
+                        cached_input_per_million: None,
+                        batch_multiplier: Some(0.5),
                     },
                 ),
                 (
@@ -67,6 +161,8 @@ pub fn default_pricing() -> HashMap<String, ProviderPricing> {
                     ModelPricing {
                         input_per_million: 0.02,
                         output_per_million: 0.02,
+                        cached_input_per_million: None,
+                        batch_multiplier: Some(0.5),
                     },
                 ),
             ]
@@ -75,16 +171,130 @@ pub fn default_pricing() -> HashMap<String, ProviderPricing> {
         },
     );
 
-    // Fireworks pricing
+    // Fireworks serverless pricing, tiered by model size (see
+    // `FireworksProvider::pricing_for_model` for unlisted models)
     pricing.insert(
         "fireworks".to_string(),
         ProviderPricing {
             name: "Fireworks".to_string(),
+            models: [
+                (
+                    "llama-v3p2-3b-instruct".to_string(),
+                    ModelPricing {
+                        input_per_million: 0.10,
+                        output_per_million: 0.10,
+                        cached_input_per_million: None,
+                        batch_multiplier: None,
+                    },
+                ),
+                (
+                    "llama-v3p1-8b-instruct".to_string(),
+                    ModelPricing {
+                        input_per_million: 0.20,
+                        output_per_million: 0.20,
+                        cached_input_per_million: None,
+                        batch_multiplier: None,
+                    },
+                ),
+                (
+                    "llama-v3p1-70b-instruct".to_string(),
+                    ModelPricing {
+                        input_per_million: 0.90,
+                        output_per_million: 0.90,
+                        cached_input_per_million: None,
+                        batch_multiplier: None,
+                    },
+                ),
+                (
+                    "llama-v3p3-70b-instruct".to_string(),
+                    ModelPricing {
+                        input_per_million: 0.90,
+                        output_per_million: 0.90,
+                        cached_input_per_million: None,
+                        batch_multiplier: None,
+                    },
+                ),
+                (
+                    "llama-v3p1-405b-instruct".to_string(),
+                    ModelPricing {
+                        input_per_million: 3.00,
+                        output_per_million: 3.00,
+                        cached_input_per_million: None,
+                        batch_multiplier: None,
+                    },
+                ),
+                (
+                    "qwen2p5-72b-instruct".to_string(),
+                    ModelPricing {
+                        input_per_million: 0.90,
+                        output_per_million: 0.90,
+                        cached_input_per_million: None,
+                        batch_multiplier: None,
+                    },
+                ),
+                (
+                    "mixtral-8x7b-instruct".to_string(),
+                    ModelPricing {
+                        input_per_million: 0.50,
+                        output_per_million: 0.50,
+                        cached_input_per_million: None,
+                        batch_multiplier: None,
+                    },
+                ),
+                (
+                    "mixtral-8x22b-instruct".to_string(),
+                    ModelPricing {
+                        input_per_million: 1.20,
+                        output_per_million: 1.20,
+                        cached_input_per_million: None,
+                        batch_multiplier: None,
+                    },
+                ),
+                (
+                    "deepseek-v3".to_string(),
+                    ModelPricing {
+                        input_per_million: 0.90,
+                        output_per_million: 0.90,
+                        cached_input_per_million: None,
+                        batch_multiplier: None,
+                    },
+                ),
+                (
+                    "deepseek-r1".to_string(),
+                    ModelPricing {
+                        input_per_million: 3.00,
+                        output_per_million: 8.00,
+                        cached_input_per_million: None,
+                        batch_multiplier: None,
+                    },
+                ),
+                (
+                    "nomic-embed-text-v1.5".to_string(),
+                    ModelPricing {
+                        input_per_million: 0.008,
+                        output_per_million: 0.0,
+                        cached_input_per_million: None,
+                        batch_multiplier: None,
+                    },
+                ),
+            ]
+            .into_iter()
+            .collect(),
+        },
+    );
+
+    // Together AI embeddings pricing (chat models use the provider's flat rate)
+    pricing.insert(
+        "together".to_string(),
+        ProviderPricing {
+            name: "Together AI".to_string(),
             models: [(
-                "llama-v3p1-70b-instruct".to_string(),
+                "BAAI/bge-large-en-v1.5".to_string(),
                 ModelPricing {
-                    input_per_million: 0.20,
-                    output_per_million: 0.20,
+                    input_per_million: 0.02,
+                    output_per_million: 0.0,
+                    cached_input_per_million: None,
+                    batch_multiplier: None,
                 },
             )]
             .into_iter()
@@ -97,13 +307,200 @@ pub fn default_pricing() -> HashMap<String, ProviderPricing> {
         "deepseek".to_string(),
         ProviderPricing {
             name: "DeepSeek".to_string(),
-            models: [(
-                "deepseek-chat".to_string(),
-                ModelPricing {
-                    input_per_million: 0.014,
-                    output_per_million: 0.028,
-                },
-            )]
+            models: [
+                (
+                    "deepseek-chat".to_string(),
+                    ModelPricing {
+                        input_per_million: 0.27,
+                        output_per_million: 1.10,
+                        cached_input_per_million: Some(0.07),
+                        batch_multiplier: None,
+                    },
+                ),
+                (
+                    "deepseek-reasoner".to_string(),
+                    ModelPricing {
+                        input_per_million: 0.55,
+                        output_per_million: 2.19,
+                        cached_input_per_million: Some(0.14),
+                        batch_multiplier: None,
+                    },
+                ),
+            ]
+            .into_iter()
+            .collect(),
+        },
+    );
+
+    // Z.ai (Zhipu) GLM pricing
+    pricing.insert(
+        "zai".to_string(),
+        ProviderPricing {
+            name: "Z.ai (Zhipu)".to_string(),
+            models: [
+                (
+                    "glm-4.7".to_string(),
+                    ModelPricing {
+                        input_per_million: 0.60,
+                        output_per_million: 2.20,
+                        cached_input_per_million: Some(0.11),
+                        batch_multiplier: None,
+                    },
+                ),
+                (
+                    "glm-4.6".to_string(),
+                    ModelPricing {
+                        input_per_million: 0.60,
+                        output_per_million: 2.20,
+                        cached_input_per_million: Some(0.11),
+                        batch_multiplier: None,
+                    },
+                ),
+                (
+                    "glm-4.5".to_string(),
+                    ModelPricing {
+                        input_per_million: 0.60,
+                        output_per_million: 2.20,
+                        cached_input_per_million: Some(0.11),
+                        batch_multiplier: None,
+                    },
+                ),
+                (
+                    "glm-4.5-air".to_string(),
+                    ModelPricing {
+                        input_per_million: 0.20,
+                        output_per_million: 1.10,
+                        cached_input_per_million: Some(0.03),
+                        batch_multiplier: None,
+                    },
+                ),
+                (
+                    "glm-4.5-flash".to_string(),
+                    ModelPricing {
+                        input_per_million: 0.0,
+                        output_per_million: 0.0,
+                        cached_input_per_million: None,
+                        batch_multiplier: None,
+                    },
+                ),
+            ]
+            .into_iter()
+            .collect(),
+        },
+    );
+
+    // Moonshot Kimi pricing
+    pricing.insert(
+        "moonshot".to_string(),
+        ProviderPricing {
+            name: "Moonshot".to_string(),
+            models: [
+                (
+                    "kimi-k2.5".to_string(),
+                    ModelPricing {
+                        input_per_million: 0.60,
+                        output_per_million: 3.00,
+                        cached_input_per_million: Some(0.10),
+                        batch_multiplier: None,
+                    },
+                ),
+                (
+                    "kimi-k2-0905-preview".to_string(),
+                    ModelPricing {
+                        input_per_million: 0.60,
+                        output_per_million: 2.50,
+                        cached_input_per_million: Some(0.15),
+                        batch_multiplier: None,
+                    },
+                ),
+                (
+                    "kimi-k2-turbo-preview".to_string(),
+                    ModelPricing {
+                        input_per_million: 1.15,
+                        output_per_million: 8.00,
+                        cached_input_per_million: Some(0.15),
+                        batch_multiplier: None,
+                    },
+                ),
+                (
+                    "kimi-k2-thinking".to_string(),
+                    ModelPricing {
+                        input_per_million: 0.60,
+                        output_per_million: 2.50,
+                        cached_input_per_million: Some(0.15),
+                        batch_multiplier: None,
+                    },
+                ),
+            ]
+            .into_iter()
+            .collect(),
+        },
+    );
+
+    // Azure OpenAI pricing (applies when the deployment is named after the model)
+    pricing.insert(
+        "azure".to_string(),
+        ProviderPricing {
+            name: "Azure OpenAI".to_string(),
+            models: [
+                (
+                    "gpt-4o".to_string(),
+                    ModelPricing {
+                        input_per_million: 2.50,
+                        output_per_million: 10.00,
+                        cached_input_per_million: Some(1.25),
+                        batch_multiplier: Some(0.5),
+                    },
+                ),
+                (
+                    "gpt-4o-mini".to_string(),
+                    ModelPricing {
+                        input_per_million: 0.15,
+                        output_per_million: 0.60,
+                        cached_input_per_million: Some(0.075),
+                        batch_multiplier: Some(0.5),
+                    },
+                ),
+            ]
+            .into_iter()
+            .collect(),
+        },
+    );
+
+    // Vertex AI pricing (Gemini)
+    pricing.insert(
+        "vertex".to_string(),
+        ProviderPricing {
+            name: "Vertex AI".to_string(),
+            models: [
+                (
+                    "gemini-2.0-flash-001".to_string(),
+                    ModelPricing {
+                        input_per_million: 0.15,
+                        output_per_million: 0.60,
+                        cached_input_per_million: Some(0.0375),
+                        batch_multiplier: Some(0.5),
+                    },
+                ),
+                (
+                    "gemini-2.0-flash-lite-001".to_string(),
+                    ModelPricing {
+                        input_per_million: 0.075,
+                        output_per_million: 0.30,
+                        cached_input_per_million: None,
+                        batch_multiplier: Some(0.5),
+                    },
+                ),
+                (
+                    "gemini-1.5-pro-002".to_string(),
+                    ModelPricing {
+                        input_per_million: 1.25,
+                        output_per_million: 5.00,
+                        cached_input_per_million: Some(0.3125),
+                        batch_multiplier: Some(0.5),
+                    },
+                ),
+            ]
             .into_iter()
             .collect(),
         },
@@ -119,6 +516,8 @@ pub fn default_pricing() -> HashMap<String, ProviderPricing> {
                 ModelPricing {
                     input_per_million: 0.0,
                     output_per_million: 0.0,
+                    cached_input_per_million: None,
+                    batch_multiplier: None,
                 },
             )]
             .into_iter()
@@ -129,9 +528,91 @@ pub fn default_pricing() -> HashMap<String, ProviderPricing> {
     pricing
 }
 
+/// Load per-model rate overrides from a TOML pricing file
+///
+/// Overrides apply process-wide to every later price lookup, so this should be
+/// called once at startup.
+pub fn load_pricing_file(path: &Path) -> Result<(), PricingError> {
+    let contents = std::fs::read_to_string(path).map_err(|source| PricingError::Io {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    let overrides = toml::from_str(&contents).map_err(|source| PricingError::Parse {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    PRICING_OVERRIDES
+        .set(overrides)
+        .map_err(|_| PricingError::AlreadyLoaded)
+}
+
+/// Built-in pricing with any `--pricing-file` overrides applied
+pub fn pricing() -> HashMap<String, ProviderPricing> {
+    let mut pricing = DEFAULT_PRICING.get_or_init(default_pricing).clone();
+    for (id, overrides) in PRICING_OVERRIDES.get().into_iter().flatten() {
+        let provider = pricing
+            .entry(id.clone())
+            .or_insert_with(|| ProviderPricing {
+                name: id.clone(),
+                models: HashMap::new(),
+            });
+        if !overrides.name.is_empty() {
+            provider.name = overrides.name.clone();
+        }
+        provider.models.extend(overrides.models.clone());
+    }
+    pricing
+}
+
+/// Warning to show when the built-in rates are stale and no pricing file
+/// was supplied
+pub fn staleness_warning() -> Option<String> {
+    if PRICING_OVERRIDES.get().is_some() {
+        return None;
+    }
+    let age = age_in_days(PRICING_UPDATED, Utc::now().date_naive())?;
+    (age > STALE_AFTER_DAYS).then(|| {
+        format!(
+            "Built-in prices were last updated {} ({} days ago) and may be out of date; \
+             supply current rates with --pricing-file",
+            PRICING_UPDATED, age
+        )
+    })
+}
+
+/// Days between an ISO date and `today`
+fn age_in_days(date: &str, today: NaiveDate) -> Option<i64> {
+    let date = NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()?;
+    Some((today - date).num_days())
+}
+
+/// Look up (input, output) pricing per 1M tokens for a provider's model
+pub fn model_pricing(provider: &str, model: &str) -> Option<(f64, f64)> {
+    model_rates(provider, model).map(|m| (m.input_per_million, m.output_per_million))
+}
+
+/// Look up the full rate card for a provider's model
+///
+/// Path-style model IDs (e.g. `accounts/fireworks/models/x`) also match on
+/// their final segment.
+pub fn model_rates(provider: &str, model: &str) -> Option<ModelPricing> {
+    let tables = [
+        PRICING_OVERRIDES.get(),
+        Some(DEFAULT_PRICING.get_or_init(default_pricing)),
+    ];
+    let last_segment = model.rsplit('/').next().unwrap_or(model);
+    [model, last_segment].into_iter().find_map(|key| {
+        tables
+            .iter()
+            .flatten()
+            .find_map(|pricing| pricing.get(provider)?.models.get(key))
+            .cloned()
+    })
+}
+
 /// Format pricing information as a displayable string
 pub fn format_pricing_table() -> String {
-    let pricing = default_pricing();
+    let pricing = pricing();
     let mut output = String::new();
 
     output.push_str("Provider Pricing (per 1M tokens)\n");
@@ -141,15 +622,140 @@ pub fn format_pricing_table() -> String {
         output.push_str(&format!("{}:\n", provider.name));
         for (model, prices) in &provider.models {
             output.push_str(&format!(
-                "  {}: ${:.3} input / ${:.3} output\n",
+                "  {}: ${:.3} input / ${:.3} output",
                 model, prices.input_per_million, prices.output_per_million
             ));
+            if let Some(cached) = prices.cached_input_per_million {
+                output.push_str(&format!(" (cached input ${:.4})", cached));
+            }
+            if let Some(multiplier) = prices.batch_multiplier {
+                output.push_str(&format!(" (batch x{})", multiplier));
+            }
+            output.push('\n');
         }
         output.push('\n');
     }
 
-    output
-        .push_str("Note: Prices as of January 2025. Check provider websites for current rates.\n");
+    output.push_str(&format!(
+        "Note: Built-in prices as of {}. Check provider websites for current rates.\n",
+        PRICING_UPDATED
+    ));
+    if PRICING_OVERRIDES.get().is_some() {
+        output.push_str("Rates from --pricing-file override the built-in prices.\n");
+    }
+    if let Some(warning) = staleness_warning() {
+        output.push_str(&format!("Warning: {}\n", warning));
+    }
 
     output
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_age_in_days() {
+        let today = NaiveDate::from_ymd_opt(2025, 4, 16).unwrap();
+        assert_eq!(age_in_days("2025-01-15", today), Some(91));
+        assert_eq!(age_in_days("2025-04-16", today), Some(0));
+        assert_eq!(age_in_days("January 2025", today), None);
+    }
+
+    #[test]
+    fn test_default_models_are_priced() {
+        // Registered providers whose default model must be in the table
+        for (provider, model) in [
+            ("zai", "glm-4.7"),
+            ("moonshot", "kimi-k2.5"),
+            ("deepseek", "deepseek-chat"),
+        ] {
+            let rates = &default_pricing()[provider].models[model];
+            assert!(
+                rates.input_per_million > 0.0 && rates.output_per_million > 0.0,
+                "{}/{}",
+                provider,
+                model
+            );
+        }
+    }
+
+    #[test]
+    fn test_cached_rates_are_discounts() {
+        for (provider, pricing) in default_pricing() {
+            for (model, rates) in pricing.models {
+                if let Some(cached) = rates.cached_input_per_million {
+                    assert!(
+                        cached <= rates.input_per_million,
+                        "{}/{}: cached input ${} above standard ${}",
+                        provider,
+                        model,
+                        cached,
+                        rates.input_per_million
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_pricing_mode_apply() {
+        let standard = (1.0, 2.0);
+        // Groq's batch API halves both rates
+        assert_eq!(
+            PricingMode::Standard.apply("groq", "llama3-70b-8192", standard),
+            standard
+        );
+        assert_eq!(
+            PricingMode::Batch.apply("groq", "llama3-70b-8192", standard),
+            (0.5, 1.0)
+        );
+        // No prompt-cache rate: standard input
+        assert_eq!(
+            PricingMode::Cached.apply("groq", "llama3-70b-8192", standard),
+            standard
+        );
+
+        // DeepSeek bills cached input at its own rate and has no batch API
+        assert_eq!(
+            PricingMode::Cached.apply("deepseek", "deepseek-chat", standard),
+            (0.07, 2.0)
+        );
+        assert_eq!(
+            PricingMode::Batch.apply("deepseek", "deepseek-chat", standard),
+            standard
+        );
+
+        // Unknown models keep their standard rates in every mode
+        assert_eq!(
+            PricingMode::Batch.apply("groq", "no-such-model", standard),
+            standard
+        );
+    }
+
+    #[test]
+    fn test_model_rates_match_final_path_segment() {
+        let rates = model_rates("groq", "llama3-70b-8192").unwrap();
+        let prefixed = model_rates("groq", "meta-llama/llama3-70b-8192").unwrap();
+        assert_eq!(prefixed.input_per_million, rates.input_per_million);
+        assert_eq!(prefixed.output_per_million, rates.output_per_million);
+
+        assert!(model_rates("groq", "meta-llama/no-such-model").is_none());
+        assert!(model_rates("no-such-provider", "llama3-70b-8192").is_none());
+    }
+
+    #[test]
+    fn test_pricing_file_shape_parses() {
+        let overrides: HashMap<String, ProviderPricing> = toml::from_str(
+            r#"
+            [groq.models."llama3-70b-8192"]
+            input_per_million = 0.59
+            output_per_million = 0.79
+            "#,
+        )
+        .unwrap();
+        let model = &overrides["groq"].models["llama3-70b-8192"];
+        assert_eq!(model.input_per_million, 0.59);
+        assert_eq!(model.batch_multiplier, None);
+    }
+}
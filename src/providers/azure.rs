@@ -0,0 +1,150 @@
+//! Azure OpenAI deployment provider implementation.
+//!
+//! Azure serves OpenAI models through per-resource deployments, with the
+//! deployment name in the URL path and an `api-key` header instead of a
+//! bearer token.
+
+use super::openai_stream::{self, AuthHint, ChatRequest, StreamOptions};
+use super::{InferenceProvider, InferenceRequest, InferenceResponse, ProviderError, http};
+use crate::pricing::model_pricing;
+use async_trait::async_trait;
+use reqwest::{Client, StatusCode};
+use std::time::Instant;
+
+const DEFAULT_API_VERSION: &str = "2024-10-21";
+const TIMEOUT_SECS: u64 = 120;
+const AUTH: AuthHint = AuthHint {
+    provider: "azure",
+    env_var: "AZURE_OPENAI_KEY",
+};
+
+/// Azure OpenAI inference provider - enterprise OpenAI deployments
+pub struct AzureOpenAIProvider {
+    client: Client,
+    endpoint: String,
+    api_key: String,
+    deployment: String,
+    api_version: String,
+}
+
+impl AzureOpenAIProvider {
+    /// Create a new Azure OpenAI provider from environment variables
+    ///
+    /// Environment variables:
+    /// - AZURE_OPENAI_ENDPOINT: Resource endpoint (e.g., https://my-resource.openai.azure.com)
+    /// - AZURE_OPENAI_KEY: API key
+    /// - AZURE_OPENAI_DEPLOYMENT: Deployment name
+    /// - AZURE_OPENAI_API_VERSION: API version (default: 2024-10-21)
+    pub fn from_env() -> Result<Self, ProviderError> {
+        let required = |name: &str| {
+            std::env::var(name).map_err(|_| {
+                ProviderError::NotConfigured(format!("{} environment variable not set", name))
+            })
+        };
+
+        let endpoint = required("AZURE_OPENAI_ENDPOINT")?;
+        let api_key = required("AZURE_OPENAI_KEY")?;
+        let deployment = required("AZURE_OPENAI_DEPLOYMENT")?;
+        let api_version = std::env::var("AZURE_OPENAI_API_VERSION")
+            .unwrap_or_else(|_| DEFAULT_API_VERSION.to_string());
+
+        let client = http::build_client(TIMEOUT_SECS)?;
+
+        Ok(Self {
+            client,
+            endpoint: endpoint.trim_end_matches('/').to_string(),
+            api_key,
+            deployment,
+            api_version,
+        })
+    }
+
+    /// Chat completions URL for a deployment
+    fn chat_url(&self, deployment: &str) -> String {
+        format!(
+            "{}/openai/deployments/{}/chat/completions?api-version={}",
+            self.endpoint, deployment, self.api_version
+        )
+    }
+}
+
+#[async_trait]
+impl InferenceProvider for AzureOpenAIProvider {
+    fn name(&self) -> &str {
+        "azure"
+    }
+
+    fn display_name(&self) -> &str {
+        "Azure OpenAI"
+    }
+
+    async fn is_available(&self) -> bool {
+        // An empty chat request reaches the deployment without generating:
+        // a missing deployment or rejected key fails, a 400 for the missing
+        // messages means both are fine
+        let request = self
+            .client
+            .post(self.chat_url(&self.deployment))
+            .header("api-key", &self.api_key)
+            .json(&serde_json::json!({}));
+
+        openai_stream::probe_status(request)
+            .await
+            .is_some_and(|status| status.is_success() || status == StatusCode::BAD_REQUEST)
+    }
+
+    async fn infer(&self, request: &InferenceRequest) -> Result<InferenceResponse, ProviderError> {
+        let start = Instant::now();
+
+        // On Azure the model is selected by deployment name
+        let deployment = request
+            .model
+            .clone()
+            .unwrap_or_else(|| self.deployment.clone());
+        let url = self.chat_url(&deployment);
+        let mut chat_request = ChatRequest::new(deployment, request);
+        chat_request.stream_options = StreamOptions::usage(request.stream);
+
+        let http_request = self
+            .client
+            .post(&url)
+            .header("api-key", &self.api_key)
+            .header("Content-Type", "application/json")
+            .json(&chat_request);
+
+        openai_stream::stream_chat(
+            http_request,
+            start,
+            TIMEOUT_SECS,
+            AUTH,
+            request.first_token_timeout_ms,
+            request.stall_timeout_ms,
+        )
+        .await
+    }
+
+    fn default_model(&self) -> &str {
+        &self.deployment
+    }
+
+    fn endpoint(&self) -> Option<&str> {
+        Some(&self.endpoint)
+    }
+
+    fn supports_non_streaming(&self) -> bool {
+        true
+    }
+
+    fn supports_tools(&self) -> bool {
+        true
+    }
+
+    fn supports_history(&self) -> bool {
+        true
+    }
+
+    fn pricing_for_model(&self, model: &str) -> (f64, f64) {
+        // Deployment names are user-chosen; price them only if they match a known model
+        model_pricing("azure", model).unwrap_or((0.0, 0.0))
+    }
+}
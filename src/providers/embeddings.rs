@@ -0,0 +1,107 @@
+//! Shared request and response handling for OpenAI-style embeddings APIs.
+//!
+//! Providers build the `/embeddings` HTTP request themselves, as with chat, and
+//! hand it to [`send_embeddings`] for timing and parsing.
+
+use super::openai_stream::{self, AuthHint};
+use super::{EmbeddingRequest, EmbeddingResponse, ProviderError, http};
+use reqwest::RequestBuilder;
+use serde::{Deserialize, Serialize};
+use std::time::Instant;
+
+/// OpenAI-style embeddings request body
+#[derive(Serialize)]
+pub(super) struct EmbeddingsBody {
+    pub model: String,
+    pub input: String,
+}
+
+impl EmbeddingsBody {
+    /// Build a single-input request for the given model
+    pub fn new(model: String, request: &EmbeddingRequest) -> Self {
+        Self {
+            model,
+            input: request.input.clone(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct EmbeddingsReply {
+    data: Vec<EmbeddingData>,
+    usage: Option<EmbeddingUsage>,
+}
+
+#[derive(Deserialize)]
+struct EmbeddingData {
+    embedding: Vec<f32>,
+}
+
+#[derive(Deserialize)]
+struct EmbeddingUsage {
+    prompt_tokens: u32,
+}
+
+/// Send an embeddings request and measure its latency
+///
+/// `start` should be taken before the request body is built so that
+/// `time_to_prompt_ms` covers serialization and upload.
+pub(super) async fn send_embeddings(
+    request: RequestBuilder,
+    start: Instant,
+    timeout_secs: u64,
+    auth: AuthHint,
+) -> Result<EmbeddingResponse, ProviderError> {
+    let response = http::send(request)
+        .await
+        .map_err(|e| openai_stream::map_send_error(e, timeout_secs))?;
+
+    let time_to_prompt_ms = start.elapsed().as_millis() as u64;
+    let response = openai_stream::check_status(response, auth).await?;
+
+    let body = response
+        .bytes()
+        .await
+        .map_err(|e| ProviderError::Network(e.to_string()))?;
+    let total_latency_ms = start.elapsed().as_millis() as u64;
+
+    let (input_tokens, dimensions) = parse_reply(&body)?;
+
+    Ok(EmbeddingResponse {
+        input_tokens,
+        dimensions,
+        time_to_prompt_ms,
+        total_latency_ms,
+        model_load_time_ms: None,
+    })
+}
+
+/// Extract (input tokens, vector length) from an embeddings response body
+fn parse_reply(body: &[u8]) -> Result<(u32, u32), ProviderError> {
+    let reply: EmbeddingsReply =
+        serde_json::from_slice(body).map_err(|e| ProviderError::ParseError(e.to_string()))?;
+
+    let dimensions = reply
+        .data
+        .first()
+        .map(|d| d.embedding.len() as u32)
+        .ok_or_else(|| ProviderError::ParseError("no embeddings returned".to_string()))?;
+
+    Ok((reply.usage.map_or(0, |u| u.prompt_tokens), dimensions))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_reply_reads_dimensions_and_usage() {
+        let body = br#"{"object":"list","data":[{"object":"embedding","index":0,"embedding":[0.1,-0.2,0.3,0.4]}],"model":"m","usage":{"prompt_tokens":7,"total_tokens":7}}"#;
+        assert_eq!(parse_reply(body).unwrap(), (7, 4));
+
+        assert!(matches!(
+            parse_reply(br#"{"data":[]}"#),
+            Err(ProviderError::ParseError(_))
+        ));
+    }
+}
@@ -1,63 +1,49 @@
 //! Fireworks AI inference provider implementation.
-
-use super::{InferenceProvider, InferenceRequest, InferenceResponse, ProviderError};
+//!
+//! Fireworks bills serverless models by size tier; models missing from the
+//! pricing table are priced from the parameter count in their name.
+
+use super::embeddings::{self, EmbeddingsBody};
+use super::openai_stream::{self, AuthHint, ChatRequest};
+use super::{
+    EmbeddingRequest, EmbeddingResponse, InferenceProvider, InferenceRequest, InferenceResponse,
+    ProviderError, http,
+};
+use crate::pricing::model_pricing;
 use async_trait::async_trait;
-use futures::StreamExt;
 use reqwest::Client;
-use serde::{Deserialize, Serialize};
-use std::time::{Duration, Instant};
+use std::time::Instant;
 
-const FIREWORKS_API_URL: &str = "https://api.fireworks.ai/inference/v1/chat/completions";
+const DEFAULT_BASE_URL: &str = "https://api.fireworks.ai/inference/v1";
 const DEFAULT_MODEL: &str = "accounts/fireworks/models/llama-v3p1-70b-instruct";
+/// Account path prepended to bare model names from `FIREWORKS_MODEL`
+const MODEL_PREFIX: &str = "accounts/fireworks/models/";
+const DEFAULT_EMBEDDING_MODEL: &str = "nomic-ai/nomic-embed-text-v1.5";
 const TIMEOUT_SECS: u64 = 120; // Fireworks may have cold starts
+const AUTH: AuthHint = AuthHint {
+    provider: "fireworks",
+    env_var: "FIREWORKS_API_KEY",
+};
 
 /// Fireworks AI inference provider - serverless inference
 pub struct FireworksProvider {
     client: Client,
+    base_url: String,
     api_key: String,
     model: String,
-}
-
-#[derive(Serialize)]
-struct ChatRequest {
-    model: String,
-    messages: Vec<Message>,
-    max_tokens: u32,
-    stream: bool,
-}
-
-#[derive(Serialize)]
-struct Message {
-    role: String,
-    content: String,
-}
-
-#[derive(Deserialize)]
-struct StreamChunk {
-    choices: Vec<StreamChoice>,
-    usage: Option<Usage>,
-}
-
-#[derive(Deserialize)]
-struct StreamChoice {
-    delta: Delta,
-    #[serde(rename = "finish_reason")]
-    _finish_reason: Option<String>,
-}
-
-#[derive(Deserialize)]
-struct Delta {
-    content: Option<String>,
-}
-
-#[derive(Deserialize)]
-struct Usage {
-    prompt_tokens: u32,
-    completion_tokens: u32,
+    embedding_model: String,
 }
 
 impl FireworksProvider {
     /// Create a new Fireworks provider from environment variables
+    ///
+    /// Environment variables:
+    /// - FIREWORKS_API_KEY: API key (required)
+    /// - FIREWORKS_BASE_URL: API base URL, e.g. a regional endpoint (default:
+    ///   https://api.fireworks.ai/inference/v1)
+    /// - FIREWORKS_MODEL: Model to use, either a full `accounts/.../models/x`
+    ///   path or a bare Fireworks model name (default: llama-v3p1-70b-instruct)
+    /// - FIREWORKS_EMBEDDING_MODEL: Model for `--mode embeddings`
     pub fn from_env() -> Result<Self, ProviderError> {
         let api_key = std::env::var("FIREWORKS_API_KEY").map_err(|_| {
             ProviderError::NotConfigured(
@@ -65,15 +51,23 @@ impl FireworksProvider {
             )
         })?;
 
-        let client = Client::builder()
-            .timeout(Duration::from_secs(TIMEOUT_SECS))
-            .build()
-            .map_err(|e| ProviderError::Network(e.to_string()))?;
+        let model = match std::env::var("FIREWORKS_MODEL") {
+            Ok(model) if model.contains('/') => model,
+            Ok(model) => format!("{}{}", MODEL_PREFIX, model),
+            Err(_) => DEFAULT_MODEL.to_string(),
+        };
+        let embedding_model = std::env::var("FIREWORKS_EMBEDDING_MODEL")
+            .unwrap_or_else(|_| DEFAULT_EMBEDDING_MODEL.to_string());
+
+        let base_url = http::base_url("FIREWORKS_BASE_URL", DEFAULT_BASE_URL);
+        let client = http::build_client(TIMEOUT_SECS)?;
 
         Ok(Self {
             client,
+            base_url,
             api_key,
-            model: DEFAULT_MODEL.to_string(),
+            model,
+            embedding_model,
         })
     }
 }
@@ -97,114 +91,138 @@ impl InferenceProvider for FireworksProvider {
 
         let model = request.model.clone().unwrap_or_else(|| self.model.clone());
 
-        let chat_request = ChatRequest {
-            model,
-            messages: vec![Message {
-                role: "user".to_string(),
-                content: request.prompt.clone(),
-            }],
-            max_tokens: request.max_tokens,
-            stream: true,
-        };
+        let mut chat_request = ChatRequest::new(model, request);
+        chat_request.min_tokens = request.min_tokens;
 
-        let response = self
+        let http_request = self
             .client
-            .post(FIREWORKS_API_URL)
+            .post(format!("{}/chat/completions", self.base_url))
             .header("Authorization", format!("Bearer {}", self.api_key))
             .header("Content-Type", "application/json")
-            .json(&chat_request)
-            .send()
-            .await
-            .map_err(|e| {
-                if e.is_timeout() {
-                    ProviderError::Timeout(TIMEOUT_SECS * 1000)
-                } else if e.is_connect() {
-                    ProviderError::Network(e.to_string())
-                } else {
-                    ProviderError::ApiError(e.to_string())
-                }
-            })?;
-
-        let time_to_prompt_ms = start.elapsed().as_millis() as u64;
-
-        if response.status() == 429 {
-            return Err(ProviderError::RateLimited);
-        }
-
-        if !response.status().is_success() {
-            let status = response.status();
-            let body = response.text().await.unwrap_or_default();
-            return Err(ProviderError::ApiError(format!(
-                "HTTP {}: {}",
-                status, body
-            )));
-        }
-
-        let mut stream = response.bytes_stream();
-        let mut first_token_time: Option<Duration> = None;
-        let mut output_text = String::new();
-        let mut input_tokens = 0u32;
-        let mut output_tokens = 0u32;
-        let mut buffer = String::new();
-
-        while let Some(chunk_result) = stream.next().await {
-            let chunk = chunk_result.map_err(|e| ProviderError::Network(e.to_string()))?;
-
-            if first_token_time.is_none() && !chunk.is_empty() {
-                first_token_time = Some(start.elapsed());
-            }
-
-            let chunk_str = String::from_utf8_lossy(&chunk);
-            buffer.push_str(&chunk_str);
-
-            while let Some(line_end) = buffer.find('\n') {
-                let line = buffer[..line_end].trim().to_string();
-                buffer = buffer[line_end + 1..].to_string();
-
-                if let Some(data) = line.strip_prefix("data: ") {
-                    if data == "[DONE]" {
-                        continue;
-                    }
-
-                    if let Ok(chunk_data) = serde_json::from_str::<StreamChunk>(data) {
-                        for choice in chunk_data.choices {
-                            if let Some(content) = choice.delta.content {
-                                output_text.push_str(&content);
-                            }
-                        }
-                        if let Some(usage) = chunk_data.usage {
-                            input_tokens = usage.prompt_tokens;
-                            output_tokens = usage.completion_tokens;
-                        }
-                    }
-                }
-            }
-        }
-
-        let total_latency_ms = start.elapsed().as_millis() as u64;
-        let ttft_ms = first_token_time
-            .map(|t| t.as_millis() as u64)
-            .unwrap_or(total_latency_ms);
-
-        let time_to_first_token_ms = ttft_ms.saturating_sub(time_to_prompt_ms);
-
-        Ok(InferenceResponse {
-            text: output_text,
-            input_tokens,
-            output_tokens,
-            time_to_prompt_ms,
-            time_to_first_token_ms,
-            total_latency_ms,
-            model_load_time_ms: None,
-        })
+            .json(&chat_request);
+
+        openai_stream::stream_chat(
+            http_request,
+            start,
+            TIMEOUT_SECS,
+            AUTH,
+            request.first_token_timeout_ms,
+            request.stall_timeout_ms,
+        )
+        .await
     }
 
     fn default_model(&self) -> &str {
-        "llama-v3p1-70b-instruct"
+        &self.model
+    }
+
+    fn endpoint(&self) -> Option<&str> {
+        Some(&self.base_url)
+    }
+
+    fn supports_non_streaming(&self) -> bool {
+        true
     }
 
-    fn pricing_per_million(&self) -> (f64, f64) {
-        // Fireworks pricing as of Jan 2025
-        (0.20, 0.20)
+    fn supports_tools(&self) -> bool {
+        true
+    }
+
+    fn supports_history(&self) -> bool {
+        true
+    }
+
+    fn embedding_model(&self) -> Option<&str> {
+        Some(&self.embedding_model)
+    }
+
+    async fn embed(&self, request: &EmbeddingRequest) -> Result<EmbeddingResponse, ProviderError> {
+        let start = Instant::now();
+
+        let model = request
+            .model
+            .clone()
+            .unwrap_or_else(|| self.embedding_model.clone());
+
+        let http_request = self
+            .client
+            .post(format!("{}/embeddings", self.base_url))
+            .header("Authorization", format!("Bearer {}", self.api_key))
+            .header("Content-Type", "application/json")
+            .json(&EmbeddingsBody::new(model, request));
+
+        embeddings::send_embeddings(http_request, start, TIMEOUT_SECS, AUTH).await
+    }
+
+    fn pricing_for_model(&self, model: &str) -> (f64, f64) {
+        // Pricing table uses the short model name without the account path
+        let short_name = model.rsplit('/').next().unwrap_or(model);
+        model_pricing("fireworks", short_name).unwrap_or_else(|| size_tier_pricing(short_name))
+    }
+}
+
+/// Serverless rate for a model by size tier, from the parameter count in its
+/// name (e.g. `8b`, `70b`, `8x22b`)
+///
+/// Names without a recognizable size are priced at the >16B tier so cost
+/// estimates err high rather than low.
+fn size_tier_pricing(model: &str) -> (f64, f64) {
+    let rate = match parameter_count_b(model) {
+        Some(ParamCount::Dense(b)) if b <= 4.0 => 0.10,
+        Some(ParamCount::Dense(b)) if b <= 16.0 => 0.20,
+        Some(ParamCount::Moe(b)) if b <= 56.0 => 0.50,
+        Some(ParamCount::Moe(b)) if b <= 176.0 => 1.20,
+        _ => 0.90,
+    };
+    (rate, rate)
+}
+
+/// Total parameters in billions parsed from a model name
+#[derive(Debug, PartialEq)]
+enum ParamCount {
+    Dense(f64),
+    /// Mixture of experts, `experts x size`
+    Moe(f64),
+}
+
+fn parameter_count_b(model: &str) -> Option<ParamCount> {
+    model
+        .to_ascii_lowercase()
+        .split(['-', '_'])
+        .find_map(|part| {
+            let size = part.strip_suffix('b')?;
+            match size.split_once('x') {
+                Some((experts, each)) => {
+                    let experts: f64 = experts.parse().ok()?;
+                    let each: f64 = each.parse().ok()?;
+                    Some(ParamCount::Moe(experts * each))
+                }
+                // Fireworks writes decimal points as `p` (e.g. `1p5b`)
+                None => size.replace('p', ".").parse().ok().map(ParamCount::Dense),
+            }
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_size_tier_pricing_from_model_name() {
+        assert_eq!(
+            parameter_count_b("qwen2p5-1p5b-instruct"),
+            Some(ParamCount::Dense(1.5))
+        );
+        assert_eq!(
+            parameter_count_b("mixtral-8x22b-instruct"),
+            Some(ParamCount::Moe(176.0))
+        );
+        assert_eq!(parameter_count_b("firefunction-v2"), None);
+
+        assert_eq!(size_tier_pricing("llama-v3p2-1b-instruct"), (0.10, 0.10));
+        assert_eq!(size_tier_pricing("qwen2p5-coder-7b-instruct"), (0.20, 0.20));
+        assert_eq!(size_tier_pricing("mixtral-8x7b-instruct-hf"), (0.50, 0.50));
+        assert_eq!(size_tier_pricing("gemma2-27b-it"), (0.90, 0.90));
+        assert_eq!(size_tier_pricing("firefunction-v2"), (0.90, 0.90));
     }
 }
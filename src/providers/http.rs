@@ -0,0 +1,321 @@
+//! HTTP client construction and request tracing shared by all providers.
+//!
+//! With `RUST_LOG=speed_kings=trace`, every request's method, URL, headers,
+//! and JSON body are logged before sending, with credentials redacted.
+//! Error text bound for results and logs goes through [`redact`].
+
+use super::ProviderError;
+use reqwest::{Client, Request, RequestBuilder, Response};
+use serde_json::Value;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+/// Placeholder logged in place of secrets
+const REDACTED: &str = "[REDACTED]";
+
+/// Headers that carry credentials
+const SENSITIVE_HEADERS: &[&str] = &["authorization", "api-key", "x-api-key", "x-goog-api-key"];
+
+/// JSON body fields and URL query parameters that carry credentials
+const SENSITIVE_FIELDS: &[&str] = &["api_key", "apikey", "key", "access_token", "assertion"];
+
+/// Prefixes of common API key formats (OpenAI-style, Groq, Cerebras,
+/// Fireworks, xAI, Google)
+const KEY_PREFIXES: &[&str] = &["sk-", "sk_", "gsk_", "csk-", "fw_", "xai-", "AIza"];
+
+/// Shortest token with a known prefix that is masked
+const MIN_PREFIXED_KEY_LEN: usize = 20;
+
+/// Shortest letter-and-digit token masked for its length alone
+const MIN_OPAQUE_KEY_LEN: usize = 32;
+
+/// Shortest secret environment value or bearer token that is masked
+const MIN_SECRET_LEN: usize = 8;
+
+/// Request timeout set by `--timeout`, replacing every provider's default
+static TIMEOUT_OVERRIDE: OnceLock<Duration> = OnceLock::new();
+
+/// Connection reuse set by `--fresh-connection` or `--pool-idle-timeout`
+static POOL_OVERRIDE: OnceLock<ConnectionPool> = OnceLock::new();
+
+/// How long reqwest keeps an idle pooled connection by default
+pub const DEFAULT_POOL_IDLE_TIMEOUT_SECS: u64 = 90;
+
+/// How provider clients reuse connections between requests
+///
+/// By default each provider's client keeps connections alive and pools them
+/// for [`DEFAULT_POOL_IDLE_TIMEOUT_SECS`], so only the first request to a
+/// host pays DNS, TCP, and TLS setup.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ConnectionPool {
+    /// Keep idle connections for reuse this long
+    IdleTimeout(Duration),
+    /// Never reuse a connection; every request sets up its own
+    Fresh,
+}
+
+/// Override connection pooling for every provider client built afterwards
+///
+/// Must be called before providers are constructed; returns false if a
+/// policy was already set.
+pub fn set_connection_pool(pool: ConnectionPool) -> bool {
+    POOL_OVERRIDE.set(pool).is_ok()
+}
+
+/// Override the request timeout of every provider client built afterwards
+///
+/// Must be called before providers are constructed; returns false if a
+/// timeout was already set.
+pub fn set_request_timeout(timeout: Duration) -> bool {
+    TIMEOUT_OVERRIDE.set(timeout).is_ok()
+}
+
+/// Effective request timeout for a provider whose default is `default_secs`
+pub(super) fn request_timeout(default_secs: u64) -> Duration {
+    TIMEOUT_OVERRIDE
+        .get()
+        .copied()
+        .unwrap_or(Duration::from_secs(default_secs))
+}
+
+/// Build an HTTP client with the given request timeout, unless overridden
+/// by [`set_request_timeout`], pooling connections per [`set_connection_pool`]
+///
+/// Responses compressed with gzip, deflate, or brotli are decoded
+/// transparently (and advertised via `Accept-Encoding`), so gateways that
+/// compress SSE streams parse the same as plaintext ones.
+pub(super) fn build_client(timeout_secs: u64) -> Result<Client, ProviderError> {
+    let builder = Client::builder()
+        .timeout(request_timeout(timeout_secs))
+        .gzip(true)
+        .deflate(true)
+        .brotli(true);
+    let builder = match POOL_OVERRIDE.get() {
+        Some(ConnectionPool::IdleTimeout(timeout)) => builder.pool_idle_timeout(*timeout),
+        // Nothing is kept idle, so nothing can be reused
+        Some(ConnectionPool::Fresh) => builder.pool_max_idle_per_host(0),
+        None => builder,
+    };
+    builder
+        .build()
+        .map_err(|e| ProviderError::Network(e.to_string()))
+}
+
+/// Base URL from `env_var` if set, else `default`, without a trailing slash
+pub(super) fn base_url(env_var: &str, default: &str) -> String {
+    std::env::var(env_var)
+        .ok()
+        .filter(|url| !url.is_empty())
+        .map_or_else(
+            || default.to_string(),
+            |url| url.trim_end_matches('/').to_string(),
+        )
+}
+
+/// Size of a request's body in bytes (0 if it has none or it is streamed)
+pub(super) fn body_len(request: &RequestBuilder) -> u64 {
+    request
+        .try_clone()
+        .and_then(|r| r.build().ok())
+        .and_then(|r| r.body().and_then(|b| b.as_bytes()).map(|b| b.len() as u64))
+        .unwrap_or(0)
+}
+
+/// Send a request, logging it (redacted) and the response status at trace level
+pub(super) async fn send(request: RequestBuilder) -> Result<Response, reqwest::Error> {
+    let (client, request) = request.build_split();
+    let request = request?;
+
+    if tracing::enabled!(tracing::Level::TRACE) {
+        trace_request(&request);
+    }
+
+    let response = client.execute(request).await?;
+    tracing::trace!(status = %response.status(), url = %redact_url(response.url()), "HTTP response");
+    Ok(response)
+}
+
+fn trace_request(request: &Request) {
+    let headers: Vec<String> = request
+        .headers()
+        .iter()
+        .map(|(name, value)| {
+            let value = if SENSITIVE_HEADERS.contains(&name.as_str()) {
+                REDACTED
+            } else {
+                value.to_str().unwrap_or("<binary>")
+            };
+            format!("{}: {}", name, value)
+        })
+        .collect();
+
+    let body = request
+        .body()
+        .and_then(|b| b.as_bytes())
+        .map(redact_body)
+        .unwrap_or_default();
+
+    tracing::trace!(
+        method = %request.method(),
+        url = %redact_url(request.url()),
+        headers = ?headers,
+        body = %body,
+        "HTTP request"
+    );
+}
+
+/// Render a URL with credential query parameters masked
+fn redact_url(url: &reqwest::Url) -> String {
+    if !url
+        .query_pairs()
+        .any(|(k, _)| SENSITIVE_FIELDS.contains(&k.as_ref()))
+    {
+        return url.to_string();
+    }
+
+    let mut redacted = url.clone();
+    let pairs: Vec<(String, String)> = url
+        .query_pairs()
+        .map(|(k, v)| {
+            let v = if SENSITIVE_FIELDS.contains(&k.as_ref()) {
+                REDACTED.to_string()
+            } else {
+                v.into_owned()
+            };
+            (k.into_owned(), v)
+        })
+        .collect();
+    redacted.query_pairs_mut().clear().extend_pairs(pairs);
+    redacted.to_string()
+}
+
+/// Render a request body with credential fields masked
+///
+/// Non-JSON bodies (e.g. OAuth form posts) are summarized rather than logged.
+fn redact_body(bytes: &[u8]) -> String {
+    match serde_json::from_slice::<Value>(bytes) {
+        Ok(mut value) => {
+            redact_json(&mut value);
+            value.to_string()
+        }
+        Err(_) => format!("<{} bytes, not JSON>", bytes.len()),
+    }
+}
+
+fn redact_json(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (key, field) in map.iter_mut() {
+                if SENSITIVE_FIELDS.contains(&key.to_ascii_lowercase().as_str()) {
+                    *field = Value::String(REDACTED.to_string());
+                } else {
+                    redact_json(field);
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(redact_json),
+        _ => {}
+    }
+}
+
+/// Mask anything resembling a credential in text bound for output
+///
+/// Masks the values of `*_KEY` and `*_TOKEN` environment variables wherever
+/// they appear, bearer tokens, tokens with a known key prefix, and long
+/// tokens mixing letters and digits. UUIDs, such as request IDs, are kept.
+pub fn redact(text: &str) -> String {
+    let mut text = text.to_string();
+    for (name, value) in std::env::vars() {
+        if (name.ends_with("_KEY") || name.ends_with("_TOKEN")) && value.len() >= MIN_SECRET_LEN {
+            text = text.replace(&value, REDACTED);
+        }
+    }
+
+    let mut output = String::with_capacity(text.len());
+    let mut previous = "";
+    let mut start = None;
+    for (i, c) in text.char_indices() {
+        let in_token = c.is_ascii_alphanumeric() || c == '-' || c == '_';
+        match start {
+            None if in_token => start = Some(i),
+            Some(s) if !in_token => {
+                push_token(&mut output, &text[s..i], previous);
+                previous = &text[s..i];
+                start = None;
+            }
+            _ => {}
+        }
+        if !in_token {
+            output.push(c);
+        }
+    }
+    if let Some(s) = start {
+        push_token(&mut output, &text[s..], previous);
+    }
+    output
+}
+
+fn push_token(output: &mut String, token: &str, previous: &str) {
+    if looks_like_key(token, previous) {
+        output.push_str(REDACTED);
+    } else {
+        output.push_str(token);
+    }
+}
+
+/// Whether a token of letters, digits, `-`, and `_` looks like a credential
+fn looks_like_key(token: &str, previous: &str) -> bool {
+    if previous.eq_ignore_ascii_case("bearer") {
+        return token.len() >= MIN_SECRET_LEN;
+    }
+    if KEY_PREFIXES.iter().any(|p| token.starts_with(p)) {
+        return token.len() >= MIN_PREFIXED_KEY_LEN;
+    }
+    token.len() >= MIN_OPAQUE_KEY_LEN
+        && token.bytes().any(|b| b.is_ascii_digit())
+        && token.bytes().any(|b| b.is_ascii_alphabetic())
+        && !is_uuid(token)
+}
+
+fn is_uuid(token: &str) -> bool {
+    token.len() == 36
+        && token.char_indices().all(|(i, c)| match i {
+            8 | 13 | 18 | 23 => c == '-',
+            _ => c.is_ascii_hexdigit(),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redact_masks_key_like_tokens() {
+        let text = redact(
+            "HTTP 400: invalid key gsk_abcdefghij0123456789XYZ in \"Authorization: \
+             Bearer tok3n-abc\" (request 123e4567-e89b-12d3-a456-426614174000, \
+             hash a1b2c3d4e5f6a7b8c9d0e1f2a3b4c5d6e7f8, model llama-3.1-8b-instant)",
+        );
+        assert_eq!(
+            text,
+            "HTTP 400: invalid key [REDACTED] in \"Authorization: Bearer [REDACTED]\" \
+             (request 123e4567-e89b-12d3-a456-426614174000, hash [REDACTED], \
+             model llama-3.1-8b-instant)"
+        );
+        assert_eq!(redact("sk-short"), "sk-short");
+    }
+
+    #[test]
+    fn test_redacts_credentials_in_body_and_url() {
+        let body = redact_body(br#"{"model":"m","api_key":"sk-secret","nested":[{"key":"k2"}]}"#);
+        assert!(!body.contains("sk-secret"));
+        assert!(!body.contains("k2"));
+        assert!(body.contains("\"model\":\"m\""));
+
+        let url =
+            reqwest::Url::parse("https://example.com/v1/generate?key=abc123&alt=sse").unwrap();
+        let redacted = redact_url(&url);
+        assert!(!redacted.contains("abc123"));
+        assert!(redacted.contains("alt=sse"));
+    }
+}
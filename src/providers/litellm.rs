@@ -1,18 +1,26 @@
 //! LiteLLM proxy provider implementation.
 //!
 //! LiteLLM provides a unified OpenAI-compatible interface to 100+ LLMs.
-//! This provider routes requests through a LiteLLM proxy server.
-
-use super::{InferenceProvider, InferenceRequest, InferenceResponse, ProviderError};
+//! This provider routes requests through a LiteLLM proxy server. The models
+//! the proxy can route to, and their upstreams, come from its `/model/info`
+//! endpoint.
+
+use super::openai_stream::{self, AuthHint, ChatRequest, StreamOptions};
+use super::{
+    InferenceProvider, InferenceRequest, InferenceResponse, ModelInfo, ProviderError, http,
+};
 use async_trait::async_trait;
-use futures::StreamExt;
 use reqwest::Client;
-use serde::{Deserialize, Serialize};
-use std::time::{Duration, Instant};
+use serde::Deserialize;
+use std::time::Instant;
 
 const DEFAULT_URL: &str = "http://localhost:4000/v1/chat/completions";
 const DEFAULT_MODEL: &str = "llama-8b";
 const TIMEOUT_SECS: u64 = 120;
+const AUTH: AuthHint = AuthHint {
+    provider: "litellm",
+    env_var: "LITELLM_API_KEY",
+};
 
 /// LiteLLM proxy provider - unified interface to multiple LLMs
 pub struct LiteLLMProvider {
@@ -22,42 +30,24 @@ pub struct LiteLLMProvider {
     model: String,
 }
 
-#[derive(Serialize)]
-struct ChatRequest {
-    model: String,
-    messages: Vec<Message>,
-    max_tokens: u32,
-    stream: bool,
-}
-
-#[derive(Serialize)]
-struct Message {
-    role: String,
-    content: String,
-}
-
-#[derive(Deserialize)]
-struct StreamChunk {
-    choices: Vec<StreamChoice>,
-    usage: Option<Usage>,
-}
-
+/// `/model/info` response: one entry per deployment
 #[derive(Deserialize)]
-struct StreamChoice {
-    delta: Delta,
-    #[serde(rename = "finish_reason")]
-    _finish_reason: Option<String>,
+struct ModelInfoResponse {
+    data: Vec<Deployment>,
 }
 
 #[derive(Deserialize)]
-struct Delta {
-    content: Option<String>,
+struct Deployment {
+    /// Name clients request
+    model_name: String,
+    #[serde(default)]
+    litellm_params: DeploymentParams,
 }
 
-#[derive(Deserialize)]
-struct Usage {
-    prompt_tokens: u32,
-    completion_tokens: u32,
+#[derive(Default, Deserialize)]
+struct DeploymentParams {
+    /// Upstream model, e.g. `groq/llama-3.1-8b-instant`
+    model: Option<String>,
 }
 
 impl LiteLLMProvider {
@@ -84,10 +74,7 @@ impl LiteLLMProvider {
         let api_key = std::env::var("LITELLM_API_KEY").unwrap_or_else(|_| "dummy".to_string());
         let model = std::env::var("LITELLM_MODEL").unwrap_or_else(|_| DEFAULT_MODEL.to_string());
 
-        let client = Client::builder()
-            .timeout(Duration::from_secs(TIMEOUT_SECS))
-            .build()
-            .map_err(|e| ProviderError::Network(e.to_string()))?;
+        let client = http::build_client(TIMEOUT_SECS)?;
 
         Ok(Self {
             client,
@@ -117,117 +104,151 @@ impl InferenceProvider for LiteLLMProvider {
 
         let model = request.model.clone().unwrap_or_else(|| self.model.clone());
 
-        let chat_request = ChatRequest {
-            model,
-            messages: vec![Message {
-                role: "user".to_string(),
-                content: request.prompt.clone(),
-            }],
-            max_tokens: request.max_tokens,
-            stream: true,
-        };
+        let mut chat_request = ChatRequest::new(model, request);
+        chat_request.stream_options = StreamOptions::usage(request.stream);
 
-        let response = self
+        let http_request = self
             .client
             .post(&self.api_url)
             .header("Authorization", format!("Bearer {}", self.api_key))
             .header("Content-Type", "application/json")
-            .json(&chat_request)
-            .send()
-            .await
-            .map_err(|e| {
-                if e.is_timeout() {
-                    ProviderError::Timeout(TIMEOUT_SECS * 1000)
-                } else if e.is_connect() {
-                    ProviderError::Network(format!(
-                        "Cannot connect to LiteLLM at {}. Is it running?",
-                        self.api_url
-                    ))
-                } else {
-                    ProviderError::ApiError(e.to_string())
-                }
-            })?;
+            .json(&chat_request);
+        let request_bytes = http::body_len(&http_request);
+        let send = http::send(http_request);
+        let response =
+            openai_stream::before_first_token(send, start, request.first_token_timeout_ms)
+                .await?
+                .map_err(|e| {
+                    if e.is_connect() {
+                        ProviderError::Network(format!(
+                            "Cannot connect to LiteLLM at {}. Is it running?",
+                            self.api_url
+                        ))
+                    } else {
+                        openai_stream::map_send_error(e, TIMEOUT_SECS)
+                    }
+                })?;
 
         let time_to_prompt_ms = start.elapsed().as_millis() as u64;
+        let response = openai_stream::check_status(response, AUTH).await?;
 
-        if response.status() == 429 {
-            return Err(ProviderError::RateLimited);
-        }
+        let mut result = openai_stream::read_stream(
+            response,
+            start,
+            time_to_prompt_ms,
+            request.first_token_timeout_ms,
+            request.stall_timeout_ms,
+        )
+        .await?;
+        result.request_bytes = request_bytes;
+        Ok(result)
+    }
 
-        if !response.status().is_success() {
-            let status = response.status();
-            let body = response.text().await.unwrap_or_default();
-            return Err(ProviderError::ApiError(format!(
-                "HTTP {}: {}",
-                status, body
-            )));
-        }
+    fn default_model(&self) -> &str {
+        &self.model
+    }
+
+    fn endpoint(&self) -> Option<&str> {
+        Some(&self.api_url)
+    }
 
-        let mut stream = response.bytes_stream();
-        let mut first_token_time: Option<Duration> = None;
-        let mut output_text = String::new();
-        let mut input_tokens = 0u32;
-        let mut output_tokens = 0u32;
-        let mut buffer = String::new();
+    async fn list_models(&self) -> Result<Vec<ModelInfo>, ProviderError> {
+        let url = format!("{}/model/info", proxy_root(&self.api_url));
+        let request = self
+            .client
+            .get(&url)
+            .header("Authorization", format!("Bearer {}", self.api_key));
+        let response = http::send(request)
+            .await
+            .map_err(|e| openai_stream::map_send_error(e, TIMEOUT_SECS))?;
+        let response = openai_stream::check_status(response, AUTH).await?;
+        let body = response
+            .bytes()
+            .await
+            .map_err(|e| ProviderError::Network(e.to_string()))?;
+        routable_models(&body)
+    }
 
-        while let Some(chunk_result) = stream.next().await {
-            let chunk = chunk_result.map_err(|e| ProviderError::Network(e.to_string()))?;
+    fn supports_non_streaming(&self) -> bool {
+        true
+    }
 
-            if first_token_time.is_none() && !chunk.is_empty() {
-                first_token_time = Some(start.elapsed());
-            }
+    fn supports_tools(&self) -> bool {
+        true
+    }
 
-            let chunk_str = String::from_utf8_lossy(&chunk);
-            buffer.push_str(&chunk_str);
+    fn supports_history(&self) -> bool {
+        true
+    }
 
-            while let Some(line_end) = buffer.find('\n') {
-                let line = buffer[..line_end].trim().to_string();
-                buffer = buffer[line_end + 1..].to_string();
+    fn pricing_for_model(&self, _model: &str) -> (f64, f64) {
+        // Pricing depends on underlying model - assume free/unknown
+        (0.0, 0.0)
+    }
+}
 
-                if let Some(data) = line.strip_prefix("data: ") {
-                    if data == "[DONE]" {
-                        continue;
-                    }
+/// Proxy base URL, without the chat completions path of `LITELLM_URL`
+fn proxy_root(api_url: &str) -> &str {
+    let root = api_url.trim_end_matches('/');
+    let root = root.strip_suffix("/chat/completions").unwrap_or(root);
+    root.strip_suffix("/v1").unwrap_or(root)
+}
 
-                    if let Ok(chunk_data) = serde_json::from_str::<StreamChunk>(data) {
-                        for choice in chunk_data.choices {
-                            if let Some(content) = choice.delta.content {
-                                output_text.push_str(&content);
-                            }
-                        }
-                        if let Some(usage) = chunk_data.usage {
-                            input_tokens = usage.prompt_tokens;
-                            output_tokens = usage.completion_tokens;
-                        }
-                    }
-                }
+/// Model names in a `/model/info` response, in listed order, each with the
+/// upstreams of its deployments
+fn routable_models(body: &[u8]) -> Result<Vec<ModelInfo>, ProviderError> {
+    let info: ModelInfoResponse =
+        serde_json::from_slice(body).map_err(|e| ProviderError::ParseError(e.to_string()))?;
+    let mut models: Vec<ModelInfo> = Vec::new();
+    for deployment in info.data {
+        let index = match models.iter().position(|m| m.id == deployment.model_name) {
+            Some(index) => index,
+            None => {
+                models.push(ModelInfo {
+                    id: deployment.model_name,
+                    upstreams: Vec::new(),
+                });
+                models.len() - 1
             }
+        };
+        if let Some(upstream) = deployment.litellm_params.model
+            && !models[index].upstreams.contains(&upstream)
+        {
+            models[index].upstreams.push(upstream);
         }
-
-        let total_latency_ms = start.elapsed().as_millis() as u64;
-        let ttft_ms = first_token_time
-            .map(|t| t.as_millis() as u64)
-            .unwrap_or(total_latency_ms);
-
-        let time_to_first_token_ms = ttft_ms.saturating_sub(time_to_prompt_ms);
-
-        Ok(InferenceResponse {
-            text: output_text,
-            input_tokens,
-            output_tokens,
-            time_to_prompt_ms,
-            time_to_first_token_ms,
-            total_latency_ms,
-            model_load_time_ms: None,
-        })
-    }
-
-    fn default_model(&self) -> &str {
-        &self.model
     }
+    Ok(models)
+}
 
-    fn pricing_per_million(&self) -> (f64, f64) {
-        // Pricing depends on underlying model - assume free/unknown
-        (0.0, 0.0)
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_routable_models_group_deployments() {
+        assert_eq!(
+            proxy_root("http://localhost:4000/v1/chat/completions"),
+            "http://localhost:4000"
+        );
+        assert_eq!(
+            proxy_root("https://llm.example.com/"),
+            "https://llm.example.com"
+        );
+
+        let body = br#"{"data":[
+            {"model_name":"llama-8b","litellm_params":{"model":"groq/llama-3.1-8b-instant"}},
+            {"model_name":"gpt-4o","litellm_params":{"model":"openai/gpt-4o"},"model_info":{}},
+            {"model_name":"llama-8b","litellm_params":{"model":"cerebras/llama3.1-8b"}},
+            {"model_name":"embed"}
+        ]}"#;
+        let models = routable_models(body).unwrap();
+
+        let ids: Vec<&str> = models.iter().map(|m| m.id.as_str()).collect();
+        assert_eq!(ids, ["llama-8b", "gpt-4o", "embed"]);
+        assert_eq!(
+            models[0].upstreams,
+            ["groq/llama-3.1-8b-instant", "cerebras/llama3.1-8b"]
+        );
+        assert!(models[2].upstreams.is_empty());
     }
 }
@@ -3,24 +3,55 @@
 //! Supports multiple Ollama instances for comparing different hardware:
 //! - `local` - Primary instance (OLLAMA_URL, default: localhost:11434)
 //! - `local-rtx` - Secondary instance for RTX GPU (OLLAMA_RTX_URL)
-
-use super::{InferenceProvider, InferenceRequest, InferenceResponse, ProviderError};
+//!
+//! The secondary is configured only by its own `OLLAMA_RTX_*` variables and
+//! never inherits the primary's URL or model; its embedding model and API
+//! mode alone fall back to the shared OLLAMA_EMBEDDING_MODEL (default:
+//! nomic-embed-text) and OLLAMA_OPENAI_MODE.
+//!
+//! Chat inference streams newline-delimited JSON from Ollama's native
+//! `/api/generate` by default. With OLLAMA_OPENAI_MODE=true it streams from
+//! the OpenAI-compatible `/v1/chat/completions` instead, which does not
+//! report model load time.
+
+use super::openai_stream::{self, AuthHint, ChatRequest, StreamOptions};
+use super::{
+    EmbeddingRequest, EmbeddingResponse, InferenceProvider, InferenceRequest, InferenceResponse,
+    ProviderError, http,
+};
+use crate::benchmark::estimate_tokens;
 use async_trait::async_trait;
-use reqwest::Client;
+use futures::StreamExt;
+use reqwest::{Client, Response};
 use serde::{Deserialize, Serialize};
 use std::time::{Duration, Instant};
 
 const DEFAULT_OLLAMA_URL: &str = "http://localhost:11434";
 const DEFAULT_MODEL: &str = "llama3.1:8b";
+const DEFAULT_EMBEDDING_MODEL: &str = "nomic-embed-text";
+const DEFAULT_RTX_DISPLAY_NAME: &str = "Local RTX (Ollama)";
 const TIMEOUT_SECS: u64 = 300; // Local inference can be slow
 
+/// Upper bound on the reachability probe, so an unreachable remote instance
+/// is skipped quickly instead of waiting out the inference timeout
+const AVAILABILITY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Ollama takes no API key; a 401/403 can only come from a proxy in front
+const OPENAI_AUTH: AuthHint = AuthHint {
+    provider: "ollama",
+    env_var: "OLLAMA_URL",
+};
+
 /// Local inference provider using Ollama
 pub struct LocalProvider {
     client: Client,
     base_url: String,
     model: String,
+    embedding_model: String,
     name: String,
     display_name: String,
+    /// Stream chat from `/v1/chat/completions` instead of `/api/generate`
+    openai_mode: bool,
 }
 
 #[derive(Serialize)]
@@ -28,19 +59,46 @@ struct GenerateRequest {
     model: String,
     prompt: String,
     stream: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    options: Option<GenerateOptions>,
+}
+
+#[derive(Serialize)]
+struct GenerateOptions {
+    /// Maximum tokens to generate
+    num_predict: u32,
 }
 
+/// One line of a streamed `/api/generate` response; counts and durations
+/// arrive on the final (`done`) line
 #[derive(Deserialize)]
 struct GenerateResponse {
     response: String,
-    #[serde(rename = "done")]
-    _done: bool,
+    done: bool,
     #[serde(default)]
     prompt_eval_count: u32,
     #[serde(default)]
     eval_count: u32,
     #[serde(default)]
     load_duration: u64, // nanoseconds
+    /// `"length"` when generation stopped at `num_predict`
+    #[serde(default)]
+    done_reason: Option<String>,
+}
+
+#[derive(Serialize)]
+struct EmbedRequest {
+    model: String,
+    input: String,
+}
+
+#[derive(Deserialize)]
+struct EmbedResponse {
+    embeddings: Vec<Vec<f32>>,
+    #[serde(default)]
+    prompt_eval_count: u32,
+    #[serde(default)]
+    load_duration: u64, // nanoseconds
 }
 
 #[derive(Deserialize)]
@@ -60,18 +118,19 @@ impl LocalProvider {
         display_name: &str,
         base_url: String,
         model: String,
+        embedding_model: String,
+        openai_mode: bool,
     ) -> Result<Self, ProviderError> {
-        let client = Client::builder()
-            .timeout(Duration::from_secs(TIMEOUT_SECS))
-            .build()
-            .map_err(|e| ProviderError::Network(e.to_string()))?;
+        let client = http::build_client(TIMEOUT_SECS)?;
 
         Ok(Self {
             client,
             base_url,
             model,
+            embedding_model,
             name: name.to_string(),
             display_name: display_name.to_string(),
+            openai_mode,
         })
     }
 
@@ -80,46 +139,119 @@ impl LocalProvider {
     /// Environment variables:
     /// - OLLAMA_URL: Ollama server URL (default: http://localhost:11434)
     /// - OLLAMA_MODEL: Model to use (default: llama3.1:8b)
+    /// - OLLAMA_EMBEDDING_MODEL: Embedding model (default: nomic-embed-text)
+    /// - OLLAMA_OPENAI_MODE: `true` to stream chat from `/v1` (default: false)
     pub fn detect() -> Result<Self, ProviderError> {
         let base_url =
             std::env::var("OLLAMA_URL").unwrap_or_else(|_| DEFAULT_OLLAMA_URL.to_string());
         let model =
             std::env::var("OLLAMA_MODEL").unwrap_or_else(|_| DEFAULT_MODEL.to_string());
 
-        Self::new("local", "Local (Ollama)", base_url, model)
+        Self::new(
+            "local",
+            "Local (Ollama)",
+            base_url,
+            model,
+            shared_embedding_model(),
+            shared_openai_mode(),
+        )
     }
 
     /// Detect and create the secondary RTX Ollama provider
     ///
-    /// Environment variables:
-    /// - OLLAMA_RTX_URL: RTX machine Ollama URL (required)
-    /// - OLLAMA_RTX_MODEL: Model to use (default: llama3.1:8b)
+    /// Environment variables, each resolved independently of the primary:
+    /// - OLLAMA_RTX_URL: RTX machine Ollama URL (required; OLLAMA_URL is
+    ///   never used)
+    /// - OLLAMA_RTX_MODEL: Model to use (default: llama3.1:8b, not OLLAMA_MODEL)
+    /// - OLLAMA_RTX_NAME: Display name (default: "Local RTX (Ollama)")
+    /// - OLLAMA_RTX_EMBEDDING_MODEL: Embedding model (default:
+    ///   OLLAMA_EMBEDDING_MODEL, then nomic-embed-text)
+    /// - OLLAMA_RTX_OPENAI_MODE: `true` to stream chat from `/v1` (default:
+    ///   OLLAMA_OPENAI_MODE)
     pub fn detect_rtx() -> Result<Self, ProviderError> {
         let base_url = std::env::var("OLLAMA_RTX_URL").map_err(|_| {
-            ProviderError::NotConfigured(
-                "OLLAMA_RTX_URL environment variable not set".to_string(),
-            )
+            ProviderError::NotConfigured("OLLAMA_RTX_URL environment variable not set".to_string())
         })?;
-        let model =
-            std::env::var("OLLAMA_RTX_MODEL").unwrap_or_else(|_| DEFAULT_MODEL.to_string());
-
-        Self::new("local-rtx", "Local RTX (Ollama)", base_url, model)
+        let model = std::env::var("OLLAMA_RTX_MODEL").unwrap_or_else(|_| DEFAULT_MODEL.to_string());
+        let display_name = std::env::var("OLLAMA_RTX_NAME")
+            .unwrap_or_else(|_| DEFAULT_RTX_DISPLAY_NAME.to_string());
+        let embedding_model = std::env::var("OLLAMA_RTX_EMBEDDING_MODEL")
+            .unwrap_or_else(|_| shared_embedding_model());
+        let openai_mode = std::env::var("OLLAMA_RTX_OPENAI_MODE")
+            .map(|v| is_true(&v))
+            .unwrap_or_else(|_| shared_openai_mode());
+
+        Self::new(
+            "local-rtx",
+            &display_name,
+            base_url,
+            model,
+            embedding_model,
+            openai_mode,
+        )
     }
 
     /// Check if Ollama is running
     async fn check_ollama(&self) -> bool {
         let url = format!("{}/api/tags", self.base_url);
-        self.client.get(&url).send().await.is_ok()
+        http::send(self.client.get(&url).timeout(AVAILABILITY_TIMEOUT))
+            .await
+            .is_ok()
+    }
+
+    /// Map a send failure, pointing at `ollama serve` when nothing is listening
+    fn map_send_error(&self, e: reqwest::Error) -> ProviderError {
+        if e.is_timeout() {
+            ProviderError::Timeout(http::request_timeout(TIMEOUT_SECS).as_millis() as u64)
+        } else if e.is_connect() {
+            ProviderError::Network(format!(
+                "Cannot connect to Ollama at {}. Is it running? (ollama serve)",
+                self.base_url
+            ))
+        } else {
+            ProviderError::ApiError(e.to_string())
+        }
+    }
+
+    /// Stream a chat completion from Ollama's OpenAI-compatible endpoint
+    async fn infer_openai(
+        &self,
+        request: &InferenceRequest,
+        start: Instant,
+    ) -> Result<InferenceResponse, ProviderError> {
+        let model = request.model.clone().unwrap_or_else(|| self.model.clone());
+        let mut chat_request = ChatRequest::new(model, request);
+        chat_request.stream_options = StreamOptions::usage(request.stream);
+
+        let url = format!("{}/v1/chat/completions", self.base_url);
+        let http_request = self.client.post(&url).json(&chat_request);
+        let request_bytes = http::body_len(&http_request);
+        let send = http::send(http_request);
+        let response =
+            openai_stream::before_first_token(send, start, request.first_token_timeout_ms)
+                .await?
+                .map_err(|e| self.map_send_error(e))?;
+
+        let time_to_prompt_ms = start.elapsed().as_millis() as u64;
+        let response = openai_stream::check_status(response, OPENAI_AUTH).await?;
+
+        let mut result = openai_stream::read_stream(
+            response,
+            start,
+            time_to_prompt_ms,
+            request.first_token_timeout_ms,
+            request.stall_timeout_ms,
+        )
+        .await?;
+        result.request_bytes = request_bytes;
+        Ok(result)
     }
 
     /// List available models
     #[allow(dead_code)]
     async fn list_models(&self) -> Result<Vec<String>, ProviderError> {
         let url = format!("{}/api/tags", self.base_url);
-        let response = self
-            .client
-            .get(&url)
-            .send()
+        let response = http::send(self.client.get(&url))
             .await
             .map_err(|e| ProviderError::Network(e.to_string()))?;
 
@@ -132,6 +264,103 @@ impl LocalProvider {
     }
 }
 
+/// Read a streamed `/api/generate` response, one JSON object per line
+///
+/// TTFT is taken at the first line carrying generated text.
+async fn read_ndjson(
+    response: Response,
+    start: Instant,
+    time_to_prompt_ms: u64,
+    request: &InferenceRequest,
+) -> Result<InferenceResponse, ProviderError> {
+    let mut stream = response.bytes_stream();
+    let mut first_token_time: Option<Duration> = None;
+    let mut text = String::new();
+    let mut buffer = String::new();
+    let mut chunk_token_counts = Vec::new();
+    let mut response_bytes = 0u64;
+    let mut last = None;
+
+    loop {
+        let next = if first_token_time.is_none() {
+            openai_stream::before_first_token(stream.next(), start, request.first_token_timeout_ms)
+                .await?
+        } else {
+            openai_stream::next_chunk(&mut stream, request.stall_timeout_ms).await?
+        };
+        let Some(chunk_result) = next else {
+            break;
+        };
+        let chunk = chunk_result.map_err(|e| ProviderError::Network(e.to_string()))?;
+        response_bytes += chunk.len() as u64;
+        buffer.push_str(&String::from_utf8_lossy(&chunk));
+
+        // Estimated tokens of text in this read
+        let mut tokens = 0u32;
+        while let Some(line_end) = buffer.find('\n') {
+            let line = buffer[..line_end].trim().to_string();
+            buffer = buffer[line_end + 1..].to_string();
+            if line.is_empty() {
+                continue;
+            }
+            tracing::trace!(line = %line, "NDJSON line");
+
+            let line: GenerateResponse = serde_json::from_str(&line)
+                .map_err(|e| ProviderError::ParseError(e.to_string()))?;
+            if !line.response.is_empty() {
+                first_token_time.get_or_insert_with(|| start.elapsed());
+                tokens += estimate_tokens(&line.response);
+                text.push_str(&line.response);
+            }
+            if line.done {
+                last = Some(line);
+            }
+        }
+        if tokens > 0 {
+            chunk_token_counts.push(tokens);
+        }
+    }
+
+    let last = last.ok_or_else(|| {
+        ProviderError::ParseError("stream ended before the final line".to_string())
+    })?;
+    let total_latency_ms = start.elapsed().as_millis() as u64;
+    let ttft_ms = first_token_time
+        .map(|t| t.as_millis() as u64)
+        .unwrap_or(total_latency_ms);
+
+    // Ollama provides load_duration in nanoseconds
+    let model_load_time_ms = (last.load_duration > 0).then_some(last.load_duration / 1_000_000);
+
+    Ok(InferenceResponse {
+        text,
+        input_tokens: last.prompt_eval_count,
+        output_tokens: last.eval_count,
+        time_to_prompt_ms,
+        time_to_first_token_ms: ttft_ms.saturating_sub(time_to_prompt_ms),
+        total_latency_ms,
+        model_load_time_ms,
+        chunk_token_counts,
+        truncated: last.done_reason.as_deref() == Some("length"),
+        response_bytes,
+        ..Default::default()
+    })
+}
+
+/// Embedding model from OLLAMA_EMBEDDING_MODEL, shared by both instances
+fn shared_embedding_model() -> String {
+    std::env::var("OLLAMA_EMBEDDING_MODEL").unwrap_or_else(|_| DEFAULT_EMBEDDING_MODEL.to_string())
+}
+
+/// API mode from OLLAMA_OPENAI_MODE, shared by both instances
+fn shared_openai_mode() -> bool {
+    std::env::var("OLLAMA_OPENAI_MODE").is_ok_and(|v| is_true(&v))
+}
+
+fn is_true(value: &str) -> bool {
+    value == "1" || value == "true"
+}
+
 #[async_trait]
 impl InferenceProvider for LocalProvider {
     fn name(&self) -> &str {
@@ -149,34 +378,90 @@ impl InferenceProvider for LocalProvider {
     async fn infer(&self, request: &InferenceRequest) -> Result<InferenceResponse, ProviderError> {
         let start = Instant::now();
 
+        if self.openai_mode {
+            return self.infer_openai(request, start).await;
+        }
+
         let model = request.model.clone().unwrap_or_else(|| self.model.clone());
 
         let generate_request = GenerateRequest {
             model,
             prompt: request.prompt.clone(),
-            stream: false, // Non-streaming for simplicity; can add streaming later
+            stream: true,
+            options: Some(GenerateOptions {
+                num_predict: request.max_tokens,
+            }),
         };
 
         let url = format!("{}/api/generate", self.base_url);
 
-        let response = self
-            .client
-            .post(&url)
-            .json(&generate_request)
-            .send()
+        let http_request = self.client.post(&url).json(&generate_request);
+        let request_bytes = http::body_len(&http_request);
+        let send = http::send(http_request);
+        let response =
+            openai_stream::before_first_token(send, start, request.first_token_timeout_ms)
+                .await?
+                .map_err(|e| self.map_send_error(e))?;
+
+        let time_to_prompt_ms = start.elapsed().as_millis() as u64;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            return Err(ProviderError::ApiError(format!(
+                "HTTP {}: {}",
+                status,
+                http::redact(&body)
+            )));
+        }
+
+        let mut result = read_ndjson(response, start, time_to_prompt_ms, request).await?;
+        result.request_bytes = request_bytes;
+        Ok(result)
+    }
+
+    fn default_model(&self) -> &str {
+        &self.model
+    }
+
+    fn endpoint(&self) -> Option<&str> {
+        Some(&self.base_url)
+    }
+
+    fn supports_non_streaming(&self) -> bool {
+        // Requests to the native API are always streamed
+        self.openai_mode
+    }
+
+    fn supports_tools(&self) -> bool {
+        // Only the OpenAI-compatible API is sent tool definitions
+        self.openai_mode
+    }
+
+    fn supports_history(&self) -> bool {
+        // Likewise for earlier messages; /api/generate takes one prompt
+        self.openai_mode
+    }
+
+    fn embedding_model(&self) -> Option<&str> {
+        Some(&self.embedding_model)
+    }
+
+    async fn embed(&self, request: &EmbeddingRequest) -> Result<EmbeddingResponse, ProviderError> {
+        let start = Instant::now();
+
+        let embed_request = EmbedRequest {
+            model: request
+                .model
+                .clone()
+                .unwrap_or_else(|| self.embedding_model.clone()),
+            input: request.input.clone(),
+        };
+
+        let url = format!("{}/api/embed", self.base_url);
+        let response = http::send(self.client.post(&url).json(&embed_request))
             .await
-            .map_err(|e| {
-                if e.is_timeout() {
-                    ProviderError::Timeout(TIMEOUT_SECS * 1000)
-                } else if e.is_connect() {
-                    ProviderError::Network(format!(
-                        "Cannot connect to Ollama at {}. Is it running? (ollama serve)",
-                        self.base_url
-                    ))
-                } else {
-                    ProviderError::ApiError(e.to_string())
-                }
-            })?;
+            .map_err(|e| self.map_send_error(e))?;
 
         let time_to_prompt_ms = start.elapsed().as_millis() as u64;
 
@@ -185,45 +470,168 @@ impl InferenceProvider for LocalProvider {
             let body = response.text().await.unwrap_or_default();
             return Err(ProviderError::ApiError(format!(
                 "HTTP {}: {}",
-                status, body
+                status,
+                http::redact(&body)
             )));
         }
 
-        let result: GenerateResponse = response
+        let result: EmbedResponse = response
             .json()
             .await
             .map_err(|e| ProviderError::ParseError(e.to_string()))?;
 
         let total_latency_ms = start.elapsed().as_millis() as u64;
 
-        // Ollama provides load_duration in nanoseconds
+        let dimensions = result
+            .embeddings
+            .first()
+            .map(|e| e.len() as u32)
+            .ok_or_else(|| ProviderError::ParseError("no embeddings returned".to_string()))?;
+
         let model_load_time_ms = if result.load_duration > 0 {
             Some(result.load_duration / 1_000_000)
         } else {
             None
         };
 
-        // For non-streaming, TTFT is approximately the full latency minus output generation
-        // This is an approximation; streaming would give more accurate TTFT
-        let time_to_first_token_ms = time_to_prompt_ms;
-
-        Ok(InferenceResponse {
-            text: result.response,
+        Ok(EmbeddingResponse {
             input_tokens: result.prompt_eval_count,
-            output_tokens: result.eval_count,
+            dimensions,
             time_to_prompt_ms,
-            time_to_first_token_ms,
             total_latency_ms,
             model_load_time_ms,
         })
     }
 
-    fn default_model(&self) -> &str {
-        &self.model
+    async fn preload(&self, model: &str) -> Result<Option<u64>, ProviderError> {
+        // Ollama loads the model and returns without generating for an empty prompt
+        let load_request = GenerateRequest {
+            model: model.to_string(),
+            prompt: String::new(),
+            stream: false,
+            options: None,
+        };
+
+        let url = format!("{}/api/generate", self.base_url);
+        let response = http::send(self.client.post(&url).json(&load_request))
+            .await
+            .map_err(|e| ProviderError::Network(e.to_string()))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            return Err(ProviderError::ApiError(format!(
+                "HTTP {}: {}",
+                status,
+                http::redact(&body)
+            )));
+        }
+
+        let result: GenerateResponse = response
+            .json()
+            .await
+            .map_err(|e| ProviderError::ParseError(e.to_string()))?;
+
+        Ok(Some(result.load_duration / 1_000_000))
     }
 
-    fn pricing_per_million(&self) -> (f64, f64) {
+    fn pricing_for_model(&self, _model: &str) -> (f64, f64) {
         // Local inference is free
         (0.0, 0.0)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::mock_server::{MockResponse, MockServer};
+
+    #[tokio::test]
+    async fn test_openai_mode_streams_from_v1() {
+        let server = MockServer::start(vec![
+            MockResponse::sse(&[
+                r#"{"choices":[{"delta":{"content":"Hello"}}]}"#,
+                r#"{"choices":[{"delta":{"content":" there"}}]}"#,
+                r#"{"choices":[],"usage":{"prompt_tokens":7,"completion_tokens":2}}"#,
+                "[DONE]",
+            ])
+            .first_chunk_delay(Duration::from_millis(50)),
+        ])
+        .await;
+        let provider = LocalProvider::new(
+            "local",
+            "Local (Ollama)",
+            server.base_url().to_string(),
+            DEFAULT_MODEL.to_string(),
+            DEFAULT_EMBEDDING_MODEL.to_string(),
+            true,
+        )
+        .unwrap();
+
+        let response = provider
+            .infer(&InferenceRequest {
+                prompt: "Hi".to_string(),
+                max_tokens: 16,
+                min_tokens: None,
+                model: None,
+                first_token_timeout_ms: None,
+                stall_timeout_ms: None,
+                idempotency_key: None,
+                stream: true,
+                tools: false,
+                history: Vec::new(),
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(response.text, "Hello there");
+        assert_eq!((response.input_tokens, response.output_tokens), (7, 2));
+        assert!(response.time_to_first_token_ms >= 40);
+        assert!(server.requests()[0].starts_with("POST /v1/chat/completions "));
+    }
+
+    #[tokio::test]
+    async fn test_native_mode_counts_tokens_per_ndjson_line() {
+        let burst = "several words arriving in a single line";
+        let server = MockServer::start(vec![MockResponse::ndjson(&[
+            r#"{"response":"Hi","done":false}"#,
+            &format!(r#"{{"response":"{burst}","done":false}}"#),
+            r#"{"response":"","done":true,"done_reason":"stop","prompt_eval_count":5,"eval_count":9,"load_duration":3000000}"#,
+        ])])
+        .await;
+        let provider = LocalProvider::new(
+            "local",
+            "Local (Ollama)",
+            server.base_url().to_string(),
+            DEFAULT_MODEL.to_string(),
+            DEFAULT_EMBEDDING_MODEL.to_string(),
+            false,
+        )
+        .unwrap();
+
+        let response = provider
+            .infer(&InferenceRequest {
+                prompt: "Hi".to_string(),
+                max_tokens: 16,
+                min_tokens: None,
+                model: None,
+                first_token_timeout_ms: None,
+                stall_timeout_ms: None,
+                idempotency_key: None,
+                stream: true,
+                tools: false,
+                history: Vec::new(),
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(response.text, format!("Hi{burst}"));
+        assert_eq!((response.input_tokens, response.output_tokens), (5, 9));
+        assert_eq!(response.model_load_time_ms, Some(3));
+        assert_eq!(
+            response.chunk_token_counts.iter().sum::<u32>(),
+            estimate_tokens("Hi") + estimate_tokens(burst)
+        );
+        assert!(server.requests()[0].starts_with("POST /api/generate "));
+    }
+}
@@ -0,0 +1,213 @@
+//! Canned-response HTTP server for provider tests.
+//!
+//! Serves a scripted sequence of responses on a local port, one per
+//! connection, and records each request, so `infer` can be exercised end to
+//! end (streaming parse, TTFT, error mapping, retries) without API keys.
+
+use super::{CompletionMode, OpenAICompatibleProvider};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+/// API key sent by [`openai_provider`], long enough to be redacted from
+/// error messages
+pub(crate) const MOCK_API_KEY: &str = "sk-test-0123456789abcdefghijklmn";
+
+/// One scripted response
+#[derive(Debug, Clone)]
+pub(crate) struct MockResponse {
+    status: u16,
+    headers: Vec<(String, String)>,
+    body: Vec<String>,
+    first_chunk_delay: Duration,
+    abort: bool,
+    stall: bool,
+}
+
+impl MockResponse {
+    /// `200 OK` SSE stream with one `data:` event per item, sent as
+    /// separate chunks
+    pub fn sse(events: &[&str]) -> Self {
+        Self {
+            status: 200,
+            headers: vec![("Content-Type".to_string(), "text/event-stream".to_string())],
+            body: events.iter().map(|e| format!("data: {}\n\n", e)).collect(),
+            first_chunk_delay: Duration::ZERO,
+            abort: false,
+            stall: false,
+        }
+    }
+
+    /// `200 OK` newline-delimited JSON stream with one line per item, sent as
+    /// separate chunks
+    pub fn ndjson(lines: &[&str]) -> Self {
+        Self {
+            status: 200,
+            headers: vec![(
+                "Content-Type".to_string(),
+                "application/x-ndjson".to_string(),
+            )],
+            body: lines.iter().map(|l| format!("{}\n", l)).collect(),
+            first_chunk_delay: Duration::ZERO,
+            abort: false,
+            stall: false,
+        }
+    }
+
+    /// Empty `/models` listing, answering an availability probe
+    pub fn probe() -> Self {
+        Self::status(200, r#"{"data":[]}"#)
+    }
+
+    /// Plain response with the given status and body
+    pub fn status(status: u16, body: &str) -> Self {
+        Self {
+            status,
+            headers: Vec::new(),
+            body: vec![body.to_string()],
+            first_chunk_delay: Duration::ZERO,
+            abort: false,
+            stall: false,
+        }
+    }
+
+    /// Add a response header
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    /// Wait this long after the headers before sending the first body chunk
+    pub fn first_chunk_delay(mut self, delay: Duration) -> Self {
+        self.first_chunk_delay = delay;
+        self
+    }
+
+    /// Drop the connection after the first body chunk, mid-stream
+    pub fn abort_mid_body(mut self) -> Self {
+        self.abort = true;
+        self
+    }
+
+    /// Go quiet after the first body chunk, holding the connection open
+    /// until the client hangs up
+    pub fn stall_after_first_chunk(mut self) -> Self {
+        self.stall = true;
+        self
+    }
+}
+
+/// OpenAI-compatible chat provider (`mock`, serving `mock-model`) pointed at
+/// `server`'s `/v1`
+pub(crate) fn openai_provider(server: &MockServer) -> OpenAICompatibleProvider {
+    OpenAICompatibleProvider::new(
+        format!("{}/v1", server.base_url()),
+        Some(MOCK_API_KEY.to_string()),
+        "mock-model".to_string(),
+        "mock".to_string(),
+        CompletionMode::Chat,
+    )
+    .unwrap()
+}
+
+/// Local server answering connections with scripted responses in order
+pub(crate) struct MockServer {
+    base_url: String,
+    requests: Arc<Mutex<Vec<String>>>,
+}
+
+impl MockServer {
+    /// Start serving `responses`, one per incoming connection
+    pub async fn start(responses: Vec<MockResponse>) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+
+        let recorded = Arc::clone(&requests);
+        tokio::spawn(async move {
+            for response in responses {
+                let Ok((mut socket, _)) = listener.accept().await else {
+                    return;
+                };
+                let request = read_request(&mut socket).await;
+                recorded.lock().unwrap().push(request);
+                let _ = write_response(&mut socket, &response).await;
+            }
+        });
+
+        Self { base_url, requests }
+    }
+
+    /// `http://127.0.0.1:<port>`, without a trailing slash
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// Raw requests received so far (request line, headers, and body)
+    pub fn requests(&self) -> Vec<String> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+/// Read one request: headers, then a `Content-Length` body if present
+async fn read_request(socket: &mut TcpStream) -> String {
+    let mut data = Vec::new();
+    let mut buf = [0u8; 4096];
+    while let Ok(n) = socket.read(&mut buf).await {
+        if n == 0 {
+            break;
+        }
+        data.extend_from_slice(&buf[..n]);
+
+        let text = String::from_utf8_lossy(&data);
+        if let Some(header_end) = text.find("\r\n\r\n") {
+            let content_length = text[..header_end]
+                .lines()
+                .find_map(|line| {
+                    let (name, value) = line.split_once(':')?;
+                    name.eq_ignore_ascii_case("content-length")
+                        .then(|| value.trim().parse::<usize>().ok())?
+                })
+                .unwrap_or(0);
+            if data.len() >= header_end + 4 + content_length {
+                break;
+            }
+        }
+    }
+    String::from_utf8_lossy(&data).into_owned()
+}
+
+/// Write a response with a chunked body, honoring the scripted delay, abort,
+/// and stall
+async fn write_response(socket: &mut TcpStream, response: &MockResponse) -> std::io::Result<()> {
+    let mut head = format!("HTTP/1.1 {} Mock\r\n", response.status);
+    for (name, value) in &response.headers {
+        head.push_str(&format!("{}: {}\r\n", name, value));
+    }
+    head.push_str("Transfer-Encoding: chunked\r\nConnection: close\r\n\r\n");
+    socket.write_all(head.as_bytes()).await?;
+    socket.flush().await?;
+
+    if !response.first_chunk_delay.is_zero() {
+        tokio::time::sleep(response.first_chunk_delay).await;
+    }
+    for chunk in &response.body {
+        socket
+            .write_all(format!("{:x}\r\n{}\r\n", chunk.len(), chunk).as_bytes())
+            .await?;
+        socket.flush().await?;
+        if response.abort {
+            // Promise more data than is sent, then hang up
+            socket.write_all(b"100\r\npartial").await?;
+            return socket.shutdown().await;
+        }
+        if response.stall {
+            // Nothing more is sent; a read returns once the client disconnects
+            let _ = socket.read(&mut [0u8; 1]).await;
+            return Ok(());
+        }
+    }
+    socket.write_all(b"0\r\n\r\n").await?;
+    socket.shutdown().await
+}
@@ -1,29 +1,45 @@
 //! Provider trait and implementations for LLM inference APIs.
 
+mod azure;
 mod cerebras;
 mod deepseek;
+mod embeddings;
 mod fireworks;
 mod groq;
+mod http;
 mod litellm;
 mod local;
+#[cfg(test)]
+pub(crate) mod mock_server;
+pub mod model_cache;
 mod moonshot;
 mod openai_compatible;
+mod openai_stream;
 mod openrouter;
 mod sambanova;
 mod together;
+mod validate;
+mod vertex;
 mod zai;
 
+pub use azure::AzureOpenAIProvider;
 pub use cerebras::CerebrasProvider;
 pub use deepseek::DeepSeekProvider;
 pub use fireworks::FireworksProvider;
 pub use groq::GroqProvider;
+pub use http::{
+    ConnectionPool, DEFAULT_POOL_IDLE_TIMEOUT_SECS, redact, set_connection_pool,
+    set_request_timeout,
+};
 pub use litellm::LiteLLMProvider;
 pub use local::LocalProvider;
 pub use moonshot::MoonshotProvider;
-pub use openai_compatible::OpenAICompatibleProvider;
+pub use openai_compatible::{CompletionMode, OpenAICompatibleProvider, ProviderDefinition};
 pub use openrouter::OpenRouterProvider;
 pub use sambanova::SambaNovaProvider;
 pub use together::TogetherProvider;
+pub use validate::{ValidationStatus, validate_provider};
+pub use vertex::VertexProvider;
 pub use zai::ZaiProvider;
 
 use async_trait::async_trait;
@@ -38,19 +54,52 @@ pub struct InferenceRequest {
     pub prompt: String,
     /// Maximum tokens to generate
     pub max_tokens: u32,
+    /// Minimum tokens to generate, for providers that support forcing length
+    pub min_tokens: Option<u32>,
     /// Specific model to use (provider default if None)
     pub model: Option<String>,
+    /// Abort with [`ProviderError::FirstTokenTimeout`] if streaming providers
+    /// see no response data within this many ms of starting the request
+    pub first_token_timeout_ms: Option<u64>,
+    /// Abort with [`ProviderError::StreamStalled`] if a stream that has
+    /// started sends nothing for this many ms
+    pub stall_timeout_ms: Option<u64>,
+    /// Sent as `Idempotency-Key` by providers that honor it; the same for
+    /// every retry of one iteration
+    pub idempotency_key: Option<String>,
+    /// Stream the response; false asks providers that
+    /// [support it](InferenceProvider::supports_non_streaming) for a single
+    /// JSON body instead
+    pub stream: bool,
+    /// Offer a function definition the prompt should call, for providers
+    /// that [support it](InferenceProvider::supports_tools)
+    pub tools: bool,
+    /// Earlier conversation messages sent ahead of `prompt`, for providers
+    /// that [support it](InferenceProvider::supports_history)
+    pub history: Vec<ChatMessage>,
+}
+
+/// One message of a chat conversation
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChatMessage {
+    /// `system`, `user`, or `assistant`
+    pub role: String,
+    pub content: String,
 }
 
 /// Response from an inference provider with timing metrics
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct InferenceResponse {
     /// Generated text
     pub text: String,
     /// Number of input tokens
     pub input_tokens: u32,
-    /// Number of output tokens generated
+    /// Number of output tokens generated, excluding reasoning
     pub output_tokens: u32,
+    /// Hidden reasoning ("thinking") tokens generated before the answer,
+    /// billed as output
+    #[serde(default)]
+    pub reasoning_tokens: u32,
     /// Time until prompt was fully sent (ms)
     pub time_to_prompt_ms: u64,
     /// Time from prompt sent to first token received (ms)
@@ -59,6 +108,111 @@ pub struct InferenceResponse {
     pub total_latency_ms: u64,
     /// One-time model load time, if applicable (ms)
     pub model_load_time_ms: Option<u64>,
+    /// Rate-limit quota reported by the provider, if exposed in headers
+    #[serde(default)]
+    pub rate_limit: Option<RateLimitInfo>,
+    /// Estimated tokens of generated text received in each network read
+    /// while streaming (empty for non-streaming providers)
+    #[serde(default)]
+    pub chunk_token_counts: Vec<u32>,
+    /// The provider reported stopping at `max_tokens` (e.g. `finish_reason:
+    /// "length"`) rather than finishing on its own
+    #[serde(default)]
+    pub truncated: bool,
+    /// Serialized request body size (bytes)
+    #[serde(default)]
+    pub request_bytes: u64,
+    /// Response body received, after any decompression (bytes); with SSE
+    /// this includes the event framing
+    #[serde(default)]
+    pub response_bytes: u64,
+    /// The request carried an `Idempotency-Key`
+    #[serde(default)]
+    pub idempotent: bool,
+    /// The model answered with a tool call (its arguments are in `text`)
+    #[serde(default)]
+    pub tool_call: bool,
+    /// Time the request waited in the provider's queue before processing
+    /// began, where the API reports it (ms)
+    #[serde(default)]
+    pub queue_time_ms: Option<u64>,
+    /// Processing times reported by the provider, where the API has them
+    #[serde(default)]
+    pub server_timing: Option<ServerTiming>,
+}
+
+impl InferenceResponse {
+    /// Reject a response with no generated text or no output tokens
+    ///
+    /// A `200 OK` with empty content (e.g. a content filter or safety
+    /// refusal) would otherwise count as an implausibly fast success.
+    pub fn validate(&self) -> Result<(), ProviderError> {
+        if self.text.trim().is_empty() || self.output_tokens == 0 {
+            return Err(ProviderError::EmptyResponse {
+                output_tokens: self.output_tokens,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Request to an embeddings endpoint
+#[derive(Debug, Clone)]
+pub struct EmbeddingRequest {
+    /// Text to embed
+    pub input: String,
+    /// Specific model to use (provider's embedding model if None)
+    pub model: Option<String>,
+}
+
+/// Response from an embeddings endpoint with timing metrics
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EmbeddingResponse {
+    /// Number of input tokens embedded
+    pub input_tokens: u32,
+    /// Length of the returned vector
+    pub dimensions: u32,
+    /// Time until the input was fully sent (ms)
+    pub time_to_prompt_ms: u64,
+    /// Total request latency (ms)
+    pub total_latency_ms: u64,
+    /// One-time model load time, if applicable (ms)
+    pub model_load_time_ms: Option<u64>,
+}
+
+/// A model a provider can serve, as listed by the `models` subcommand
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModelInfo {
+    /// Name to request the model by
+    pub id: String,
+    /// Upstream models a proxy routes the name to (several when it load
+    /// balances); empty when unknown
+    pub upstreams: Vec<String>,
+}
+
+/// Rate-limit quota reported by a provider's response headers
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RateLimitInfo {
+    /// Requests remaining in the current window
+    pub remaining_requests: Option<u64>,
+    /// Tokens remaining in the current window
+    pub remaining_tokens: Option<u64>,
+    /// Seconds to wait before retrying, if the provider asked us to back off
+    pub retry_after_secs: Option<f64>,
+}
+
+/// Processing times a provider reports for a request (Groq's `usage`
+/// timings), free of the network time in client-side measurements
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct ServerTiming {
+    /// Prompt processing (ms)
+    pub prompt_ms: u64,
+    /// Generating the completion (ms)
+    pub completion_ms: u64,
+    /// Queue wait, prompt processing, and generation together (ms)
+    pub total_ms: u64,
 }
 
 /// Errors that can occur during inference
@@ -73,17 +227,38 @@ pub enum ProviderError {
     #[error("Request timeout after {0}ms")]
     Timeout(u64),
 
+    #[error("No first token within {0}ms")]
+    FirstTokenTimeout(u64),
+
+    #[error("Stream stalled: no data for {0}ms")]
+    StreamStalled(u64),
+
     #[error("Rate limited by provider")]
     RateLimited,
 
+    #[error("Invalid or missing API key for {provider}: check {env_var}")]
+    InvalidApiKey { provider: String, env_var: String },
+
     #[error("Network error: {0}")]
     Network(String),
 
     #[error("Failed to parse response: {0}")]
     ParseError(String),
+
+    #[error("Not supported: {0}")]
+    Unsupported(String),
+
+    #[error("Empty response ({output_tokens} output tokens): possible content filter or refusal")]
+    EmptyResponse { output_tokens: u32 },
+
+    #[error("Model answered without calling the offered tool")]
+    NoToolCall,
 }
 
 /// Trait that all inference providers must implement
+///
+/// The trait is object-safe so custom implementations can be boxed and added
+/// to a [`ProviderRegistry`] via [`ProviderRegistry::register`].
 #[async_trait]
 pub trait InferenceProvider: Send + Sync {
     /// Unique identifier for this provider
@@ -101,8 +276,89 @@ pub trait InferenceProvider: Send + Sync {
     /// Get the default model for this provider
     fn default_model(&self) -> &str;
 
-    /// Get pricing per million tokens (input, output)
-    fn pricing_per_million(&self) -> (f64, f64);
+    /// Load `model` ahead of timed requests, returning the load time in ms
+    /// if the provider reports one
+    ///
+    /// Only meaningful for providers that load models on demand; the default
+    /// does nothing.
+    async fn preload(&self, _model: &str) -> Result<Option<u64>, ProviderError> {
+        Ok(None)
+    }
+
+    /// Model used for embeddings, or None if the provider has no embeddings API
+    fn embedding_model(&self) -> Option<&str> {
+        None
+    }
+
+    /// Execute an embeddings request
+    ///
+    /// Providers without an embeddings API keep the default, which reports
+    /// the request as unsupported.
+    async fn embed(&self, _request: &EmbeddingRequest) -> Result<EmbeddingResponse, ProviderError> {
+        Err(ProviderError::Unsupported(format!(
+            "{} has no embeddings endpoint",
+            self.display_name()
+        )))
+    }
+
+    /// Base URL requests are sent to, recorded with results and used to spot
+    /// two providers pointing at the same backend
+    fn endpoint(&self) -> Option<&str> {
+        None
+    }
+
+    /// Models the provider can serve; the default lists only the default
+    /// model, without querying the provider
+    async fn list_models(&self) -> Result<Vec<ModelInfo>, ProviderError> {
+        Ok(vec![ModelInfo {
+            id: self.default_model().to_string(),
+            upstreams: Vec::new(),
+        }])
+    }
+
+    /// Whether [`InferenceRequest::stream`] can be turned off; providers
+    /// that only stream keep the default and ignore the flag
+    fn supports_non_streaming(&self) -> bool {
+        false
+    }
+
+    /// Whether [`InferenceRequest::tools`] is sent; providers without
+    /// function calling keep the default and ignore the flag
+    fn supports_tools(&self) -> bool {
+        false
+    }
+
+    /// Whether [`InferenceRequest::history`] is sent; single-prompt
+    /// providers keep the default and ignore it
+    fn supports_history(&self) -> bool {
+        false
+    }
+
+    /// Get pricing per million tokens (input, output) for a specific model,
+    /// falling back to the provider's default rate for unknown models
+    fn pricing_for_model(&self, model: &str) -> (f64, f64);
+
+    /// Get pricing per million tokens (input, output) for the default model
+    fn pricing_per_million(&self) -> (f64, f64) {
+        self.pricing_for_model(self.default_model())
+    }
+}
+
+/// A provider's entry in `list --json`
+///
+/// Fields other than `name` are `null` when the provider is not configured
+/// and nothing is known about it without its environment.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ProviderStatus {
+    pub name: String,
+    pub display_name: Option<String>,
+    pub default_model: Option<String>,
+    /// Input price for the default model (USD per 1M tokens)
+    pub input_per_million: Option<f64>,
+    /// Output price for the default model (USD per 1M tokens)
+    pub output_per_million: Option<f64>,
+    /// Whether the provider's environment is set up for benchmarking
+    pub configured: bool,
 }
 
 /// Registry of all available providers
@@ -111,101 +367,164 @@ pub struct ProviderRegistry {
 }
 
 impl ProviderRegistry {
+    /// Create an empty registry with no providers
+    ///
+    /// Useful for library consumers that want to register only their own
+    /// `InferenceProvider` implementations.
+    pub fn empty() -> Self {
+        Self {
+            providers: HashMap::new(),
+        }
+    }
+
     /// Create a new registry, attempting to initialize all known providers
     pub fn new() -> Self {
-        let mut registry = Self {
-            providers: HashMap::new(),
-        };
+        let mut registry = Self::empty();
 
         // Specialized AI chip providers
         if let Ok(provider) = CerebrasProvider::from_env() {
-            registry
-                .providers
-                .insert("cerebras".to_string(), Box::new(provider));
+            registry.register("cerebras", Box::new(provider));
         }
 
         if let Ok(provider) = GroqProvider::from_env() {
-            registry
-                .providers
-                .insert("groq".to_string(), Box::new(provider));
+            registry.register("groq", Box::new(provider));
         }
 
         if let Ok(provider) = SambaNovaProvider::from_env() {
-            registry
-                .providers
-                .insert("sambanova".to_string(), Box::new(provider));
+            registry.register("sambanova", Box::new(provider));
         }
 
         // NVIDIA GPU cloud providers
         if let Ok(provider) = FireworksProvider::from_env() {
-            registry
-                .providers
-                .insert("fireworks".to_string(), Box::new(provider));
+            registry.register("fireworks", Box::new(provider));
         }
 
         if let Ok(provider) = TogetherProvider::from_env() {
-            registry
-                .providers
-                .insert("together".to_string(), Box::new(provider));
+            registry.register("together", Box::new(provider));
         }
 
         // Chinese AI providers
         if let Ok(provider) = DeepSeekProvider::from_env() {
-            registry
-                .providers
-                .insert("deepseek".to_string(), Box::new(provider));
+            registry.register("deepseek", Box::new(provider));
         }
 
         if let Ok(provider) = ZaiProvider::from_env() {
-            registry
-                .providers
-                .insert("zai".to_string(), Box::new(provider));
+            registry.register("zai", Box::new(provider));
         }
 
         if let Ok(provider) = MoonshotProvider::from_env() {
-            registry
-                .providers
-                .insert("moonshot".to_string(), Box::new(provider));
+            registry.register("moonshot", Box::new(provider));
+        }
+
+        // Hyperscaler clouds
+        if let Ok(provider) = AzureOpenAIProvider::from_env() {
+            registry.register("azure", Box::new(provider));
+        }
+
+        if let Ok(provider) = VertexProvider::from_env() {
+            registry.register("vertex", Box::new(provider));
         }
 
         // Aggregators
         if let Ok(provider) = OpenRouterProvider::from_env() {
-            registry
-                .providers
-                .insert("openrouter".to_string(), Box::new(provider));
+            registry.register("openrouter", Box::new(provider));
         }
 
         // LiteLLM proxy (unified interface)
         if let Ok(provider) = LiteLLMProvider::from_env() {
-            registry
-                .providers
-                .insert("litellm".to_string(), Box::new(provider));
+            registry.register("litellm", Box::new(provider));
         }
 
         // OpenAI-compatible custom endpoint
         if let Ok(provider) = OpenAICompatibleProvider::from_env() {
-            registry
-                .providers
-                .insert("openai-compatible".to_string(), Box::new(provider));
+            registry.register("openai-compatible", Box::new(provider));
         }
 
         // Local provider (Ollama) - primary instance (M3/default)
         if let Ok(provider) = LocalProvider::detect() {
-            registry
-                .providers
-                .insert("local".to_string(), Box::new(provider));
+            registry.register("local", Box::new(provider));
         }
 
         // Local provider (Ollama) - secondary RTX instance
         if let Ok(provider) = LocalProvider::detect_rtx() {
-            registry
-                .providers
-                .insert("local-rtx".to_string(), Box::new(provider));
+            registry.register("local-rtx", Box::new(provider));
         }
 
         registry
     }
 
+    /// Create a registry of the known providers plus the endpoints defined
+    /// in the config file
+    pub fn with_definitions(definitions: &[ProviderDefinition]) -> Self {
+        let mut registry = Self::new();
+        registry.register_definitions(definitions);
+        registry
+    }
+
+    /// Register each config-defined endpoint under its own name
+    ///
+    /// A definition whose API key variable is unset is skipped with a
+    /// warning.
+    pub fn register_definitions(&mut self, definitions: &[ProviderDefinition]) {
+        for definition in definitions {
+            match OpenAICompatibleProvider::from_definition(definition) {
+                Ok(provider) => self.register(&definition.name, Box::new(provider)),
+                Err(e) => {
+                    tracing::warn!(provider = %definition.name, error = %e, "skipping provider")
+                }
+            }
+        }
+    }
+
+    /// Status of every known provider, configured or not
+    ///
+    /// Built-in providers come first in [`BUILTIN_PROVIDERS`] order, then
+    /// `definitions` in order, then any other registered providers by name.
+    /// An unconfigured definition still reports its model and pricing from
+    /// the config file.
+    pub fn statuses(&self, definitions: &[ProviderDefinition]) -> Vec<ProviderStatus> {
+        let mut names: Vec<&str> = BUILTIN_PROVIDERS.to_vec();
+        names.extend(definitions.iter().map(|d| d.name.as_str()));
+        let mut others: Vec<&str> = self
+            .available()
+            .into_iter()
+            .filter(|name| !names.contains(name))
+            .collect();
+        others.sort_unstable();
+        names.extend(others);
+
+        names
+            .into_iter()
+            .map(|name| {
+                if let Some(provider) = self.get(name) {
+                    let (input, output) = provider.pricing_per_million();
+                    return ProviderStatus {
+                        name: name.to_string(),
+                        display_name: Some(provider.display_name().to_string()),
+                        default_model: Some(provider.default_model().to_string()),
+                        input_per_million: Some(input),
+                        output_per_million: Some(output),
+                        configured: true,
+                    };
+                }
+                let definition = definitions.iter().find(|d| d.name == name);
+                ProviderStatus {
+                    name: name.to_string(),
+                    display_name: definition.and_then(|d| d.display_name.clone()),
+                    default_model: definition.and_then(|d| d.default_model.clone()),
+                    input_per_million: definition.map(|d| d.input_per_million),
+                    output_per_million: definition.map(|d| d.output_per_million),
+                    configured: false,
+                }
+            })
+            .collect()
+    }
+
+    /// Register a provider under the given name, replacing any existing entry
+    pub fn register(&mut self, name: &str, provider: Box<dyn InferenceProvider>) {
+        self.providers.insert(name.to_string(), provider);
+    }
+
     /// Get a provider by name
     pub fn get(&self, name: &str) -> Option<&dyn InferenceProvider> {
         self.providers.get(name).map(|p| p.as_ref())
@@ -237,3 +556,196 @@ impl Default for ProviderRegistry {
         Self::new()
     }
 }
+
+/// Groups of providers whose endpoints share a host and port
+///
+/// Layered proxies make it easy to register the same backend twice, e.g. a
+/// LiteLLM proxy also set as `OPENAI_COMPATIBLE_URL`. Each group lists the
+/// `host:port` and the provider names, in input order.
+pub fn shared_endpoints(providers: &[&dyn InferenceProvider]) -> Vec<(String, Vec<String>)> {
+    let mut groups: Vec<(String, Vec<String>)> = Vec::new();
+    for provider in providers {
+        let Some(host) = provider.endpoint().and_then(endpoint_host) else {
+            continue;
+        };
+        match groups.iter_mut().find(|(h, _)| *h == host) {
+            Some((_, names)) => names.push(provider.name().to_string()),
+            None => groups.push((host, vec![provider.name().to_string()])),
+        }
+    }
+    groups.retain(|(_, names)| names.len() > 1);
+    groups
+}
+
+/// `host:port` of a URL, with the scheme's default port filled in
+fn endpoint_host(url: &str) -> Option<String> {
+    let url = reqwest::Url::parse(url).ok()?;
+    let host = url.host_str()?.to_ascii_lowercase();
+    let host = match host.as_str() {
+        "127.0.0.1" | "[::1]" => "localhost".to_string(),
+        _ => host,
+    };
+    Some(format!("{}:{}", host, url.port_or_known_default()?))
+}
+
+/// Names of the providers [`ProviderRegistry::new`] knows about
+pub const BUILTIN_PROVIDERS: &[&str] = &[
+    "cerebras",
+    "groq",
+    "sambanova",
+    "fireworks",
+    "together",
+    "deepseek",
+    "zai",
+    "moonshot",
+    "azure",
+    "vertex",
+    "openrouter",
+    "litellm",
+    "openai-compatible",
+    "local",
+    "local-rtx",
+];
+
+/// Named provider groups, mirroring the sections of [`ProviderRegistry::new`]
+pub const PROVIDER_GROUPS: &[(&str, &[&str])] = &[
+    ("fast-chips", &["cerebras", "groq", "sambanova"]),
+    ("gpu-clouds", &["fireworks", "together"]),
+    ("chinese", &["deepseek", "zai", "moonshot"]),
+    ("hyperscalers", &["azure", "vertex"]),
+    ("aggregators", &["openrouter", "litellm"]),
+    ("local-all", &["local", "local-rtx"]),
+];
+
+/// Provider names a group alias expands to, or `None` if `name` isn't a group
+pub fn provider_group(name: &str) -> Option<Vec<&'static str>> {
+    PROVIDER_GROUPS
+        .iter()
+        .find(|(group, _)| *group == name)
+        .map(|(_, members)| members.to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_provider_group() {
+        assert_eq!(
+            provider_group("fast-chips"),
+            Some(vec!["cerebras", "groq", "sambanova"])
+        );
+        assert_eq!(provider_group("groq"), None);
+
+        // Group names must not shadow provider names
+        for (group, members) in PROVIDER_GROUPS {
+            assert!(!BUILTIN_PROVIDERS.contains(group));
+            assert!(members.iter().all(|m| BUILTIN_PROVIDERS.contains(m)));
+        }
+    }
+
+    #[test]
+    fn test_defined_providers_are_registered() {
+        let definition = |name: &str, api_key_env: Option<&str>| ProviderDefinition {
+            name: name.to_string(),
+            display_name: Some("Acme".to_string()),
+            base_url: "http://localhost:9/v1/".to_string(),
+            api_key_env: api_key_env.map(str::to_string),
+            default_model: Some("acme-large".to_string()),
+            input_per_million: 0.2,
+            output_per_million: 0.6,
+        };
+        let mut registry = ProviderRegistry::empty();
+        registry.register_definitions(&[
+            definition("acme", None),
+            definition("keyless", Some("SPEED_KINGS_TEST_UNSET_KEY")),
+        ]);
+
+        assert_eq!(registry.available(), vec!["acme"]);
+        let acme = registry.get("acme").unwrap();
+        assert_eq!(acme.name(), "acme");
+        assert_eq!(acme.display_name(), "Acme");
+        assert_eq!(acme.default_model(), "acme-large");
+        assert_eq!(acme.endpoint(), Some("http://localhost:9/v1"));
+        assert_eq!(acme.pricing_per_million(), (0.2, 0.6));
+
+        let statuses = registry.statuses(&[
+            definition("acme", None),
+            definition("keyless", Some("SPEED_KINGS_TEST_UNSET_KEY")),
+        ]);
+        let names: Vec<&str> = statuses.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names[..2], ["cerebras", "groq"]);
+        assert_eq!(names[names.len() - 2..], ["acme", "keyless"]);
+        assert!(!statuses[0].configured);
+        assert_eq!(statuses[0].display_name, None);
+
+        let acme = &statuses[names.len() - 2];
+        assert!(acme.configured);
+        assert_eq!(acme.display_name.as_deref(), Some("Acme"));
+        assert_eq!(acme.output_per_million, Some(0.6));
+        // Known from the config file even without its key
+        let keyless = &statuses[names.len() - 1];
+        assert!(!keyless.configured);
+        assert_eq!(keyless.default_model.as_deref(), Some("acme-large"));
+    }
+
+    #[test]
+    fn test_empty_response_is_rejected() {
+        let response = InferenceResponse {
+            text: "Hello".to_string(),
+            output_tokens: 2,
+            ..Default::default()
+        };
+        assert!(response.validate().is_ok());
+
+        let refusal = InferenceResponse {
+            text: " \n".to_string(),
+            output_tokens: 1,
+            ..Default::default()
+        };
+        assert!(matches!(
+            refusal.validate(),
+            Err(ProviderError::EmptyResponse { output_tokens: 1 })
+        ));
+        let no_tokens = InferenceResponse {
+            text: "Hi".to_string(),
+            ..Default::default()
+        };
+        assert!(no_tokens.validate().is_err());
+    }
+
+    #[test]
+    fn test_shared_endpoints() {
+        let provider = |name: &str, url: &str| {
+            OpenAICompatibleProvider::new(
+                url.to_string(),
+                None,
+                "model".to_string(),
+                name.to_string(),
+                CompletionMode::Chat,
+            )
+            .unwrap()
+        };
+        let proxy = provider("proxy", "http://localhost:4000/v1");
+        let alias = provider("alias", "http://127.0.0.1:4000/v1/chat/completions");
+        let other_port = provider("ollama", "http://localhost:11434");
+        let hosted = provider("hosted", "https://api.example.com/v1");
+        let hosted_again = provider("hosted-2", "https://API.example.com:443/openai");
+
+        let groups = shared_endpoints(&[&proxy, &other_port, &hosted, &alias, &hosted_again]);
+        assert_eq!(
+            groups,
+            vec![
+                (
+                    "localhost:4000".to_string(),
+                    vec!["proxy".to_string(), "alias".to_string()]
+                ),
+                (
+                    "api.example.com:443".to_string(),
+                    vec!["hosted".to_string(), "hosted-2".to_string()]
+                ),
+            ]
+        );
+        assert!(shared_endpoints(&[&proxy, &other_port]).is_empty());
+    }
+}
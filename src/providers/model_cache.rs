@@ -0,0 +1,142 @@
+//! On-disk cache of models discovered from provider `/models` endpoints.
+//!
+//! Entries live in `models.json` under the speed-kings config directory,
+//! keyed by provider id and base URL, and expire after [`CACHE_TTL_SECS`].
+//! `--refresh-models` discards the file so the next lookup re-queries.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Cache file name within the speed-kings config directory
+pub const MODELS_CACHE_FILE: &str = "models.json";
+
+/// Age after which a cached model list is ignored and re-fetched
+pub const CACHE_TTL_SECS: u64 = 24 * 60 * 60;
+
+/// Discovered model lists, keyed by [`cache_key`]
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ModelCache {
+    entries: HashMap<String, CachedModels>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CachedModels {
+    /// Unix time (seconds) the list was fetched
+    fetched_at: u64,
+    models: Vec<String>,
+}
+
+impl ModelCache {
+    /// Load a cache file; a missing or unreadable file yields an empty cache
+    pub fn load(path: &Path) -> Self {
+        std::fs::read(path)
+            .ok()
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .unwrap_or_default()
+    }
+
+    /// Write the cache, creating parent directories as needed
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let json = serde_json::to_vec_pretty(self).map_err(std::io::Error::other)?;
+        std::fs::write(path, json)
+    }
+
+    /// Models cached under `key`, unless the entry is older than the TTL
+    pub fn fresh(&self, key: &str, now: u64) -> Option<&[String]> {
+        self.entries
+            .get(key)
+            .filter(|entry| now.saturating_sub(entry.fetched_at) < CACHE_TTL_SECS)
+            .map(|entry| entry.models.as_slice())
+    }
+
+    /// Record a freshly fetched model list, dropping any expired entries
+    pub fn insert(&mut self, key: String, models: Vec<String>, now: u64) {
+        self.entries
+            .retain(|_, entry| now.saturating_sub(entry.fetched_at) < CACHE_TTL_SECS);
+        self.entries.insert(
+            key,
+            CachedModels {
+                fetched_at: now,
+                models,
+            },
+        );
+    }
+}
+
+/// Cache key for a provider endpoint
+pub fn cache_key(provider: &str, base_url: &str) -> String {
+    format!("{}@{}", provider, base_url)
+}
+
+/// Location of `models.json`, if a config directory can be determined
+pub fn cache_path() -> Option<PathBuf> {
+    crate::config::config_dir().map(|dir| dir.join("speed-kings").join(MODELS_CACHE_FILE))
+}
+
+/// Current Unix time in seconds
+pub fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+/// Cached models for a provider endpoint, if present and fresh
+pub fn lookup(provider: &str, base_url: &str) -> Option<Vec<String>> {
+    let path = cache_path()?;
+    ModelCache::load(&path)
+        .fresh(&cache_key(provider, base_url), now_secs())
+        .map(<[String]>::to_vec)
+}
+
+/// Store a discovered model list; failures are logged and otherwise ignored
+pub fn store(provider: &str, base_url: &str, models: Vec<String>) {
+    let Some(path) = cache_path() else {
+        return;
+    };
+    let mut cache = ModelCache::load(&path);
+    cache.insert(cache_key(provider, base_url), models, now_secs());
+    if let Err(e) = cache.save(&path) {
+        tracing::warn!(path = %path.display(), error = %e, "failed to write model cache");
+    }
+}
+
+/// Delete the cache file so models are re-discovered (`--refresh-models`)
+pub fn clear() -> std::io::Result<()> {
+    match cache_path() {
+        Some(path) => match std::fs::remove_file(path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        },
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_entries_expire_after_ttl_and_survive_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("nested").join(MODELS_CACHE_FILE);
+        let key = cache_key("openai-compatible", "http://localhost:8000/v1");
+
+        let mut cache = ModelCache::default();
+        cache.insert(key.clone(), vec!["m1".to_string(), "m2".to_string()], 1_000);
+        cache.save(&path).unwrap();
+
+        let cache = ModelCache::load(&path);
+        assert_eq!(cache.fresh(&key, 1_000 + 60).unwrap(), ["m1", "m2"]);
+        assert!(cache.fresh(&key, 1_000 + CACHE_TTL_SECS).is_none());
+        assert!(cache.fresh("other@url", 1_000).is_none());
+
+        // Corrupt files are treated as empty rather than failing discovery
+        std::fs::write(&path, b"not json").unwrap();
+        assert!(ModelCache::load(&path).fresh(&key, 1_000).is_none());
+    }
+}
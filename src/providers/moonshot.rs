@@ -2,64 +2,37 @@
 //!
 //! Moonshot offers Kimi models including Kimi K2 and K2.5.
 
-use super::{InferenceProvider, InferenceRequest, InferenceResponse, ProviderError};
+use super::openai_stream::{self, AuthHint, ChatRequest};
+use super::{InferenceProvider, InferenceRequest, InferenceResponse, ProviderError, http};
+use crate::pricing::model_pricing;
 use async_trait::async_trait;
-use futures::StreamExt;
 use reqwest::Client;
-use serde::{Deserialize, Serialize};
-use std::time::{Duration, Instant};
+use std::time::Instant;
 
-const MOONSHOT_API_URL: &str = "https://api.moonshot.cn/v1/chat/completions";
+const DEFAULT_BASE_URL: &str = "https://api.moonshot.cn/v1";
 const DEFAULT_MODEL: &str = "kimi-k2.5";
 const TIMEOUT_SECS: u64 = 120;
+const AUTH: AuthHint = AuthHint {
+    provider: "moonshot",
+    env_var: "MOONSHOT_API_KEY",
+};
 
 /// Moonshot AI inference provider - Kimi models
 pub struct MoonshotProvider {
     client: Client,
+    base_url: String,
     api_key: String,
     model: String,
 }
 
-#[derive(Serialize)]
-struct ChatRequest {
-    model: String,
-    messages: Vec<Message>,
-    max_tokens: u32,
-    stream: bool,
-}
-
-#[derive(Serialize)]
-struct Message {
-    role: String,
-    content: String,
-}
-
-#[derive(Deserialize)]
-struct StreamChunk {
-    choices: Vec<StreamChoice>,
-    usage: Option<Usage>,
-}
-
-#[derive(Deserialize)]
-struct StreamChoice {
-    delta: Delta,
-    #[serde(rename = "finish_reason")]
-    _finish_reason: Option<String>,
-}
-
-#[derive(Deserialize)]
-struct Delta {
-    content: Option<String>,
-}
-
-#[derive(Deserialize)]
-struct Usage {
-    prompt_tokens: u32,
-    completion_tokens: u32,
-}
-
 impl MoonshotProvider {
     /// Create a new Moonshot provider from environment variables
+    ///
+    /// Environment variables:
+    /// - MOONSHOT_API_KEY: API key (required)
+    /// - MOONSHOT_BASE_URL: API base URL, e.g. a regional endpoint (default:
+    ///   https://api.moonshot.cn/v1)
+    /// - MOONSHOT_MODEL: Model to use (default: kimi-k2.5)
     pub fn from_env() -> Result<Self, ProviderError> {
         let api_key = std::env::var("MOONSHOT_API_KEY").map_err(|_| {
             ProviderError::NotConfigured(
@@ -69,13 +42,12 @@ impl MoonshotProvider {
 
         let model = std::env::var("MOONSHOT_MODEL").unwrap_or_else(|_| DEFAULT_MODEL.to_string());
 
-        let client = Client::builder()
-            .timeout(Duration::from_secs(TIMEOUT_SECS))
-            .build()
-            .map_err(|e| ProviderError::Network(e.to_string()))?;
+        let base_url = http::base_url("MOONSHOT_BASE_URL", DEFAULT_BASE_URL);
+        let client = http::build_client(TIMEOUT_SECS)?;
 
         Ok(Self {
             client,
+            base_url,
             api_key,
             model,
         })
@@ -101,114 +73,50 @@ impl InferenceProvider for MoonshotProvider {
 
         let model = request.model.clone().unwrap_or_else(|| self.model.clone());
 
-        let chat_request = ChatRequest {
-            model,
-            messages: vec![Message {
-                role: "user".to_string(),
-                content: request.prompt.clone(),
-            }],
-            max_tokens: request.max_tokens,
-            stream: true,
-        };
-
-        let response = self
+        let chat_request = ChatRequest::new(model, request);
+
+        let http_request = self
             .client
-            .post(MOONSHOT_API_URL)
+            .post(format!("{}/chat/completions", self.base_url))
             .header("Authorization", format!("Bearer {}", self.api_key))
             .header("Content-Type", "application/json")
-            .json(&chat_request)
-            .send()
-            .await
-            .map_err(|e| {
-                if e.is_timeout() {
-                    ProviderError::Timeout(TIMEOUT_SECS * 1000)
-                } else if e.is_connect() {
-                    ProviderError::Network(e.to_string())
-                } else {
-                    ProviderError::ApiError(e.to_string())
-                }
-            })?;
-
-        let time_to_prompt_ms = start.elapsed().as_millis() as u64;
-
-        if response.status() == 429 {
-            return Err(ProviderError::RateLimited);
-        }
-
-        if !response.status().is_success() {
-            let status = response.status();
-            let body = response.text().await.unwrap_or_default();
-            return Err(ProviderError::ApiError(format!(
-                "HTTP {}: {}",
-                status, body
-            )));
-        }
-
-        let mut stream = response.bytes_stream();
-        let mut first_token_time: Option<Duration> = None;
-        let mut output_text = String::new();
-        let mut input_tokens = 0u32;
-        let mut output_tokens = 0u32;
-        let mut buffer = String::new();
-
-        while let Some(chunk_result) = stream.next().await {
-            let chunk = chunk_result.map_err(|e| ProviderError::Network(e.to_string()))?;
-
-            if first_token_time.is_none() && !chunk.is_empty() {
-                first_token_time = Some(start.elapsed());
-            }
-
-            let chunk_str = String::from_utf8_lossy(&chunk);
-            buffer.push_str(&chunk_str);
-
-            while let Some(line_end) = buffer.find('\n') {
-                let line = buffer[..line_end].trim().to_string();
-                buffer = buffer[line_end + 1..].to_string();
-
-                if let Some(data) = line.strip_prefix("data: ") {
-                    if data == "[DONE]" {
-                        continue;
-                    }
-
-                    if let Ok(chunk_data) = serde_json::from_str::<StreamChunk>(data) {
-                        for choice in chunk_data.choices {
-                            if let Some(content) = choice.delta.content {
-                                output_text.push_str(&content);
-                            }
-                        }
-                        if let Some(usage) = chunk_data.usage {
-                            input_tokens = usage.prompt_tokens;
-                            output_tokens = usage.completion_tokens;
-                        }
-                    }
-                }
-            }
-        }
-
-        let total_latency_ms = start.elapsed().as_millis() as u64;
-        let ttft_ms = first_token_time
-            .map(|t| t.as_millis() as u64)
-            .unwrap_or(total_latency_ms);
-
-        let time_to_first_token_ms = ttft_ms.saturating_sub(time_to_prompt_ms);
-
-        Ok(InferenceResponse {
-            text: output_text,
-            input_tokens,
-            output_tokens,
-            time_to_prompt_ms,
-            time_to_first_token_ms,
-            total_latency_ms,
-            model_load_time_ms: None,
-        })
+            .json(&chat_request);
+
+        openai_stream::stream_chat(
+            http_request,
+            start,
+            TIMEOUT_SECS,
+            AUTH,
+            request.first_token_timeout_ms,
+            request.stall_timeout_ms,
+        )
+        .await
     }
 
     fn default_model(&self) -> &str {
         &self.model
     }
 
-    fn pricing_per_million(&self) -> (f64, f64) {
-        // Moonshot Kimi K2.5 pricing (estimated)
-        (0.20, 0.20)
+    fn endpoint(&self) -> Option<&str> {
+        Some(&self.base_url)
+    }
+
+    fn supports_non_streaming(&self) -> bool {
+        true
+    }
+
+    fn supports_tools(&self) -> bool {
+        true
+    }
+
+    fn supports_history(&self) -> bool {
+        true
+    }
+
+    fn pricing_for_model(&self, model: &str) -> (f64, f64) {
+        // Unlisted MOONSHOT_MODEL overrides are estimated at the default model's rates
+        model_pricing("moonshot", model)
+            .or_else(|| model_pricing("moonshot", DEFAULT_MODEL))
+            .unwrap_or((0.60, 3.00))
     }
 }
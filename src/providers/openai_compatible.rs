@@ -2,61 +2,142 @@
 //!
 //! This provider works with any API that implements the OpenAI chat completions
 //! interface, including local servers like vLLM, text-generation-inference, etc.
-
-use super::{InferenceProvider, InferenceRequest, InferenceResponse, ProviderError};
+//! Servers (or base models) that only expose the legacy `/completions` endpoint
+//! are supported via `OPENAI_COMPATIBLE_MODE=completions`. Embeddings are
+//! benchmarked against the `/embeddings` endpoint. Without a configured model,
+//! the first model listed by `/models` is used; the listing is cached on disk
+//! for a day (see [`super::model_cache`]).
+//!
+//! Further endpoints can be defined in the config file as `[[provider]]`
+//! entries (see [`ProviderDefinition`]), each registered under its own name.
+
+use super::embeddings::{self, EmbeddingsBody};
+use super::model_cache;
+use super::openai_stream::{self, AuthHint, ChatRequest, StreamOptions};
+use super::{
+    EmbeddingRequest, EmbeddingResponse, InferenceProvider, InferenceRequest, InferenceResponse,
+    ModelInfo, ProviderError, http,
+};
+use crate::pricing::model_pricing;
 use async_trait::async_trait;
-use futures::StreamExt;
-use reqwest::Client;
+use reqwest::{Client, RequestBuilder};
 use serde::{Deserialize, Serialize};
-use std::time::{Duration, Instant};
+use std::time::Instant;
+use tokio::sync::OnceCell;
 
 const DEFAULT_TIMEOUT_SECS: u64 = 120;
+/// Reported before a model has been discovered from `/models`
+const UNRESOLVED_MODEL: &str = "default";
+const AUTH: AuthHint = AuthHint {
+    provider: "openai-compatible",
+    env_var: "OPENAI_COMPATIBLE_KEY",
+};
+
+/// Which OpenAI-style endpoint (and request shape) to use
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CompletionMode {
+    /// `/chat/completions` with a `messages` array
+    #[default]
+    Chat,
+    /// Legacy `/completions` with a raw `prompt` field
+    Completions,
+}
+
+impl CompletionMode {
+    /// Parse a mode name ("chat" or "completions")
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "chat" => Some(CompletionMode::Chat),
+            "completions" => Some(CompletionMode::Completions),
+            _ => None,
+        }
+    }
+
+    /// Endpoint path relative to the base URL
+    fn path(&self) -> &'static str {
+        match self {
+            CompletionMode::Chat => "chat/completions",
+            CompletionMode::Completions => "completions",
+        }
+    }
+}
 
 /// OpenAI-compatible API provider for custom endpoints
 pub struct OpenAICompatibleProvider {
     client: Client,
     base_url: String,
     api_key: Option<String>,
-    model: String,
+    /// Configured model, or None to use the first model the server lists
+    model: Option<String>,
+    /// Model discovered from `/models` when none is configured
+    discovered_model: OnceCell<String>,
+    /// Configured embedding model (defaults to the chat model)
+    embedding_model: Option<String>,
     name: String,
+    display_name: String,
+    /// Environment variable the API key came from, named in auth failures
+    api_key_env: Option<String>,
+    /// USD per million (input, output) tokens unless a pricing file has rates
+    pricing: (f64, f64),
+    mode: CompletionMode,
 }
 
-#[derive(Serialize)]
-struct ChatRequest {
-    model: String,
-    messages: Vec<Message>,
-    max_tokens: u32,
-    stream: bool,
-}
-
-#[derive(Serialize)]
-struct Message {
-    role: String,
-    content: String,
-}
-
-#[derive(Deserialize)]
-struct StreamChunk {
-    choices: Vec<StreamChoice>,
-    usage: Option<Usage>,
+/// OpenAI-compatible endpoint defined in the config file
+///
+/// ```toml
+/// [[provider]]
+/// name = "acme"
+/// base_url = "https://api.acme.ai/v1"
+/// api_key_env = "ACME_API_KEY"
+/// default_model = "acme-large"
+/// input_per_million = 0.20
+/// output_per_million = 0.60
+/// ```
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProviderDefinition {
+    /// Name to select it with `--providers`
+    pub name: String,
+    /// Name shown in results (defaults to `name`)
+    pub display_name: Option<String>,
+    /// Base URL the `/chat/completions` path is appended to
+    pub base_url: String,
+    /// Environment variable holding the API key; without one no key is sent
+    pub api_key_env: Option<String>,
+    /// Model to benchmark (defaults to the first listed by `/models`)
+    pub default_model: Option<String>,
+    /// USD per 1M input tokens
+    #[serde(default)]
+    pub input_per_million: f64,
+    /// USD per 1M output tokens
+    #[serde(default)]
+    pub output_per_million: f64,
 }
 
 #[derive(Deserialize)]
-struct StreamChoice {
-    delta: Delta,
-    #[serde(rename = "finish_reason")]
-    _finish_reason: Option<String>,
+struct ModelsResponse {
+    data: Vec<ModelEntry>,
 }
 
 #[derive(Deserialize)]
-struct Delta {
-    content: Option<String>,
+struct ModelEntry {
+    id: String,
 }
 
-#[derive(Deserialize)]
-struct Usage {
-    prompt_tokens: u32,
-    completion_tokens: u32,
+#[derive(Serialize)]
+struct CompletionRequest {
+    model: String,
+    prompt: String,
+    max_tokens: u32,
+    stream: bool,
+    /// vLLM extension: minimum tokens before EOS is honored
+    #[serde(skip_serializing_if = "Option::is_none")]
+    min_tokens: Option<u32>,
+    /// vLLM extension: keep generating past EOS up to `max_tokens`
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    ignore_eos: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stream_options: Option<StreamOptions>,
 }
 
 impl OpenAICompatibleProvider {
@@ -65,7 +146,9 @@ impl OpenAICompatibleProvider {
     /// Environment variables:
     /// - OPENAI_COMPATIBLE_URL: Base URL (e.g., http://localhost:8000/v1)
     /// - OPENAI_COMPATIBLE_KEY: Optional API key
-    /// - OPENAI_COMPATIBLE_MODEL: Model name (default: "default")
+    /// - OPENAI_COMPATIBLE_MODEL: Model name (default: first model from `/models`)
+    /// - OPENAI_COMPATIBLE_MODE: "chat" or "completions" (default: "chat")
+    /// - OPENAI_COMPATIBLE_EMBEDDING_MODEL: Embedding model (default: the chat model)
     pub fn from_env() -> Result<Self, ProviderError> {
         let base_url = std::env::var("OPENAI_COMPATIBLE_URL").map_err(|_| {
             ProviderError::NotConfigured(
@@ -74,20 +157,65 @@ impl OpenAICompatibleProvider {
         })?;
 
         let api_key = std::env::var("OPENAI_COMPATIBLE_KEY").ok();
-        let model =
-            std::env::var("OPENAI_COMPATIBLE_MODEL").unwrap_or_else(|_| "default".to_string());
+        let model = std::env::var("OPENAI_COMPATIBLE_MODEL").ok();
+        let mode = match std::env::var("OPENAI_COMPATIBLE_MODE") {
+            Ok(value) => CompletionMode::parse(&value).ok_or_else(|| {
+                ProviderError::NotConfigured(format!(
+                    "Invalid OPENAI_COMPATIBLE_MODE '{}' (expected chat or completions)",
+                    value
+                ))
+            })?,
+            Err(_) => CompletionMode::Chat,
+        };
+        let embedding_model = std::env::var("OPENAI_COMPATIBLE_EMBEDDING_MODEL").ok();
 
-        let client = Client::builder()
-            .timeout(Duration::from_secs(DEFAULT_TIMEOUT_SECS))
-            .build()
-            .map_err(|e| ProviderError::Network(e.to_string()))?;
+        let client = http::build_client(DEFAULT_TIMEOUT_SECS)?;
 
         Ok(Self {
             client,
             base_url,
             api_key,
             model,
+            discovered_model: OnceCell::new(),
+            embedding_model,
             name: "openai-compatible".to_string(),
+            display_name: "OpenAI Compatible".to_string(),
+            api_key_env: Some(AUTH.env_var.to_string()),
+            pricing: (0.0, 0.0),
+            mode,
+        })
+    }
+
+    /// Create a provider from a config file definition
+    ///
+    /// Fails with [`ProviderError::NotConfigured`] if the definition names
+    /// an API key variable that is not set.
+    pub fn from_definition(definition: &ProviderDefinition) -> Result<Self, ProviderError> {
+        let api_key = match &definition.api_key_env {
+            Some(var) => Some(std::env::var(var).map_err(|_| {
+                ProviderError::NotConfigured(format!(
+                    "{} environment variable not set for provider {}",
+                    var, definition.name
+                ))
+            })?),
+            None => None,
+        };
+
+        Ok(Self {
+            client: http::build_client(DEFAULT_TIMEOUT_SECS)?,
+            base_url: definition.base_url.trim_end_matches('/').to_string(),
+            api_key,
+            model: definition.default_model.clone(),
+            discovered_model: OnceCell::new(),
+            embedding_model: None,
+            name: definition.name.clone(),
+            display_name: definition
+                .display_name
+                .clone()
+                .unwrap_or_else(|| definition.name.clone()),
+            api_key_env: definition.api_key_env.clone(),
+            pricing: (definition.input_per_million, definition.output_per_million),
+            mode: CompletionMode::Chat,
         })
     }
 
@@ -97,20 +225,104 @@ impl OpenAICompatibleProvider {
         api_key: Option<String>,
         model: String,
         name: String,
+        mode: CompletionMode,
     ) -> Result<Self, ProviderError> {
-        let client = Client::builder()
-            .timeout(Duration::from_secs(DEFAULT_TIMEOUT_SECS))
-            .build()
-            .map_err(|e| ProviderError::Network(e.to_string()))?;
+        let client = http::build_client(DEFAULT_TIMEOUT_SECS)?;
 
         Ok(Self {
             client,
             base_url,
             api_key,
-            model,
+            model: Some(model),
+            discovered_model: OnceCell::new(),
+            embedding_model: None,
+            display_name: "OpenAI Compatible".to_string(),
             name,
+            api_key_env: None,
+            pricing: (0.0, 0.0),
+            mode,
         })
     }
+
+    /// Attribute an auth failure reported by the shared helpers to this
+    /// provider and its key variable
+    fn auth_failure(&self, error: ProviderError) -> ProviderError {
+        match error {
+            ProviderError::InvalidApiKey { .. } => ProviderError::InvalidApiKey {
+                provider: self.name.clone(),
+                env_var: self
+                    .api_key_env
+                    .clone()
+                    .unwrap_or_else(|| AUTH.env_var.to_string()),
+            },
+            other => other,
+        }
+    }
+
+    /// Add the API key header, if one is configured
+    fn authorize(&self, request: RequestBuilder) -> RequestBuilder {
+        match &self.api_key {
+            Some(key) => request.header("Authorization", format!("Bearer {}", key)),
+            None => request,
+        }
+    }
+
+    /// Model to use: the configured one, else the first listed by `/models`
+    /// (queried once and cached)
+    async fn resolve_model(&self) -> Result<&str, ProviderError> {
+        if let Some(model) = &self.model {
+            return Ok(model);
+        }
+        self.discovered_model
+            .get_or_try_init(|| self.fetch_first_model())
+            .await
+            .map(String::as_str)
+    }
+
+    async fn fetch_first_model(&self) -> Result<String, ProviderError> {
+        let models = self.fetch_models().await?;
+        let model = models.first().cloned().ok_or_else(|| {
+            ProviderError::NotConfigured(format!(
+                "{}/models lists no models; set OPENAI_COMPATIBLE_MODEL",
+                self.base_url
+            ))
+        })?;
+        tracing::info!(provider = %self.name, model = %model, "using first listed model");
+        Ok(model)
+    }
+
+    /// Model ids listed by `/models`, from the on-disk cache when fresh
+    async fn fetch_models(&self) -> Result<Vec<String>, ProviderError> {
+        if let Some(models) =
+            model_cache::lookup(&self.name, &self.base_url).filter(|models| !models.is_empty())
+        {
+            tracing::debug!(provider = %self.name, "using cached model list");
+            return Ok(models);
+        }
+
+        let url = format!("{}/models", self.base_url);
+        let response = http::send(self.authorize(self.client.get(&url)))
+            .await
+            .map_err(|e| openai_stream::map_send_error(e, DEFAULT_TIMEOUT_SECS))?;
+        let response = openai_stream::check_status(response, AUTH)
+            .await
+            .map_err(|e| self.auth_failure(e))?;
+        let body = response
+            .bytes()
+            .await
+            .map_err(|e| ProviderError::Network(e.to_string()))?;
+
+        let models = model_ids(&body)?;
+        model_cache::store(&self.name, &self.base_url, models.clone());
+        Ok(models)
+    }
+}
+
+/// Model ids in an OpenAI-style `/models` response, in listed order
+fn model_ids(body: &[u8]) -> Result<Vec<String>, ProviderError> {
+    let models: ModelsResponse =
+        serde_json::from_slice(body).map_err(|e| ProviderError::ParseError(e.to_string()))?;
+    Ok(models.data.into_iter().map(|m| m.id).collect())
 }
 
 #[async_trait]
@@ -120,137 +332,228 @@ impl InferenceProvider for OpenAICompatibleProvider {
     }
 
     fn display_name(&self) -> &str {
-        "OpenAI Compatible"
+        &self.display_name
     }
 
     async fn is_available(&self) -> bool {
-        // Try to reach the endpoint
-        let url = format!("{}/models", self.base_url);
-        let mut request = self.client.get(&url);
-
-        if let Some(ref key) = self.api_key {
-            request = request.header("Authorization", format!("Bearer {}", key));
+        // Discovering the model doubles as a reachability check
+        if self.model.is_none() {
+            return self.resolve_model().await.is_ok();
         }
 
-        request.send().await.is_ok()
+        // Try to reach the endpoint
+        let url = format!("{}/models", self.base_url);
+        http::send(self.authorize(self.client.get(&url)))
+            .await
+            .is_ok()
     }
 
     async fn infer(&self, request: &InferenceRequest) -> Result<InferenceResponse, ProviderError> {
         let start = Instant::now();
 
-        let model = request.model.clone().unwrap_or_else(|| self.model.clone());
-
-        let chat_request = ChatRequest {
-            model,
-            messages: vec![Message {
-                role: "user".to_string(),
-                content: request.prompt.clone(),
-            }],
-            max_tokens: request.max_tokens,
-            stream: true,
+        let model = match &request.model {
+            Some(model) => model.clone(),
+            None => self.resolve_model().await?.to_string(),
         };
 
-        let url = format!("{}/chat/completions", self.base_url);
+        let url = format!("{}/{}", self.base_url, self.mode.path());
         let mut http_request = self
             .client
             .post(&url)
-            .header("Content-Type", "application/json")
-            .json(&chat_request);
+            .header("Content-Type", "application/json");
+
+        http_request = match self.mode {
+            CompletionMode::Chat => {
+                let mut chat_request = ChatRequest::new(model, request);
+                chat_request.min_tokens = request.min_tokens;
+                chat_request.ignore_eos = request.min_tokens.is_some();
+                chat_request.stream_options = StreamOptions::usage(request.stream);
+                http_request.json(&chat_request)
+            }
+            CompletionMode::Completions => http_request.json(&CompletionRequest {
+                model,
+                prompt: request.prompt.clone(),
+                max_tokens: request.max_tokens,
+                stream: request.stream,
+                min_tokens: request.min_tokens,
+                ignore_eos: request.min_tokens.is_some(),
+                stream_options: StreamOptions::usage(request.stream),
+            }),
+        };
 
-        if let Some(ref key) = self.api_key {
-            http_request = http_request.header("Authorization", format!("Bearer {}", key));
+        http_request = self.authorize(http_request);
+        // OpenAI deduplicates requests carrying the same key, so a retry is
+        // not billed twice
+        if let Some(key) = &request.idempotency_key {
+            http_request = http_request.header("Idempotency-Key", key);
         }
 
-        let response = http_request.send().await.map_err(|e| {
-            if e.is_timeout() {
-                ProviderError::Timeout(DEFAULT_TIMEOUT_SECS * 1000)
-            } else if e.is_connect() {
-                ProviderError::Network(e.to_string())
-            } else {
-                ProviderError::ApiError(e.to_string())
-            }
-        })?;
+        let mut response = openai_stream::stream_chat(
+            http_request,
+            start,
+            DEFAULT_TIMEOUT_SECS,
+            AUTH,
+            request.first_token_timeout_ms,
+            request.stall_timeout_ms,
+        )
+        .await
+        .map_err(|e| self.auth_failure(e))?;
+        response.idempotent = request.idempotency_key.is_some();
+        Ok(response)
+    }
 
-        let time_to_prompt_ms = start.elapsed().as_millis() as u64;
+    fn default_model(&self) -> &str {
+        self.model
+            .as_deref()
+            .or_else(|| self.discovered_model.get().map(String::as_str))
+            .unwrap_or(UNRESOLVED_MODEL)
+    }
 
-        if response.status() == 429 {
-            return Err(ProviderError::RateLimited);
-        }
+    fn endpoint(&self) -> Option<&str> {
+        Some(&self.base_url)
+    }
 
-        if !response.status().is_success() {
-            let status = response.status();
-            let body = response.text().await.unwrap_or_default();
-            return Err(ProviderError::ApiError(format!(
-                "HTTP {}: {}",
-                status, body
-            )));
-        }
+    async fn list_models(&self) -> Result<Vec<ModelInfo>, ProviderError> {
+        // Resolve the default first so it can be marked in the listing
+        self.resolve_model().await?;
+        let models = self.fetch_models().await?;
+        Ok(models
+            .into_iter()
+            .map(|id| ModelInfo {
+                id,
+                upstreams: Vec::new(),
+            })
+            .collect())
+    }
+
+    fn supports_non_streaming(&self) -> bool {
+        true
+    }
 
-        let mut stream = response.bytes_stream();
-        let mut first_token_time: Option<Duration> = None;
-        let mut output_text = String::new();
-        let mut input_tokens = 0u32;
-        let mut output_tokens = 0u32;
-        let mut buffer = String::new();
+    fn supports_tools(&self) -> bool {
+        true
+    }
 
-        while let Some(chunk_result) = stream.next().await {
-            let chunk = chunk_result.map_err(|e| ProviderError::Network(e.to_string()))?;
+    fn supports_history(&self) -> bool {
+        // The completions endpoint takes one prompt string
+        self.mode == CompletionMode::Chat
+    }
 
-            if first_token_time.is_none() && !chunk.is_empty() {
-                first_token_time = Some(start.elapsed());
-            }
+    fn embedding_model(&self) -> Option<&str> {
+        Some(
+            self.embedding_model
+                .as_deref()
+                .unwrap_or_else(|| self.default_model()),
+        )
+    }
 
-            let chunk_str = String::from_utf8_lossy(&chunk);
-            buffer.push_str(&chunk_str);
-
-            while let Some(line_end) = buffer.find('\n') {
-                let line = buffer[..line_end].trim().to_string();
-                buffer = buffer[line_end + 1..].to_string();
-
-                if let Some(data) = line.strip_prefix("data: ") {
-                    if data == "[DONE]" {
-                        continue;
-                    }
-
-                    if let Ok(chunk_data) = serde_json::from_str::<StreamChunk>(data) {
-                        for choice in chunk_data.choices {
-                            if let Some(content) = choice.delta.content {
-                                output_text.push_str(&content);
-                            }
-                        }
-                        if let Some(usage) = chunk_data.usage {
-                            input_tokens = usage.prompt_tokens;
-                            output_tokens = usage.completion_tokens;
-                        }
-                    }
-                }
-            }
+    async fn embed(&self, request: &EmbeddingRequest) -> Result<EmbeddingResponse, ProviderError> {
+        let start = Instant::now();
+
+        let model = match request.model.as_ref().or(self.embedding_model.as_ref()) {
+            Some(model) => model.clone(),
+            None => self.resolve_model().await?.to_string(),
+        };
+
+        let url = format!("{}/embeddings", self.base_url);
+        let http_request = self.authorize(
+            self.client
+                .post(&url)
+                .header("Content-Type", "application/json")
+                .json(&EmbeddingsBody::new(model, request)),
+        );
+
+        embeddings::send_embeddings(http_request, start, DEFAULT_TIMEOUT_SECS, AUTH)
+            .await
+            .map_err(|e| self.auth_failure(e))
+    }
+
+    fn pricing_for_model(&self, model: &str) -> (f64, f64) {
+        // Free/self-hosted unless the endpoint's definition gives rates
+        model_pricing(&self.name, model).unwrap_or(self.pricing)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::mock_server::{MOCK_API_KEY, MockResponse, MockServer, openai_provider};
+    use std::time::Duration;
+
+    fn request() -> InferenceRequest {
+        InferenceRequest {
+            prompt: "Say hello".to_string(),
+            max_tokens: 64,
+            min_tokens: None,
+            model: None,
+            first_token_timeout_ms: None,
+            stall_timeout_ms: None,
+            idempotency_key: None,
+            stream: true,
+            tools: false,
+            history: Vec::new(),
         }
+    }
 
-        let total_latency_ms = start.elapsed().as_millis() as u64;
-        let ttft_ms = first_token_time
-            .map(|t| t.as_millis() as u64)
-            .unwrap_or(total_latency_ms);
-
-        let time_to_first_token_ms = ttft_ms.saturating_sub(time_to_prompt_ms);
-
-        Ok(InferenceResponse {
-            text: output_text,
-            input_tokens,
-            output_tokens,
-            time_to_prompt_ms,
-            time_to_first_token_ms,
-            total_latency_ms,
-            model_load_time_ms: None,
-        })
+    #[tokio::test]
+    async fn test_infer_against_mock_server() {
+        let server = MockServer::start(vec![
+            MockResponse::sse(&[
+                r#"{"choices":[{"delta":{"content":"Hello"}}]}"#,
+                r#"{"choices":[{"delta":{"content":" there"}}]}"#,
+                r#"{"choices":[],"usage":{"prompt_tokens":9,"completion_tokens":2}}"#,
+                "[DONE]",
+            ])
+            .first_chunk_delay(Duration::from_millis(150)),
+        ])
+        .await;
+
+        let response = openai_provider(&server).infer(&request()).await.unwrap();
+
+        assert_eq!(response.text, "Hello there");
+        assert_eq!((response.input_tokens, response.output_tokens), (9, 2));
+        assert!(response.time_to_first_token_ms >= 150);
+        assert!(response.total_latency_ms >= response.time_to_first_token_ms);
+
+        let requests = server.requests();
+        assert!(requests[0].starts_with("POST /v1/chat/completions "));
+        assert!(requests[0].contains(&format!("authorization: Bearer {}", MOCK_API_KEY)));
+        assert!(requests[0].contains(r#""model":"mock-model""#));
+        assert!(requests[0].contains(r#""max_tokens":64"#));
+        assert!(requests[0].contains(r#""stream_options":{"include_usage":true}"#));
+        assert!(requests[0].contains(&format!("content-length: {}", response.request_bytes)));
+        // Four `data: ...\n\n` events
+        assert_eq!(response.response_bytes, 51 + 52 + 72 + 14);
     }
 
-    fn default_model(&self) -> &str {
-        &self.model
+    #[tokio::test]
+    async fn test_error_statuses_map_to_provider_errors() {
+        let server = MockServer::start(vec![
+            MockResponse::status(429, "slow down").header("Retry-After", "2"),
+            MockResponse::status(401, "bad key"),
+            MockResponse::status(500, "boom"),
+        ])
+        .await;
+        let provider = openai_provider(&server);
+
+        let errors = [
+            provider.infer(&request()).await.unwrap_err(),
+            provider.infer(&request()).await.unwrap_err(),
+            provider.infer(&request()).await.unwrap_err(),
+        ];
+
+        assert!(matches!(errors[0], ProviderError::RateLimited));
+        assert!(matches!(errors[1], ProviderError::InvalidApiKey { .. }));
+        assert!(matches!(&errors[2], ProviderError::ApiError(msg) if msg.contains("boom")));
     }
 
-    fn pricing_per_million(&self) -> (f64, f64) {
-        // Custom endpoints - assume free/self-hosted
-        (0.0, 0.0)
+    #[test]
+    fn test_model_ids() {
+        let body = br#"{"object":"list","data":[{"id":"Qwen/Qwen2.5-7B-Instruct","object":"model"},{"id":"other"}]}"#;
+        assert_eq!(
+            model_ids(body).unwrap(),
+            ["Qwen/Qwen2.5-7B-Instruct", "other"]
+        );
+        assert!(model_ids(br#"{"data":[]}"#).unwrap().is_empty());
     }
 }
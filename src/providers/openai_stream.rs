@@ -0,0 +1,858 @@
+//! Shared request and SSE streaming support for OpenAI-style chat APIs.
+//!
+//! Most providers speak the OpenAI chat completions protocol, differing only in
+//! URL, auth header, and a few optional request fields. They build the HTTP
+//! request themselves and hand it to [`stream_chat`] for timing and parsing.
+//! Non-streaming requests get a single JSON body back, which is parsed too.
+
+use super::{ChatMessage, InferenceRequest, InferenceResponse, ProviderError, ServerTiming, http};
+use crate::benchmark::estimate_tokens;
+use futures::{Stream, StreamExt};
+use reqwest::{Client, RequestBuilder, Response, StatusCode};
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+/// OpenAI-style chat completion request body
+#[derive(Serialize)]
+pub(super) struct ChatRequest {
+    pub model: String,
+    pub messages: Vec<ChatMessage>,
+    pub max_tokens: u32,
+    pub stream: bool,
+    /// Minimum tokens before EOS is honored (vLLM, Fireworks)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_tokens: Option<u32>,
+    /// Keep generating past EOS up to `max_tokens` (vLLM)
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub ignore_eos: bool,
+    /// Ask for a final `usage` chunk; see [`StreamOptions::usage`]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stream_options: Option<StreamOptions>,
+    /// Functions the model may call; see [`Tool::weather`]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tools: Option<Vec<Tool>>,
+}
+
+/// Streaming options of the OpenAI protocol
+#[derive(Serialize)]
+pub(super) struct StreamOptions {
+    pub include_usage: bool,
+}
+
+impl StreamOptions {
+    /// Request token usage in the last chunk, which servers such as vLLM and
+    /// LiteLLM otherwise never send (token counts are then estimated)
+    ///
+    /// Only set for providers known to accept the field; some reject
+    /// unknown request fields. None for non-streaming requests, which always
+    /// report usage and may not carry stream options.
+    pub fn usage(stream: bool) -> Option<Self> {
+        stream.then_some(Self {
+            include_usage: true,
+        })
+    }
+}
+
+/// Function definition offered to the model
+#[derive(Serialize)]
+pub(super) struct Tool {
+    #[serde(rename = "type")]
+    pub kind: &'static str,
+    pub function: FunctionDefinition,
+}
+
+#[derive(Serialize)]
+pub(super) struct FunctionDefinition {
+    pub name: &'static str,
+    pub description: &'static str,
+    /// JSON Schema of the arguments
+    pub parameters: serde_json::Value,
+}
+
+impl Tool {
+    /// Weather lookup that the built-in tool prompt asks the model to call
+    pub fn weather() -> Self {
+        Self {
+            kind: "function",
+            function: FunctionDefinition {
+                name: "get_weather",
+                description: "Get the current weather for a city",
+                parameters: serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "city": { "type": "string", "description": "City name" },
+                        "unit": { "type": "string", "enum": ["celsius", "fahrenheit"] }
+                    },
+                    "required": ["city"]
+                }),
+            },
+        }
+    }
+}
+
+impl ChatRequest {
+    /// Build a request for the given model, with the prompt following any
+    /// history, streamed unless the request turns streaming off, offering a
+    /// tool if it asks for one
+    pub fn new(model: String, request: &InferenceRequest) -> Self {
+        let mut messages = request.history.clone();
+        messages.push(ChatMessage {
+            role: "user".to_string(),
+            content: request.prompt.clone(),
+        });
+        Self {
+            model,
+            messages,
+            max_tokens: request.max_tokens,
+            stream: request.stream,
+            min_tokens: None,
+            ignore_eos: false,
+            stream_options: None,
+            tools: request.tools.then(|| vec![Tool::weather()]),
+        }
+    }
+}
+
+/// Identifies a provider's credential so auth failures can name it
+#[derive(Debug, Clone, Copy)]
+pub(super) struct AuthHint {
+    pub provider: &'static str,
+    pub env_var: &'static str,
+}
+
+#[derive(Deserialize)]
+struct StreamChunk {
+    choices: Vec<StreamChoice>,
+    usage: Option<Usage>,
+    /// Groq's extension object; its last chunk carries the usage here
+    x_groq: Option<GroqExtension>,
+}
+
+#[derive(Deserialize)]
+struct GroqExtension {
+    usage: Option<Usage>,
+}
+
+#[derive(Deserialize)]
+struct StreamChoice {
+    /// Present in `/chat/completions` chunks
+    delta: Option<Delta>,
+    /// Present in legacy `/completions` chunks
+    text: Option<String>,
+    /// `"length"` when generation stopped at `max_tokens`
+    finish_reason: Option<String>,
+}
+
+/// Non-streaming chat (or legacy completions) response body
+#[derive(Deserialize)]
+struct Completion {
+    choices: Vec<CompletionChoice>,
+    usage: Option<Usage>,
+}
+
+#[derive(Deserialize)]
+struct CompletionChoice {
+    /// Present in `/chat/completions` responses; shaped like a stream delta
+    message: Option<Delta>,
+    /// Present in legacy `/completions` responses
+    text: Option<String>,
+    finish_reason: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Delta {
+    content: Option<String>,
+    /// Thinking output of reasoning models (DeepSeek, vLLM; OpenRouter
+    /// calls it `reasoning`)
+    #[serde(alias = "reasoning")]
+    reasoning_content: Option<String>,
+    /// Function calls; streamed arguments arrive in fragments
+    tool_calls: Option<Vec<ToolCall>>,
+}
+
+#[derive(Deserialize)]
+struct ToolCall {
+    function: Option<FunctionCall>,
+}
+
+#[derive(Deserialize)]
+struct FunctionCall {
+    /// JSON-encoded arguments (or a fragment of them)
+    arguments: Option<String>,
+}
+
+/// Argument text of a delta's (or message's) tool calls, if it has any
+fn tool_call_arguments(tool_calls: Option<Vec<ToolCall>>) -> Option<String> {
+    let calls = tool_calls.filter(|calls| !calls.is_empty())?;
+    Some(
+        calls
+            .into_iter()
+            .filter_map(|call| call.function?.arguments)
+            .collect(),
+    )
+}
+
+#[derive(Deserialize)]
+struct Usage {
+    prompt_tokens: u32,
+    /// Includes reasoning tokens
+    completion_tokens: u32,
+    completion_tokens_details: Option<CompletionTokensDetails>,
+    /// Seconds the request waited before processing began (Groq)
+    queue_time: Option<f64>,
+    /// Seconds spent processing the prompt (Groq)
+    prompt_time: Option<f64>,
+    /// Seconds spent generating the completion (Groq)
+    completion_time: Option<f64>,
+    /// Seconds from queueing to the end of generation (Groq)
+    total_time: Option<f64>,
+}
+
+impl Usage {
+    fn queue_time_ms(&self) -> Option<u64> {
+        self.queue_time.map(secs_to_ms)
+    }
+
+    /// Server-side timing, if the prompt and completion times are reported
+    fn server_timing(&self) -> Option<ServerTiming> {
+        let (prompt, completion) = (self.prompt_time?, self.completion_time?);
+        let total = self
+            .total_time
+            .unwrap_or(self.queue_time.unwrap_or(0.0) + prompt + completion);
+        Some(ServerTiming {
+            prompt_ms: secs_to_ms(prompt),
+            completion_ms: secs_to_ms(completion),
+            total_ms: secs_to_ms(total),
+        })
+    }
+}
+
+fn secs_to_ms(secs: f64) -> u64 {
+    (secs * 1000.0).round() as u64
+}
+
+#[derive(Deserialize)]
+struct CompletionTokensDetails {
+    #[serde(default)]
+    reasoning_tokens: u32,
+}
+
+/// Upper bound on an availability probe, independent of the inference timeout
+const AVAILABILITY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Check that an authenticated GET (typically `/models`) succeeds
+///
+/// A rejected key, non-2xx status, or network failure all count as
+/// unavailable, so bad credentials are caught before any iteration runs.
+pub(super) async fn check_available(client: &Client, url: &str, api_key: &str) -> bool {
+    let request = client
+        .get(url)
+        .header("Authorization", format!("Bearer {}", api_key));
+
+    probe_status(request)
+        .await
+        .is_some_and(|status| status.is_success())
+}
+
+/// Send an availability probe, returning its status or None on a network
+/// failure
+pub(super) async fn probe_status(request: RequestBuilder) -> Option<StatusCode> {
+    match http::send(request.timeout(AVAILABILITY_TIMEOUT)).await {
+        Ok(response) => Some(response.status()),
+        Err(e) => {
+            tracing::debug!(error = %e, "availability check failed");
+            None
+        }
+    }
+}
+
+/// Send a streaming request and parse the SSE response
+///
+/// `start` should be taken before the request body is built so that
+/// `time_to_prompt_ms` covers serialization and upload.
+pub(super) async fn stream_chat(
+    request: RequestBuilder,
+    start: Instant,
+    timeout_secs: u64,
+    auth: AuthHint,
+    first_token_timeout_ms: Option<u64>,
+    stall_timeout_ms: Option<u64>,
+) -> Result<InferenceResponse, ProviderError> {
+    let request_bytes = http::body_len(&request);
+    let response = before_first_token(http::send(request), start, first_token_timeout_ms)
+        .await?
+        .map_err(|e| map_send_error(e, timeout_secs))?;
+
+    let time_to_prompt_ms = start.elapsed().as_millis() as u64;
+    let response = check_status(response, auth).await?;
+
+    let mut result = read_stream(
+        response,
+        start,
+        time_to_prompt_ms,
+        first_token_timeout_ms,
+        stall_timeout_ms,
+    )
+    .await?;
+    result.request_bytes = request_bytes;
+    Ok(result)
+}
+
+/// Await a step that must complete before the first token deadline
+///
+/// The deadline is `first_token_timeout_ms` after `start`; without one the
+/// step is simply awaited.
+pub(super) async fn before_first_token<T>(
+    step: impl Future<Output = T>,
+    start: Instant,
+    first_token_timeout_ms: Option<u64>,
+) -> Result<T, ProviderError> {
+    let Some(limit_ms) = first_token_timeout_ms else {
+        return Ok(step.await);
+    };
+    let deadline = tokio::time::Instant::from_std(start + Duration::from_millis(limit_ms));
+    tokio::time::timeout_at(deadline, step)
+        .await
+        .map_err(|_| ProviderError::FirstTokenTimeout(limit_ms))
+}
+
+/// Await the next chunk of a stream that has already started
+///
+/// Fails with [`ProviderError::StreamStalled`] if nothing arrives within
+/// `stall_timeout_ms`; without one the chunk is simply awaited.
+pub(super) async fn next_chunk<S: Stream + Unpin>(
+    stream: &mut S,
+    stall_timeout_ms: Option<u64>,
+) -> Result<Option<S::Item>, ProviderError> {
+    let Some(limit_ms) = stall_timeout_ms else {
+        return Ok(stream.next().await);
+    };
+    tokio::time::timeout(Duration::from_millis(limit_ms), stream.next())
+        .await
+        .map_err(|_| ProviderError::StreamStalled(limit_ms))
+}
+
+/// Map a `reqwest` send failure to a provider error
+pub(super) fn map_send_error(e: reqwest::Error, timeout_secs: u64) -> ProviderError {
+    if e.is_timeout() {
+        ProviderError::Timeout(http::request_timeout(timeout_secs).as_millis() as u64)
+    } else if e.is_connect() {
+        ProviderError::Network(e.to_string())
+    } else {
+        ProviderError::ApiError(e.to_string())
+    }
+}
+
+/// Turn rate limiting, auth failures, and non-2xx statuses into provider errors
+pub(super) async fn check_status(
+    response: Response,
+    auth: AuthHint,
+) -> Result<Response, ProviderError> {
+    if response.status() == 429 {
+        return Err(ProviderError::RateLimited);
+    }
+
+    if response.status() == 401 || response.status() == 403 {
+        return Err(ProviderError::InvalidApiKey {
+            provider: auth.provider.to_string(),
+            env_var: auth.env_var.to_string(),
+        });
+    }
+
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        return Err(ProviderError::ApiError(format!(
+            "HTTP {}: {}",
+            status,
+            http::redact(&body)
+        )));
+    }
+
+    Ok(response)
+}
+
+/// Consume an SSE body, measuring time to first token and collecting output
+///
+/// A JSON body (the reply to a non-streaming request) is read whole instead.
+pub(super) async fn read_stream(
+    response: Response,
+    start: Instant,
+    time_to_prompt_ms: u64,
+    first_token_timeout_ms: Option<u64>,
+    stall_timeout_ms: Option<u64>,
+) -> Result<InferenceResponse, ProviderError> {
+    if is_json(&response) {
+        return read_completion(response, start, time_to_prompt_ms, first_token_timeout_ms).await;
+    }
+
+    // Stream the response to measure TTFT accurately
+    let mut stream = response.bytes_stream();
+    let mut first_token_time: Option<Duration> = None;
+    let mut output_text = String::new();
+    let mut reasoning_text = String::new();
+    let mut input_tokens = 0u32;
+    let mut completion_tokens = 0u32;
+    let mut reported_reasoning_tokens = None;
+    let mut queue_time_ms = None;
+    let mut server_timing = None;
+    let mut buffer = String::new();
+    let mut chunk_token_counts = Vec::new();
+    let mut truncated = false;
+    let mut tool_call = false;
+    let mut response_bytes = 0u64;
+
+    loop {
+        let next = if first_token_time.is_none() {
+            before_first_token(stream.next(), start, first_token_timeout_ms).await?
+        } else {
+            next_chunk(&mut stream, stall_timeout_ms).await?
+        };
+        let Some(chunk_result) = next else {
+            break;
+        };
+        let chunk = chunk_result.map_err(|e| ProviderError::Network(e.to_string()))?;
+        response_bytes += chunk.len() as u64;
+
+        // Record time to first chunk
+        if first_token_time.is_none() && !chunk.is_empty() {
+            first_token_time = Some(start.elapsed());
+        }
+
+        // Parse SSE data
+        let chunk_str = String::from_utf8_lossy(&chunk);
+        buffer.push_str(&chunk_str);
+
+        // Process complete SSE lines, estimating the tokens in this read
+        let mut tokens = 0u32;
+        while let Some(line_end) = buffer.find('\n') {
+            let line = buffer[..line_end].trim().to_string();
+            buffer = buffer[line_end + 1..].to_string();
+            if !line.is_empty() {
+                tracing::trace!(line = %line, "SSE line");
+            }
+
+            if let Some(data) = line.strip_prefix("data: ") {
+                if data == "[DONE]" {
+                    continue;
+                }
+
+                if let Ok(chunk_data) = serde_json::from_str::<StreamChunk>(data) {
+                    for choice in chunk_data.choices {
+                        truncated |= choice.finish_reason.as_deref() == Some("length");
+                        let (content, reasoning, tool_calls) = match choice.delta {
+                            Some(delta) => {
+                                (delta.content, delta.reasoning_content, delta.tool_calls)
+                            }
+                            None => (choice.text, None, None),
+                        };
+                        if let Some(reasoning) = reasoning.filter(|r| !r.is_empty()) {
+                            tokens += estimate_tokens(&reasoning);
+                            reasoning_text.push_str(&reasoning);
+                        }
+                        if let Some(content) = content.filter(|c| !c.is_empty()) {
+                            tokens += estimate_tokens(&content);
+                            output_text.push_str(&content);
+                        }
+                        if let Some(arguments) = tool_call_arguments(tool_calls) {
+                            tool_call = true;
+                            if !arguments.is_empty() {
+                                tokens += estimate_tokens(&arguments);
+                                output_text.push_str(&arguments);
+                            }
+                        }
+                    }
+                    let usage = chunk_data
+                        .usage
+                        .or(chunk_data.x_groq.and_then(|groq| groq.usage));
+                    if let Some(usage) = usage {
+                        input_tokens = usage.prompt_tokens;
+                        completion_tokens = usage.completion_tokens;
+                        queue_time_ms = usage.queue_time_ms().or(queue_time_ms);
+                        server_timing = usage.server_timing().or(server_timing);
+                        reported_reasoning_tokens =
+                            usage.completion_tokens_details.map(|d| d.reasoning_tokens);
+                    }
+                }
+            }
+        }
+        if tokens > 0 {
+            chunk_token_counts.push(tokens);
+        }
+    }
+
+    let total_latency_ms = start.elapsed().as_millis() as u64;
+    let ttft_ms = first_token_time
+        .map(|t| t.as_millis() as u64)
+        .unwrap_or(total_latency_ms);
+
+    // TTFT is relative to when prompt was sent
+    let time_to_first_token_ms = ttft_ms.saturating_sub(time_to_prompt_ms);
+
+    let (output_tokens, reasoning_tokens) = split_completion_tokens(
+        completion_tokens,
+        reported_reasoning_tokens,
+        &output_text,
+        &reasoning_text,
+    );
+
+    Ok(InferenceResponse {
+        text: output_text,
+        input_tokens,
+        output_tokens,
+        reasoning_tokens,
+        time_to_prompt_ms,
+        time_to_first_token_ms,
+        total_latency_ms,
+        chunk_token_counts,
+        truncated,
+        response_bytes,
+        tool_call,
+        queue_time_ms,
+        server_timing,
+        ..Default::default()
+    })
+}
+
+/// Whether the response is a single JSON body rather than an event stream
+fn is_json(response: &Response) -> bool {
+    response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("application/json"))
+}
+
+/// Read a non-streaming completion
+///
+/// The answer arrives all at once, so the first token is timed at the end
+/// of the body and there are no per-read chunk counts.
+async fn read_completion(
+    response: Response,
+    start: Instant,
+    time_to_prompt_ms: u64,
+    first_token_timeout_ms: Option<u64>,
+) -> Result<InferenceResponse, ProviderError> {
+    let body = before_first_token(response.bytes(), start, first_token_timeout_ms)
+        .await?
+        .map_err(|e| ProviderError::Network(e.to_string()))?;
+    let total_latency_ms = start.elapsed().as_millis() as u64;
+    tracing::trace!(body = %String::from_utf8_lossy(&body), "JSON response");
+
+    let completion: Completion =
+        serde_json::from_slice(&body).map_err(|e| ProviderError::ParseError(e.to_string()))?;
+    let mut output_text = String::new();
+    let mut reasoning_text = String::new();
+    let mut truncated = false;
+    let mut tool_call = false;
+    for choice in completion.choices {
+        truncated |= choice.finish_reason.as_deref() == Some("length");
+        let (content, reasoning, tool_calls) = match choice.message {
+            Some(message) => (
+                message.content,
+                message.reasoning_content,
+                message.tool_calls,
+            ),
+            None => (choice.text, None, None),
+        };
+        reasoning_text.push_str(reasoning.as_deref().unwrap_or_default());
+        output_text.push_str(content.as_deref().unwrap_or_default());
+        if let Some(arguments) = tool_call_arguments(tool_calls) {
+            tool_call = true;
+            output_text.push_str(&arguments);
+        }
+    }
+
+    let queue_time_ms = completion.usage.as_ref().and_then(Usage::queue_time_ms);
+    let server_timing = completion.usage.as_ref().and_then(Usage::server_timing);
+    let (input_tokens, completion_tokens, reported_reasoning_tokens) = match completion.usage {
+        Some(usage) => (
+            usage.prompt_tokens,
+            usage.completion_tokens,
+            usage.completion_tokens_details.map(|d| d.reasoning_tokens),
+        ),
+        None => (0, 0, None),
+    };
+    let (output_tokens, reasoning_tokens) = split_completion_tokens(
+        completion_tokens,
+        reported_reasoning_tokens,
+        &output_text,
+        &reasoning_text,
+    );
+
+    Ok(InferenceResponse {
+        text: output_text,
+        input_tokens,
+        output_tokens,
+        reasoning_tokens,
+        time_to_prompt_ms,
+        time_to_first_token_ms: total_latency_ms.saturating_sub(time_to_prompt_ms),
+        total_latency_ms,
+        truncated,
+        response_bytes: body.len() as u64,
+        tool_call,
+        queue_time_ms,
+        server_timing,
+        ..Default::default()
+    })
+}
+
+/// Split a usage `completion_tokens` count into (answer, reasoning) tokens
+///
+/// Reasoning is taken from the usage details when reported, else estimated
+/// from the streamed reasoning text. Some servers omit usage entirely; both
+/// counts are then estimated from the text.
+fn split_completion_tokens(
+    completion_tokens: u32,
+    reported_reasoning_tokens: Option<u32>,
+    output_text: &str,
+    reasoning_text: &str,
+) -> (u32, u32) {
+    if completion_tokens == 0 {
+        return (
+            estimate_tokens(output_text),
+            estimate_tokens(reasoning_text),
+        );
+    }
+    let reasoning_tokens = reported_reasoning_tokens
+        .unwrap_or_else(|| estimate_tokens(reasoning_text))
+        .min(completion_tokens);
+    (completion_tokens - reasoning_tokens, reasoning_tokens)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::mock_server::{MockResponse, MockServer};
+    use flate2::Compression;
+    use flate2::write::GzEncoder;
+    use std::io::Write;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    const TEST_AUTH: AuthHint = AuthHint {
+        provider: "test",
+        env_var: "TEST_API_KEY",
+    };
+
+    /// Serve one HTTP response with a gzip-compressed SSE body
+    async fn serve_gzipped_sse(body: &str) -> String {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(body.as_bytes()).unwrap();
+        let compressed = encoder.finish().unwrap();
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = vec![0u8; 4096];
+            let _ = socket.read(&mut request).await.unwrap();
+            let header = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nContent-Encoding: gzip\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                compressed.len()
+            );
+            socket.write_all(header.as_bytes()).await.unwrap();
+            socket.write_all(&compressed).await.unwrap();
+        });
+
+        format!("http://{}/v1/chat/completions", addr)
+    }
+
+    #[tokio::test]
+    async fn test_gzipped_sse_stream_is_decoded() {
+        let url = serve_gzipped_sse(concat!(
+            "data: {\"choices\":[{\"delta\":{\"content\":\"Hello\"}}]}\n\n",
+            "data: {\"choices\":[{\"delta\":{\"content\":\" world\"}}]}\n\n",
+            "data: {\"choices\":[],\"usage\":{\"prompt_tokens\":5,\"completion_tokens\":2}}\n\n",
+            "data: [DONE]\n\n",
+        ))
+        .await;
+
+        let client = super::super::http::build_client(5).unwrap();
+        let response = stream_chat(client.post(&url), Instant::now(), 5, TEST_AUTH, None, None)
+            .await
+            .unwrap();
+
+        assert_eq!(response.text, "Hello world");
+        assert_eq!(response.input_tokens, 5);
+        assert_eq!(response.output_tokens, 2);
+    }
+
+    #[tokio::test]
+    async fn test_chunk_token_counts_estimate_tokens_per_read() {
+        // A buffering provider delivers a burst of tokens in a single delta
+        let burst = "Once upon a time there was a provider that buffered";
+        let event = format!(r#"{{"choices":[{{"delta":{{"content":"{}"}}}}]}}"#, burst);
+        let server = MockServer::start(vec![MockResponse::sse(&[&event, "[DONE]"])]).await;
+
+        let url = format!("{}/v1/chat/completions", server.base_url());
+        let client = super::super::http::build_client(5).unwrap();
+        let response = stream_chat(client.post(&url), Instant::now(), 5, TEST_AUTH, None, None)
+            .await
+            .unwrap();
+
+        assert_eq!(response.chunk_token_counts, [estimate_tokens(burst)]);
+        assert!(response.chunk_token_counts[0] > 1);
+    }
+
+    #[tokio::test]
+    async fn test_reasoning_content_is_counted_separately() {
+        let server = MockServer::start(vec![MockResponse::sse(&[
+            r#"{"choices":[{"delta":{"reasoning_content":"Let me think"}}]}"#,
+            r#"{"choices":[{"delta":{"content":"42"}}]}"#,
+            concat!(
+                r#"{"choices":[],"usage":{"prompt_tokens":5,"completion_tokens":30,"#,
+                r#""completion_tokens_details":{"reasoning_tokens":29}}}"#,
+            ),
+            "[DONE]",
+        ])])
+        .await;
+
+        let url = format!("{}/v1/chat/completions", server.base_url());
+        let client = super::super::http::build_client(5).unwrap();
+        let response = stream_chat(client.post(&url), Instant::now(), 5, TEST_AUTH, None, None)
+            .await
+            .unwrap();
+
+        assert_eq!(response.text, "42");
+        assert_eq!(response.output_tokens, 1);
+        assert_eq!(response.reasoning_tokens, 29);
+        assert!(!response.truncated);
+    }
+
+    #[tokio::test]
+    async fn test_length_finish_reason_marks_truncated() {
+        let server = MockServer::start(vec![MockResponse::sse(&[
+            r#"{"choices":[{"delta":{"content":"Once upon"},"finish_reason":null}]}"#,
+            r#"{"choices":[{"delta":{},"finish_reason":"length"}]}"#,
+            "[DONE]",
+        ])])
+        .await;
+
+        let url = format!("{}/v1/chat/completions", server.base_url());
+        let client = super::super::http::build_client(5).unwrap();
+        let response = stream_chat(client.post(&url), Instant::now(), 5, TEST_AUTH, None, None)
+            .await
+            .unwrap();
+
+        assert_eq!(response.text, "Once upon");
+        assert!(response.truncated);
+    }
+
+    #[tokio::test]
+    async fn test_groq_usage_reports_server_timing() {
+        let server = MockServer::start(vec![MockResponse::sse(&[
+            r#"{"choices":[{"delta":{"content":"Hi"}}]}"#,
+            concat!(
+                r#"{"choices":[{"delta":{},"finish_reason":"stop"}],"#,
+                r#""x_groq":{"id":"req_1","usage":{"queue_time":0.0123,"#,
+                r#""prompt_time":0.002,"completion_time":0.01,"total_time":0.0243,"#,
+                r#""prompt_tokens":5,"completion_tokens":1}}}"#,
+            ),
+            "[DONE]",
+        ])])
+        .await;
+
+        let url = format!("{}/v1/chat/completions", server.base_url());
+        let client = super::super::http::build_client(5).unwrap();
+        let response = stream_chat(client.post(&url), Instant::now(), 5, TEST_AUTH, None, None)
+            .await
+            .unwrap();
+
+        assert_eq!(response.queue_time_ms, Some(12));
+        assert_eq!(response.input_tokens, 5);
+        assert_eq!(
+            response.server_timing,
+            Some(ServerTiming {
+                prompt_ms: 2,
+                completion_ms: 10,
+                total_ms: 24,
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_streamed_tool_call_arguments_are_collected() {
+        let server = MockServer::start(vec![MockResponse::sse(&[
+            concat!(
+                r#"{"choices":[{"delta":{"tool_calls":[{"index":0,"id":"call_1","#,
+                r#""type":"function","function":{"name":"get_weather","arguments":""}}]}}]}"#,
+            ),
+            concat!(
+                r#"{"choices":[{"delta":{"tool_calls":[{"index":0,"#,
+                r#""function":{"arguments":"{\"city\":"}}]}}]}"#,
+            ),
+            concat!(
+                r#"{"choices":[{"delta":{"tool_calls":[{"index":0,"#,
+                r#""function":{"arguments":"\"Paris\"}"}}]},"finish_reason":"tool_calls"}]}"#,
+            ),
+            "[DONE]",
+        ])])
+        .await;
+
+        let url = format!("{}/v1/chat/completions", server.base_url());
+        let client = super::super::http::build_client(5).unwrap();
+        let response = stream_chat(client.post(&url), Instant::now(), 5, TEST_AUTH, None, None)
+            .await
+            .unwrap();
+
+        assert!(response.tool_call);
+        assert_eq!(response.text, "{\"city\":\"Paris\"}");
+        assert!(!response.truncated);
+    }
+
+    #[tokio::test]
+    async fn test_headers_without_first_chunk_hit_first_token_timeout() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = vec![0u8; 4096];
+            let _ = socket.read(&mut request).await.unwrap();
+            socket
+                .write_all(b"HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nTransfer-Encoding: chunked\r\n\r\n")
+                .await
+                .unwrap();
+            // Hold the connection open without sending any body
+            tokio::time::sleep(Duration::from_secs(5)).await;
+        });
+
+        let url = format!("http://{}/v1/chat/completions", addr);
+        let client = super::super::http::build_client(5).unwrap();
+        let result = stream_chat(
+            client.post(&url),
+            Instant::now(),
+            5,
+            TEST_AUTH,
+            Some(200),
+            None,
+        )
+        .await;
+
+        assert!(matches!(result, Err(ProviderError::FirstTokenTimeout(200))));
+    }
+
+    #[tokio::test]
+    async fn test_stalled_stream_is_aborted() {
+        // The first token arrives, then the server goes quiet without closing
+        let server = MockServer::start(vec![
+            MockResponse::sse(&[r#"{"choices":[{"delta":{"content":"Hello"}}]}"#])
+                .stall_after_first_chunk(),
+        ])
+        .await;
+
+        let url = format!("{}/v1/chat/completions", server.base_url());
+        let client = super::super::http::build_client(5).unwrap();
+        let result = stream_chat(
+            client.post(&url),
+            Instant::now(),
+            5,
+            TEST_AUTH,
+            Some(1000),
+            Some(200),
+        )
+        .await;
+
+        assert!(matches!(result, Err(ProviderError::StreamStalled(200))));
+    }
+}
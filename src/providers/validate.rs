@@ -0,0 +1,118 @@
+//! Credential and reachability checks for the `validate` subcommand.
+
+use super::{InferenceProvider, InferenceRequest, ProviderError, http};
+use std::time::Instant;
+
+/// Prompt for the validation request; only the status matters
+const VALIDATION_PROMPT: &str = "Reply with OK.";
+
+/// Outcome of validating one provider
+#[derive(Debug, Clone, PartialEq)]
+pub enum ValidationStatus {
+    /// The credentials were accepted and a completion came back
+    Ok { latency_ms: u64 },
+    /// The provider rejected the API key
+    BadKey(String),
+    /// The provider could not be reached or did not answer in time
+    Unreachable(String),
+    /// The key may be fine, but the request failed for another reason
+    Failed(String),
+}
+
+impl ValidationStatus {
+    pub fn is_ok(&self) -> bool {
+        matches!(self, ValidationStatus::Ok { .. })
+    }
+}
+
+impl std::fmt::Display for ValidationStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ValidationStatus::Ok { latency_ms } => write!(f, "OK ({}ms)", latency_ms),
+            ValidationStatus::BadKey(e) => write!(f, "bad key: {}", e),
+            ValidationStatus::Unreachable(e) => write!(f, "unreachable: {}", e),
+            ValidationStatus::Failed(e) => write!(f, "failed: {}", e),
+        }
+    }
+}
+
+/// Check a provider's credentials with a 1-token completion
+///
+/// Unlike [`InferenceProvider::is_available`], which only reports a bool,
+/// this tells a rejected key apart from an unreachable endpoint. The request
+/// costs at most a few tokens.
+pub async fn validate_provider(provider: &dyn InferenceProvider) -> ValidationStatus {
+    let request = InferenceRequest {
+        prompt: VALIDATION_PROMPT.to_string(),
+        max_tokens: 1,
+        min_tokens: None,
+        model: None,
+        first_token_timeout_ms: None,
+        stall_timeout_ms: None,
+        idempotency_key: None,
+        stream: true,
+        tools: false,
+        history: Vec::new(),
+    };
+
+    let start = Instant::now();
+    match provider.infer(&request).await {
+        Ok(_) => ValidationStatus::Ok {
+            latency_ms: start.elapsed().as_millis() as u64,
+        },
+        Err(e) => classify(e),
+    }
+}
+
+fn classify(error: ProviderError) -> ValidationStatus {
+    let message = http::redact(&error.to_string());
+    match error {
+        ProviderError::InvalidApiKey { .. } | ProviderError::NotConfigured(_) => {
+            ValidationStatus::BadKey(message)
+        }
+        // Some providers surface auth failures as plain HTTP errors
+        ProviderError::ApiError(ref body)
+            if body.starts_with("HTTP 401") || body.starts_with("HTTP 403") =>
+        {
+            ValidationStatus::BadKey(message)
+        }
+        ProviderError::Network(_)
+        | ProviderError::Timeout(_)
+        | ProviderError::FirstTokenTimeout(_)
+        | ProviderError::StreamStalled(_) => ValidationStatus::Unreachable(message),
+        _ => ValidationStatus::Failed(message),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_errors_are_classified() {
+        let bad_key = classify(ProviderError::InvalidApiKey {
+            provider: "groq".to_string(),
+            env_var: "GROQ_API_KEY".to_string(),
+        });
+        assert!(matches!(bad_key, ValidationStatus::BadKey(_)));
+        assert!(matches!(
+            classify(ProviderError::ApiError(
+                "HTTP 403 Forbidden: denied".to_string()
+            )),
+            ValidationStatus::BadKey(_)
+        ));
+        assert!(matches!(
+            classify(ProviderError::Network("connection refused".to_string())),
+            ValidationStatus::Unreachable(_)
+        ));
+        assert!(matches!(
+            classify(ProviderError::RateLimited),
+            ValidationStatus::Failed(_)
+        ));
+        assert!(!ValidationStatus::Failed("x".to_string()).is_ok());
+        assert_eq!(
+            ValidationStatus::Ok { latency_ms: 12 }.to_string(),
+            "OK (12ms)"
+        );
+    }
+}
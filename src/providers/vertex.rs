@@ -0,0 +1,581 @@
+//! Google Cloud Vertex AI provider implementation.
+//!
+//! Vertex serves the Gemini models behind GCP IAM rather than an API key.
+//! Access tokens come from, in order: `VERTEX_ACCESS_TOKEN`, the service
+//! account file named by `GOOGLE_APPLICATION_CREDENTIALS`, or the GCE/GKE
+//! metadata server.
+
+use super::openai_stream::{self, AuthHint};
+use super::{InferenceProvider, InferenceRequest, InferenceResponse, ProviderError, http};
+use crate::benchmark::estimate_tokens;
+use crate::pricing::model_pricing;
+use async_trait::async_trait;
+use futures::StreamExt;
+use jsonwebtoken::{Algorithm, EncodingKey, Header};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tokio::sync::Mutex;
+
+const DEFAULT_REGION: &str = "us-central1";
+const DEFAULT_MODEL: &str = "gemini-2.0-flash-001";
+const DEFAULT_PRICING: (f64, f64) = (0.15, 0.60);
+const TIMEOUT_SECS: u64 = 120;
+const AUTH: AuthHint = AuthHint {
+    provider: "vertex",
+    env_var: "GOOGLE_APPLICATION_CREDENTIALS",
+};
+
+const CLOUD_PLATFORM_SCOPE: &str = "https://www.googleapis.com/auth/cloud-platform";
+const DEFAULT_TOKEN_URI: &str = "https://oauth2.googleapis.com/token";
+const METADATA_TOKEN_URL: &str =
+    "http://metadata.google.internal/computeMetadata/v1/instance/service-accounts/default/token";
+
+/// Refresh cached tokens this long before they expire
+const TOKEN_EXPIRY_MARGIN: Duration = Duration::from_secs(60);
+
+/// Vertex AI inference provider - Gemini models on Google Cloud
+pub struct VertexProvider {
+    client: Client,
+    /// Regional API host, e.g. `https://us-central1-aiplatform.googleapis.com`
+    base_url: String,
+    project: String,
+    region: String,
+    model: String,
+    credentials: Credentials,
+    token: Mutex<Option<CachedToken>>,
+}
+
+/// Where access tokens are obtained from
+enum Credentials {
+    /// Pre-minted token, used as-is
+    Static(String),
+    /// Service account key exchanged for tokens via a signed JWT
+    ServiceAccount(ServiceAccountKey),
+    /// GCE/GKE metadata server, at this token URL
+    Metadata(String),
+}
+
+/// Fields used from a service account JSON key file
+#[derive(Deserialize)]
+struct ServiceAccountKey {
+    client_email: String,
+    private_key: String,
+    #[serde(default)]
+    token_uri: Option<String>,
+}
+
+struct CachedToken {
+    access_token: String,
+    expires_at: Instant,
+}
+
+#[derive(Serialize)]
+struct JwtClaims<'a> {
+    iss: &'a str,
+    scope: &'a str,
+    aud: &'a str,
+    iat: u64,
+    exp: u64,
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    expires_in: u64,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct GenerateRequest {
+    contents: Vec<Content>,
+    generation_config: GenerationConfig,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Content {
+    #[serde(default)]
+    role: Option<String>,
+    #[serde(default)]
+    parts: Vec<Part>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Part {
+    #[serde(default)]
+    text: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct GenerationConfig {
+    max_output_tokens: u32,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GenerateChunk {
+    #[serde(default)]
+    candidates: Vec<Candidate>,
+    usage_metadata: Option<UsageMetadata>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Candidate {
+    content: Option<Content>,
+    /// `"MAX_TOKENS"` when generation stopped at `maxOutputTokens`
+    finish_reason: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct UsageMetadata {
+    #[serde(default)]
+    prompt_token_count: u32,
+    #[serde(default)]
+    candidates_token_count: u32,
+    /// Thinking tokens, not included in `candidates_token_count`
+    #[serde(default)]
+    thoughts_token_count: u32,
+}
+
+impl VertexProvider {
+    /// Create a new Vertex AI provider from environment variables
+    ///
+    /// Environment variables:
+    /// - VERTEX_PROJECT: GCP project ID
+    /// - VERTEX_REGION: Region (default: us-central1)
+    /// - VERTEX_MODEL: Model to use (default: gemini-2.0-flash-001)
+    /// - VERTEX_ACCESS_TOKEN: Optional pre-minted access token
+    /// - GOOGLE_APPLICATION_CREDENTIALS: Optional service account key file
+    pub fn from_env() -> Result<Self, ProviderError> {
+        let project = std::env::var("VERTEX_PROJECT").map_err(|_| {
+            ProviderError::NotConfigured("VERTEX_PROJECT environment variable not set".to_string())
+        })?;
+        let region = std::env::var("VERTEX_REGION").unwrap_or_else(|_| DEFAULT_REGION.to_string());
+        let model = std::env::var("VERTEX_MODEL").unwrap_or_else(|_| DEFAULT_MODEL.to_string());
+
+        let credentials = if let Ok(token) = std::env::var("VERTEX_ACCESS_TOKEN") {
+            Credentials::Static(token)
+        } else if let Ok(path) = std::env::var("GOOGLE_APPLICATION_CREDENTIALS") {
+            let contents = std::fs::read_to_string(&path).map_err(|e| {
+                ProviderError::NotConfigured(format!("Cannot read {}: {}", path, e))
+            })?;
+            let key = serde_json::from_str(&contents).map_err(|e| {
+                ProviderError::NotConfigured(format!(
+                    "Invalid service account file {}: {}",
+                    path, e
+                ))
+            })?;
+            Credentials::ServiceAccount(key)
+        } else {
+            Credentials::Metadata(METADATA_TOKEN_URL.to_string())
+        };
+
+        let client = http::build_client(TIMEOUT_SECS)?;
+
+        Ok(Self {
+            client,
+            base_url: format!("https://{}-aiplatform.googleapis.com", region),
+            project,
+            region,
+            model,
+            credentials,
+            token: Mutex::new(None),
+        })
+    }
+
+    /// Streaming generation URL for a model
+    fn stream_url(&self, model: &str) -> String {
+        format!(
+            "{base_url}/v1/projects/{project}/locations/{region}/publishers/google/models/{model}:streamGenerateContent?alt=sse",
+            base_url = self.base_url,
+            region = self.region,
+            project = self.project,
+            model = model,
+        )
+    }
+
+    /// Return a valid access token, refreshing the cached one if needed
+    async fn access_token(&self) -> Result<String, ProviderError> {
+        let credentials = match &self.credentials {
+            Credentials::Static(token) => return Ok(token.clone()),
+            credentials => credentials,
+        };
+
+        let mut cached = self.token.lock().await;
+        if let Some(token) = cached.as_ref()
+            && token.expires_at > Instant::now() + TOKEN_EXPIRY_MARGIN
+        {
+            return Ok(token.access_token.clone());
+        }
+
+        let response = match credentials {
+            Credentials::ServiceAccount(key) => self.fetch_service_account_token(key).await?,
+            Credentials::Metadata(url) => self.fetch_metadata_token(url).await?,
+            // Returned above without caching
+            Credentials::Static(_) => unreachable!(),
+        };
+
+        let access_token = response.access_token.clone();
+        *cached = Some(CachedToken {
+            access_token: response.access_token,
+            expires_at: Instant::now() + Duration::from_secs(response.expires_in),
+        });
+        Ok(access_token)
+    }
+
+    /// Exchange a self-signed JWT for an access token
+    async fn fetch_service_account_token(
+        &self,
+        key: &ServiceAccountKey,
+    ) -> Result<TokenResponse, ProviderError> {
+        let token_uri = key.token_uri.as_deref().unwrap_or(DEFAULT_TOKEN_URI);
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let claims = JwtClaims {
+            iss: &key.client_email,
+            scope: CLOUD_PLATFORM_SCOPE,
+            aud: token_uri,
+            iat: now,
+            exp: now + 3600,
+        };
+
+        let signing_key = EncodingKey::from_rsa_pem(key.private_key.as_bytes())
+            .map_err(|e| ProviderError::NotConfigured(format!("Invalid private key: {}", e)))?;
+        let assertion = jsonwebtoken::encode(&Header::new(Algorithm::RS256), &claims, &signing_key)
+            .map_err(|e| ProviderError::NotConfigured(format!("Failed to sign JWT: {}", e)))?;
+
+        let response = http::send(self.client.post(token_uri).form(&[
+            ("grant_type", "urn:ietf:params:oauth:grant-type:jwt-bearer"),
+            ("assertion", assertion.as_str()),
+        ]))
+        .await
+        .map_err(|e| openai_stream::map_send_error(e, TIMEOUT_SECS))?;
+
+        parse_token_response(response).await
+    }
+
+    /// Ask the metadata server for the attached service account's token
+    async fn fetch_metadata_token(&self, url: &str) -> Result<TokenResponse, ProviderError> {
+        let response = http::send(
+            self.client
+                .get(url)
+                .header("Metadata-Flavor", "Google")
+                .timeout(Duration::from_secs(5)),
+        )
+        .await
+        .map_err(|e| {
+            ProviderError::NotConfigured(format!(
+                "No Vertex credentials: set GOOGLE_APPLICATION_CREDENTIALS or run on GCP ({})",
+                e
+            ))
+        })?;
+
+        parse_token_response(response).await
+    }
+}
+
+async fn parse_token_response(response: reqwest::Response) -> Result<TokenResponse, ProviderError> {
+    let response = openai_stream::check_status(response, AUTH).await?;
+    response
+        .json()
+        .await
+        .map_err(|e| ProviderError::ParseError(format!("Invalid token response: {}", e)))
+}
+
+#[async_trait]
+impl InferenceProvider for VertexProvider {
+    fn name(&self) -> &str {
+        "vertex"
+    }
+
+    fn display_name(&self) -> &str {
+        "Vertex AI"
+    }
+
+    async fn is_available(&self) -> bool {
+        self.access_token().await.is_ok()
+    }
+
+    async fn infer(&self, request: &InferenceRequest) -> Result<InferenceResponse, ProviderError> {
+        // Token refresh is auth overhead, not inference latency
+        let token = self.access_token().await?;
+
+        let start = Instant::now();
+
+        let model = request.model.as_deref().unwrap_or(&self.model);
+        let generate_request = GenerateRequest {
+            contents: vec![Content {
+                role: Some("user".to_string()),
+                parts: vec![Part {
+                    text: Some(request.prompt.clone()),
+                }],
+            }],
+            generation_config: GenerationConfig {
+                max_output_tokens: request.max_tokens,
+            },
+        };
+
+        let http_request = self
+            .client
+            .post(self.stream_url(model))
+            .bearer_auth(&token)
+            .json(&generate_request);
+        let request_bytes = http::body_len(&http_request);
+        let send = http::send(http_request);
+        let response =
+            openai_stream::before_first_token(send, start, request.first_token_timeout_ms)
+                .await?
+                .map_err(|e| openai_stream::map_send_error(e, TIMEOUT_SECS))?;
+
+        let time_to_prompt_ms = start.elapsed().as_millis() as u64;
+        let response = openai_stream::check_status(response, AUTH).await?;
+
+        // Stream the response to measure TTFT accurately
+        let mut stream = response.bytes_stream();
+        let mut first_token_time: Option<Duration> = None;
+        let mut output_text = String::new();
+        let mut input_tokens = 0u32;
+        let mut output_tokens = 0u32;
+        let mut reasoning_tokens = 0u32;
+        let mut buffer = String::new();
+        let mut chunk_token_counts = Vec::new();
+        let mut truncated = false;
+        let mut response_bytes = 0u64;
+
+        loop {
+            let next = if first_token_time.is_none() {
+                openai_stream::before_first_token(
+                    stream.next(),
+                    start,
+                    request.first_token_timeout_ms,
+                )
+                .await?
+            } else {
+                openai_stream::next_chunk(&mut stream, request.stall_timeout_ms).await?
+            };
+            let Some(chunk_result) = next else {
+                break;
+            };
+            let chunk = chunk_result.map_err(|e| ProviderError::Network(e.to_string()))?;
+            response_bytes += chunk.len() as u64;
+
+            if first_token_time.is_none() && !chunk.is_empty() {
+                first_token_time = Some(start.elapsed());
+            }
+
+            buffer.push_str(&String::from_utf8_lossy(&chunk));
+
+            // Estimated tokens of text in this read
+            let mut tokens = 0u32;
+            while let Some(line_end) = buffer.find('\n') {
+                let line = buffer[..line_end].trim().to_string();
+                buffer = buffer[line_end + 1..].to_string();
+                if !line.is_empty() {
+                    tracing::trace!(line = %line, "SSE line");
+                }
+
+                let Some(data) = line.strip_prefix("data: ") else {
+                    continue;
+                };
+                if let Ok(chunk_data) = serde_json::from_str::<GenerateChunk>(data) {
+                    for candidate in chunk_data.candidates {
+                        truncated |= candidate.finish_reason.as_deref() == Some("MAX_TOKENS");
+                        for part in candidate.content.map(|c| c.parts).unwrap_or_default() {
+                            if let Some(text) = part.text.filter(|t| !t.is_empty()) {
+                                tokens += estimate_tokens(&text);
+                                output_text.push_str(&text);
+                            }
+                        }
+                    }
+                    // Usage is cumulative; the last chunk carries the totals
+                    if let Some(usage) = chunk_data.usage_metadata {
+                        input_tokens = usage.prompt_token_count;
+                        output_tokens = usage.candidates_token_count;
+                        reasoning_tokens = usage.thoughts_token_count;
+                    }
+                }
+            }
+            if tokens > 0 {
+                chunk_token_counts.push(tokens);
+            }
+        }
+
+        let total_latency_ms = start.elapsed().as_millis() as u64;
+        let ttft_ms = first_token_time
+            .map(|t| t.as_millis() as u64)
+            .unwrap_or(total_latency_ms);
+        if output_tokens == 0 {
+            output_tokens = estimate_tokens(&output_text);
+        }
+
+        Ok(InferenceResponse {
+            text: output_text,
+            input_tokens,
+            output_tokens,
+            reasoning_tokens,
+            time_to_prompt_ms,
+            time_to_first_token_ms: ttft_ms.saturating_sub(time_to_prompt_ms),
+            total_latency_ms,
+            chunk_token_counts,
+            truncated,
+            request_bytes,
+            response_bytes,
+            ..Default::default()
+        })
+    }
+
+    fn default_model(&self) -> &str {
+        &self.model
+    }
+
+    fn endpoint(&self) -> Option<&str> {
+        Some(&self.base_url)
+    }
+
+    fn pricing_for_model(&self, model: &str) -> (f64, f64) {
+        model_pricing("vertex", model).unwrap_or(DEFAULT_PRICING)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::mock_server::{MockResponse, MockServer};
+
+    fn mock_provider(server: &MockServer, credentials: Credentials) -> VertexProvider {
+        VertexProvider {
+            client: http::build_client(5).unwrap(),
+            base_url: server.base_url().to_string(),
+            project: "my-project".to_string(),
+            region: DEFAULT_REGION.to_string(),
+            model: DEFAULT_MODEL.to_string(),
+            credentials,
+            token: Mutex::new(None),
+        }
+    }
+
+    fn request() -> InferenceRequest {
+        InferenceRequest {
+            prompt: "Say hello".to_string(),
+            max_tokens: 64,
+            min_tokens: None,
+            model: None,
+            first_token_timeout_ms: None,
+            stall_timeout_ms: None,
+            idempotency_key: None,
+            stream: true,
+            tools: false,
+            history: Vec::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_streamed_usage_takes_last_totals() {
+        let server = MockServer::start(vec![MockResponse::sse(&[
+            concat!(
+                r#"{"candidates":[{"content":{"parts":[{"text":"Hello"}]}}],"#,
+                r#""usageMetadata":{"promptTokenCount":4,"candidatesTokenCount":1}}"#,
+            ),
+            concat!(
+                r#"{"candidates":[{"content":{"parts":[{"text":" there"}]},"#,
+                r#""finishReason":"STOP"}],"#,
+                r#""usageMetadata":{"promptTokenCount":4,"candidatesTokenCount":2}}"#,
+            ),
+        ])])
+        .await;
+        let provider = mock_provider(&server, Credentials::Static("ya29.token".to_string()));
+
+        let response = provider.infer(&request()).await.unwrap();
+
+        assert_eq!(response.text, "Hello there");
+        assert_eq!((response.input_tokens, response.output_tokens), (4, 2));
+        assert_eq!(response.chunk_token_counts.len(), 2);
+        assert!(!response.truncated);
+
+        let sent = &server.requests()[0];
+        let path = format!(
+            "/v1/projects/my-project/locations/{}/publishers/google/models/{}",
+            DEFAULT_REGION, DEFAULT_MODEL
+        );
+        assert!(sent.starts_with(&format!("POST {}:streamGenerateContent?alt=sse ", path)));
+        assert!(sent.contains("authorization: Bearer ya29.token"));
+        assert!(sent.contains(r#""maxOutputTokens":64"#));
+        assert_eq!(provider.endpoint(), Some(server.base_url()));
+    }
+
+    #[tokio::test]
+    async fn test_max_tokens_finish_marks_truncated() {
+        let server = MockServer::start(vec![MockResponse::sse(&[concat!(
+            r#"{"candidates":[{"content":{"parts":[{"text":"Once upon"}]},"#,
+            r#""finishReason":"MAX_TOKENS"}],"#,
+            r#""usageMetadata":{"promptTokenCount":4,"candidatesTokenCount":64}}"#,
+        )])])
+        .await;
+        let provider = mock_provider(&server, Credentials::Static("token".to_string()));
+
+        let response = provider.infer(&request()).await.unwrap();
+
+        assert_eq!(response.text, "Once upon");
+        assert!(response.truncated);
+    }
+
+    #[tokio::test]
+    async fn test_thought_tokens_are_counted_separately() {
+        let server = MockServer::start(vec![MockResponse::sse(&[concat!(
+            r#"{"candidates":[{"content":{"parts":[{"text":"42"}]},"finishReason":"STOP"}],"#,
+            r#""usageMetadata":{"promptTokenCount":5,"candidatesTokenCount":1,"#,
+            r#""thoughtsTokenCount":120}}"#,
+        )])])
+        .await;
+        let provider = mock_provider(&server, Credentials::Static("token".to_string()));
+
+        let response = provider.infer(&request()).await.unwrap();
+
+        assert_eq!(response.output_tokens, 1);
+        assert_eq!(response.reasoning_tokens, 120);
+    }
+
+    #[tokio::test]
+    async fn test_metadata_token_is_reused_until_near_expiry() {
+        let answer = MockResponse::sse(&[concat!(
+            r#"{"candidates":[{"content":{"parts":[{"text":"Hi"}]}}],"#,
+            r#""usageMetadata":{"promptTokenCount":2,"candidatesTokenCount":1}}"#,
+        )]);
+        let server = MockServer::start(vec![
+            MockResponse::status(200, r#"{"access_token":"fresh","expires_in":3599}"#),
+            answer.clone(),
+            answer,
+        ])
+        .await;
+        let url = format!("{}/token", server.base_url());
+        let provider = mock_provider(&server, Credentials::Metadata(url));
+
+        provider.infer(&request()).await.unwrap();
+        provider.infer(&request()).await.unwrap();
+
+        // One token fetch serves both requests
+        let requests = server.requests();
+        assert_eq!(requests.len(), 3);
+        assert!(requests[0].starts_with("GET /token "));
+        assert!(requests[0].contains("metadata-flavor: Google"));
+        assert!(requests[1].contains("authorization: Bearer fresh"));
+        assert!(requests[2].contains("authorization: Bearer fresh"));
+
+        // A token inside the expiry margin is fetched again
+        let server = MockServer::start(vec![
+            MockResponse::status(200, r#"{"access_token":"short","expires_in":30}"#),
+            MockResponse::status(200, r#"{"access_token":"next","expires_in":3599}"#),
+        ])
+        .await;
+        let url = format!("{}/token", server.base_url());
+        let provider = mock_provider(&server, Credentials::Metadata(url));
+
+        assert_eq!(provider.access_token().await.unwrap(), "short");
+        assert_eq!(provider.access_token().await.unwrap(), "next");
+    }
+}
@@ -0,0 +1,383 @@
+//! Live terminal dashboard for `benchmark --tui`.
+//!
+//! Draws a table of providers with per-provider progress and animated tok/s
+//! bars, plus a pane of iteration errors, fed by the runner's progress
+//! events. Press `q` or Esc to abandon the benchmark. Log output should stay
+//! off (no `--verbose`) while the dashboard owns the terminal.
+
+use crate::benchmark::{BenchmarkResult, BenchmarkRunner, EtaTracker, ProgressEvent};
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Borders, Cell, List, ListItem, Row, Table};
+use ratatui::{DefaultTerminal, Frame};
+use std::io;
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+/// Interval between redraws
+const TICK: Duration = Duration::from_millis(100);
+
+/// Width of the tok/s bar for the fastest provider
+const BAR_WIDTH: usize = 30;
+
+/// Fraction of the remaining distance a bar moves toward its value per tick
+const BAR_EASING: f64 = 0.3;
+
+/// Error lines kept for the log pane
+const MAX_LOG_LINES: usize = 200;
+
+const SPINNER: [&str; 10] = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+
+/// Run the benchmark while showing the dashboard
+///
+/// `providers` lists (id, display name) pairs in benchmark order. Returns
+/// `None` if the user quit before the benchmark finished.
+pub async fn run(
+    runner: BenchmarkRunner<'_>,
+    providers: &[(String, String)],
+) -> io::Result<Option<Vec<BenchmarkResult>>> {
+    let dashboard = Dashboard::new(providers, runner.planned_iterations());
+    let (tx, rx) = mpsc::unbounded_channel();
+    let runner = runner.with_progress(move |event| {
+        // The dashboard may already be gone if the user quit
+        let _ = tx.send(event.clone());
+    });
+
+    let mut terminal = ratatui::init();
+    let outcome = drive(&mut terminal, &runner, rx, dashboard).await;
+    ratatui::restore();
+    outcome
+}
+
+async fn drive(
+    terminal: &mut DefaultTerminal,
+    runner: &BenchmarkRunner<'_>,
+    mut events: mpsc::UnboundedReceiver<ProgressEvent>,
+    mut dashboard: Dashboard,
+) -> io::Result<Option<Vec<BenchmarkResult>>> {
+    let benchmark = runner.run();
+    tokio::pin!(benchmark);
+    let mut tick = tokio::time::interval(TICK);
+
+    let results = loop {
+        tokio::select! {
+            results = &mut benchmark => break results,
+            _ = tick.tick() => {
+                while let Ok(event) = events.try_recv() {
+                    dashboard.apply(event);
+                }
+                dashboard.tick();
+                terminal.draw(|frame| dashboard.render(frame))?;
+                if quit_pressed()? {
+                    return Ok(None);
+                }
+            }
+        }
+    };
+
+    // Let the bars settle on their final values, then wait for a key
+    while let Ok(event) = events.try_recv() {
+        dashboard.apply(event);
+    }
+    dashboard.finished = true;
+    loop {
+        dashboard.tick();
+        terminal.draw(|frame| dashboard.render(frame))?;
+        if event::poll(TICK)?
+            && matches!(event::read()?, Event::Key(k) if k.kind == KeyEventKind::Press)
+        {
+            return Ok(Some(results));
+        }
+    }
+}
+
+/// Whether `q` or Esc was pressed since the last check
+fn quit_pressed() -> io::Result<bool> {
+    while event::poll(Duration::ZERO)? {
+        if let Event::Key(key) = event::read()?
+            && key.kind == KeyEventKind::Press
+            && matches!(key.code, KeyCode::Char('q') | KeyCode::Esc)
+        {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RowState {
+    Waiting,
+    Running,
+    Done,
+    Skipped,
+}
+
+/// Live figures for one provider
+#[derive(Debug)]
+struct ProviderRow {
+    provider: String,
+    display_name: String,
+    state: RowState,
+    iterations: u32,
+    completed: u32,
+    failed: u32,
+    ttft_sum_ms: u64,
+    tokens_per_sec_sum: f64,
+    /// Bar value, eased toward the average each tick
+    shown_tokens_per_sec: f64,
+}
+
+impl ProviderRow {
+    fn avg_tokens_per_sec(&self) -> f64 {
+        if self.completed == 0 {
+            0.0
+        } else {
+            self.tokens_per_sec_sum / self.completed as f64
+        }
+    }
+
+    fn avg_ttft_ms(&self) -> Option<u64> {
+        (self.completed > 0).then(|| self.ttft_sum_ms / self.completed as u64)
+    }
+}
+
+/// Dashboard state built from progress events
+#[derive(Debug)]
+struct Dashboard {
+    rows: Vec<ProviderRow>,
+    log: Vec<String>,
+    eta: EtaTracker,
+    ticks: usize,
+    finished: bool,
+}
+
+impl Dashboard {
+    fn new(providers: &[(String, String)], total_iterations: u32) -> Self {
+        Self {
+            rows: providers
+                .iter()
+                .map(|(provider, display_name)| ProviderRow {
+                    provider: provider.clone(),
+                    display_name: display_name.clone(),
+                    state: RowState::Waiting,
+                    iterations: 0,
+                    completed: 0,
+                    failed: 0,
+                    ttft_sum_ms: 0,
+                    tokens_per_sec_sum: 0.0,
+                    shown_tokens_per_sec: 0.0,
+                })
+                .collect(),
+            log: Vec::new(),
+            eta: EtaTracker::new(total_iterations),
+            ticks: 0,
+            finished: false,
+        }
+    }
+
+    fn row(&mut self, provider: &str) -> Option<&mut ProviderRow> {
+        self.rows.iter_mut().find(|r| r.provider == provider)
+    }
+
+    fn apply(&mut self, event: ProgressEvent) {
+        self.eta.record(&event);
+        match event {
+            ProgressEvent::ProviderStarted {
+                provider,
+                iterations,
+            } => {
+                if let Some(row) = self.row(&provider) {
+                    row.state = RowState::Running;
+                    // Repeated runs (--runs) accumulate
+                    row.iterations += iterations;
+                }
+            }
+            ProgressEvent::IterationComplete {
+                provider,
+                ttft_ms,
+                tokens_per_sec,
+                ..
+            } => {
+                if let Some(row) = self.row(&provider) {
+                    row.completed += 1;
+                    row.ttft_sum_ms += ttft_ms;
+                    row.tokens_per_sec_sum += tokens_per_sec;
+                }
+            }
+            ProgressEvent::IterationFailed {
+                provider,
+                iteration,
+                error,
+            } => {
+                if let Some(row) = self.row(&provider) {
+                    row.failed += 1;
+                }
+                self.push_log(format!("{} #{}: {}", provider, iteration + 1, error));
+            }
+            ProgressEvent::ProviderFinished { provider, skipped } => {
+                if let Some(row) = self.row(&provider) {
+                    row.state = if skipped.is_some() && row.completed == 0 {
+                        RowState::Skipped
+                    } else {
+                        RowState::Done
+                    };
+                }
+                if let Some(reason) = skipped {
+                    self.push_log(format!("{}: {}", provider, reason));
+                }
+            }
+        }
+    }
+
+    fn push_log(&mut self, line: String) {
+        self.log.push(line);
+        if self.log.len() > MAX_LOG_LINES {
+            self.log.remove(0);
+        }
+    }
+
+    /// Advance the spinner and ease bars toward their averages
+    fn tick(&mut self) {
+        self.ticks += 1;
+        for row in &mut self.rows {
+            let target = row.avg_tokens_per_sec();
+            row.shown_tokens_per_sec += (target - row.shown_tokens_per_sec) * BAR_EASING;
+            if (target - row.shown_tokens_per_sec).abs() < 0.5 {
+                row.shown_tokens_per_sec = target;
+            }
+        }
+    }
+
+    fn render(&self, frame: &mut Frame) {
+        let log_height = (self.log.len() as u16).clamp(1, 8) + 2;
+        let [table_area, log_area] =
+            Layout::vertical([Constraint::Min(5), Constraint::Length(log_height)])
+                .areas(frame.area());
+
+        let fastest = self
+            .rows
+            .iter()
+            .map(ProviderRow::avg_tokens_per_sec)
+            .fold(0.0, f64::max);
+
+        let header = Row::new(["", "Provider", "Progress", "TTFT", "Tok/sec", ""])
+            .style(Style::default().add_modifier(Modifier::BOLD));
+        let rows = self.rows.iter().map(|row| {
+            let status = match row.state {
+                RowState::Waiting => " ",
+                RowState::Running => SPINNER[self.ticks % SPINNER.len()],
+                RowState::Done => "✓",
+                RowState::Skipped => "✗",
+            };
+            let mut progress = format!("{}/{}", row.completed + row.failed, row.iterations);
+            if row.failed > 0 {
+                progress.push_str(&format!(" ({} failed)", row.failed));
+            }
+            let bar_len = if fastest > 0.0 {
+                (row.shown_tokens_per_sec / fastest * BAR_WIDTH as f64).round() as usize
+            } else {
+                0
+            };
+            Row::new([
+                Cell::from(status),
+                Cell::from(row.display_name.as_str()),
+                Cell::from(progress).style(if row.failed > 0 {
+                    Style::default().fg(Color::Yellow)
+                } else {
+                    Style::default()
+                }),
+                Cell::from(
+                    row.avg_ttft_ms()
+                        .map_or("-".to_string(), |ms| format!("{}ms", ms)),
+                ),
+                Cell::from(format!("{:.0}", row.avg_tokens_per_sec())),
+                Cell::from("█".repeat(bar_len.min(BAR_WIDTH)))
+                    .style(Style::default().fg(Color::Green)),
+            ])
+        });
+
+        let title = if self.finished {
+            " speed-kings - finished, press any key for results ".to_string()
+        } else {
+            format!(" speed-kings - {} - q to quit ", self.eta)
+        };
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(1),
+                Constraint::Length(20),
+                Constraint::Length(18),
+                Constraint::Length(8),
+                Constraint::Length(8),
+                Constraint::Length(BAR_WIDTH as u16),
+            ],
+        )
+        .header(header)
+        .block(Block::default().borders(Borders::ALL).title(title));
+        frame.render_widget(table, table_area);
+
+        let visible = log_area.height.saturating_sub(2) as usize;
+        let items: Vec<ListItem> = self.log[self.log.len().saturating_sub(visible)..]
+            .iter()
+            .map(|line| {
+                ListItem::new(Line::from(line.as_str())).style(Style::default().fg(Color::Red))
+            })
+            .collect();
+        let log = List::new(items).block(Block::default().borders(Borders::ALL).title(" Errors "));
+        frame.render_widget(log, log_area);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dashboard_tracks_progress_and_errors() {
+        let mut dashboard = Dashboard::new(
+            &[
+                ("groq".to_string(), "Groq".to_string()),
+                ("local".to_string(), "Local".to_string()),
+            ],
+            6,
+        );
+        dashboard.apply(ProgressEvent::ProviderStarted {
+            provider: "groq".to_string(),
+            iterations: 3,
+        });
+        for tokens_per_sec in [300.0, 500.0] {
+            dashboard.apply(ProgressEvent::IterationComplete {
+                provider: "groq".to_string(),
+                iteration: 0,
+                ttft_ms: 100,
+                tokens_per_sec,
+            });
+        }
+        dashboard.apply(ProgressEvent::IterationFailed {
+            provider: "groq".to_string(),
+            iteration: 2,
+            error: "Rate limited".to_string(),
+        });
+        dashboard.apply(ProgressEvent::ProviderFinished {
+            provider: "local".to_string(),
+            skipped: Some("Provider not available".to_string()),
+        });
+
+        let groq = &dashboard.rows[0];
+        assert_eq!((groq.completed, groq.failed, groq.iterations), (2, 1, 3));
+        assert_eq!(groq.avg_tokens_per_sec(), 400.0);
+        assert_eq!(dashboard.rows[1].state, RowState::Skipped);
+        assert_eq!(dashboard.eta.percent(), 50.0);
+        assert_eq!(
+            dashboard.log,
+            ["groq #3: Rate limited", "local: Provider not available"]
+        );
+
+        // Bars ease toward the average rather than jumping
+        dashboard.tick();
+        let shown = dashboard.rows[0].shown_tokens_per_sec;
+        assert!(shown > 0.0 && shown < 400.0);
+    }
+}